@@ -3,5 +3,9 @@ use taffy::TaffyError;
 #[derive(Debug)]
 pub enum LayoutError {
     InvalidStyleName(String),
+    ///The parent of an element doesn't exist
+    InvalidParent,
+    ///The element doesn't exist
+    InvalidElement,
     Taffy(TaffyError),
 }
@@ -2,17 +2,29 @@ pub mod error;
 use ahash::RandomState;
 use error::LayoutError;
 use smol_str::SmolStr;
-use taffy::{Dimension, Layout, NodeId, Style, TaffyError, TaffyTree};
+use taffy::{
+    AvailableSpace, Dimension, Layout, NodeId, Overflow, Point, Size, Style, TaffyError, TaffyTree,
+};
+
+use crate::ui::HystElementKey;
 pub type StyleMap = hashbrown::HashMap<SmolStr, Style, RandomState>;
 
 ///Struct responsible for managing the layouts of the Hyst engine. Not that this only manages
-///how elements will be positioned
+///how elements will be positioned.
+///Nodes whose size depends on their content (such as texts) carry the key of the element they belong to, so it can be asked
+///to measure itself while the layout is computed.
 pub struct HystLayout {
-    taffy: TaffyTree<()>,
+    taffy: TaffyTree<HystElementKey>,
     styles: StyleMap,
     root: NodeId,
 }
 
+impl Default for HystLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl HystLayout {
     pub fn new() -> Self {
         let mut taffy = TaffyTree::new();
@@ -43,6 +55,32 @@ impl HystLayout {
         self.taffy.layout(node).map_err(LayoutError::Taffy)
     }
 
+    ///Gets the overflow of the given `node` on each axis. Axis whose overflow isn't visible clip the children of the node.
+    pub fn overflow_of(&self, node: NodeId) -> Result<Point<Overflow>, LayoutError> {
+        self.taffy
+            .style(node)
+            .map(|style| style.overflow)
+            .map_err(LayoutError::Taffy)
+    }
+
+    pub fn style_of(&self, node: NodeId) -> Result<&Style, LayoutError> {
+        self.taffy.style(node).map_err(LayoutError::Taffy)
+    }
+
+    ///Replaces the style of the given `node`, so it's recalculated with the new one.
+    pub fn set_style(&mut self, node: NodeId, style: Style) -> Result<(), LayoutError> {
+        self.taffy
+            .set_style(node, style)
+            .map_err(LayoutError::Taffy)
+    }
+
+    ///Replaces the children of the given `node`. Nodes which are no longer children aren't removed, so they can be added back later.
+    pub fn set_children(&mut self, node: NodeId, children: &[NodeId]) -> Result<(), LayoutError> {
+        self.taffy
+            .set_children(node, children)
+            .map_err(LayoutError::Taffy)
+    }
+
     pub fn root(&self) -> NodeId {
         self.root
     }
@@ -77,14 +115,61 @@ impl HystLayout {
         }
     }
 
+    ///Gets the style which was created with the given `name`
+    pub fn named_style(&self, name: &str) -> Option<&Style> {
+        self.styles.get(name)
+    }
+
+    ///Creates a node with the given `style` as child of `parent`, without registering the style. Used for nodes which aren't
+    ///created by the user, such as the content of scrollables. If `parent` is None, the node is child of the root.
+    pub fn create_node(
+        &mut self,
+        parent: Option<NodeId>,
+        style: Style,
+    ) -> Result<NodeId, LayoutError> {
+        let id = self.taffy.new_leaf(style).map_err(LayoutError::Taffy)?;
+        self.taffy
+            .add_child(parent.unwrap_or(self.root), id)
+            .map_err(LayoutError::Taffy)?;
+        Ok(id)
+    }
+
+    ///Marks the given `node` as measured by the element with the given `key`. When recalculating, the element will be asked
+    ///for it's intrinsic size instead of being considered empty.
+    pub fn set_measured(&mut self, node: NodeId, key: HystElementKey) -> Result<(), LayoutError> {
+        self.taffy
+            .set_node_context(node, Some(key))
+            .map_err(LayoutError::Taffy)
+    }
+
+    ///Marks the given `node` as dirty, so it's size is measured again on the next recalculation.
+    pub fn mark_dirty(&mut self, node: NodeId) -> Result<(), LayoutError> {
+        self.taffy.mark_dirty(node).map_err(LayoutError::Taffy)
+    }
+
     ///Recalculates the tree based on the given `width` and `height`.
-    pub fn recalculate(&mut self, width: f32, height: f32) -> Result<(), TaffyError> {
-        self.taffy.compute_layout(
+    /// # Arguments
+    /// * `measure` Function called for every measured node with the known dimensions, the available space and the key of the element
+    ///   that owns the node. Must return the size the element requires.
+    pub fn recalculate<F>(
+        &mut self,
+        width: f32,
+        height: f32,
+        mut measure: F,
+    ) -> Result<(), TaffyError>
+    where
+        F: FnMut(Size<Option<f32>>, Size<AvailableSpace>, HystElementKey) -> Size<f32>,
+    {
+        self.taffy.compute_layout_with_measure(
             self.root,
             taffy::Size {
                 width: taffy::AvailableSpace::Definite(width),
                 height: taffy::AvailableSpace::Definite(height),
             },
+            |known, available, _, key, _| match key {
+                Some(key) => measure(known, available, *key),
+                None => Size::ZERO,
+            },
         )
     }
 }
@@ -1,8 +1,507 @@
-use wgpu::{ShaderStages, Buffer, TextureView, TextureViewDimension, Sampler, TextureSampleType, SamplerBindingType};
-
-///Used to define bind group and layout configs
-pub enum BindGroupAndLayoutConfig<'a> {
-    Uniform(ShaderStages, &'a Buffer),
-    Texutre(TextureViewDimension, TextureSampleType, &'a TextureView),
-    Sampler(SamplerBindingType, &'a Sampler),
-}
\ No newline at end of file
+use std::num::NonZeroU64;
+
+use wgpu::{
+    BindGroupEntry, BindGroupLayoutEntry, Buffer, Sampler, SamplerBindingType, ShaderStages,
+    StorageTextureAccess, TextureFormat, TextureSampleType, TextureView, TextureViewDimension,
+};
+
+///Resource bound on an entry of a bind group
+#[derive(Debug, Clone)]
+pub enum BindingKind<'a> {
+    ///Buffer read as a uniform. With a dynamic offset, only `size` bytes of it are bound, starting at the offset given when drawing
+    Uniform {
+        buffer: &'a Buffer,
+        dynamic_offset: bool,
+        size: Option<NonZeroU64>,
+    },
+    ///Buffer the shader reads, and writes unless it's read only. With a dynamic offset, only `size` bytes of it are bound, starting
+    ///at the offset given when drawing
+    Storage {
+        buffer: &'a Buffer,
+        read_only: bool,
+        dynamic_offset: bool,
+        size: Option<NonZeroU64>,
+    },
+    Texture {
+        view: &'a TextureView,
+        dimension: TextureViewDimension,
+        sample_type: TextureSampleType,
+    },
+    ///Texture the shader accesses texel by texel, without a sampler
+    StorageTexture {
+        view: &'a TextureView,
+        dimension: TextureViewDimension,
+        format: TextureFormat,
+        access: StorageTextureAccess,
+    },
+    Sampler {
+        sampler: &'a Sampler,
+        ty: SamplerBindingType,
+    },
+}
+
+///Entry of a bind group, bound on the given `binding` and visible on the given shader stages
+#[derive(Debug, Clone)]
+pub struct BindingEntry<'a> {
+    pub binding: u32,
+    pub visibility: ShaderStages,
+    pub kind: BindingKind<'a>,
+}
+
+impl BindingEntry<'_> {
+    pub fn layout_entry(&self) -> BindGroupLayoutEntry {
+        let ty = match self.kind {
+            BindingKind::Uniform {
+                dynamic_offset,
+                size,
+                ..
+            } => wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: dynamic_offset,
+                min_binding_size: size,
+            },
+            BindingKind::Storage {
+                read_only,
+                dynamic_offset,
+                size,
+                ..
+            } => wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: dynamic_offset,
+                min_binding_size: size,
+            },
+            BindingKind::Texture {
+                dimension,
+                sample_type,
+                ..
+            } => wgpu::BindingType::Texture {
+                sample_type,
+                view_dimension: dimension,
+                multisampled: false,
+            },
+            BindingKind::StorageTexture {
+                dimension,
+                format,
+                access,
+                ..
+            } => wgpu::BindingType::StorageTexture {
+                access,
+                format,
+                view_dimension: dimension,
+            },
+            BindingKind::Sampler { ty, .. } => wgpu::BindingType::Sampler(ty),
+        };
+        BindGroupLayoutEntry {
+            binding: self.binding,
+            visibility: self.visibility,
+            ty,
+            count: None,
+        }
+    }
+
+    pub fn group_entry(&self) -> BindGroupEntry<'_> {
+        let resource = match self.kind {
+            BindingKind::Uniform { buffer, size, .. }
+            | BindingKind::Storage { buffer, size, .. } => {
+                wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer,
+                    offset: 0,
+                    size,
+                })
+            }
+            BindingKind::Texture { view, .. } | BindingKind::StorageTexture { view, .. } => {
+                wgpu::BindingResource::TextureView(view)
+            }
+            BindingKind::Sampler { sampler, .. } => wgpu::BindingResource::Sampler(sampler),
+        };
+        BindGroupEntry {
+            binding: self.binding,
+            resource,
+        }
+    }
+}
+
+///Used to define the entries of a bind group and of it's layout. Each entry is bound on the binding it's given, so they can be
+///added in any order
+#[derive(Debug, Clone, Default)]
+pub struct BindGroupConfig<'a> {
+    entries: Vec<BindingEntry<'a>>,
+}
+
+impl<'a> BindGroupConfig<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[BindingEntry<'a>] {
+        &self.entries
+    }
+
+    ///Adds the given `entry`
+    pub fn entry(mut self, entry: BindingEntry<'a>) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    fn with(self, binding: u32, visibility: ShaderStages, kind: BindingKind<'a>) -> Self {
+        self.entry(BindingEntry {
+            binding,
+            visibility,
+            kind,
+        })
+    }
+
+    ///Binds the whole given `buffer` as a uniform
+    pub fn uniform(self, binding: u32, visibility: ShaderStages, buffer: &'a Buffer) -> Self {
+        self.with(
+            binding,
+            visibility,
+            BindingKind::Uniform {
+                buffer,
+                dynamic_offset: false,
+                size: None,
+            },
+        )
+    }
+
+    ///Binds `size` bytes of the given `buffer` as a uniform, starting at the offset given when drawing. Useful for buffers holding
+    ///the uniforms of several draws, each of them aligned to the min uniform buffer offset alignment of the device
+    pub fn dynamic_uniform(
+        self,
+        binding: u32,
+        visibility: ShaderStages,
+        buffer: &'a Buffer,
+        size: NonZeroU64,
+    ) -> Self {
+        self.with(
+            binding,
+            visibility,
+            BindingKind::Uniform {
+                buffer,
+                dynamic_offset: true,
+                size: Some(size),
+            },
+        )
+    }
+
+    ///Binds the whole given `buffer` as a storage buffer, which the shader writes unless it's `read_only`
+    pub fn storage(
+        self,
+        binding: u32,
+        visibility: ShaderStages,
+        buffer: &'a Buffer,
+        read_only: bool,
+    ) -> Self {
+        self.with(
+            binding,
+            visibility,
+            BindingKind::Storage {
+                buffer,
+                read_only,
+                dynamic_offset: false,
+                size: None,
+            },
+        )
+    }
+
+    ///Binds `size` bytes of the given `buffer` as a storage buffer, starting at the offset given when drawing
+    pub fn dynamic_storage(
+        self,
+        binding: u32,
+        visibility: ShaderStages,
+        buffer: &'a Buffer,
+        read_only: bool,
+        size: NonZeroU64,
+    ) -> Self {
+        self.with(
+            binding,
+            visibility,
+            BindingKind::Storage {
+                buffer,
+                read_only,
+                dynamic_offset: true,
+                size: Some(size),
+            },
+        )
+    }
+
+    pub fn texture(
+        self,
+        binding: u32,
+        visibility: ShaderStages,
+        view: &'a TextureView,
+        dimension: TextureViewDimension,
+        sample_type: TextureSampleType,
+    ) -> Self {
+        self.with(
+            binding,
+            visibility,
+            BindingKind::Texture {
+                view,
+                dimension,
+                sample_type,
+            },
+        )
+    }
+
+    ///Binds the given `view` as a storage texture with the given `format`, which must be the one of the texture
+    pub fn storage_texture(
+        self,
+        binding: u32,
+        visibility: ShaderStages,
+        view: &'a TextureView,
+        format: TextureFormat,
+        access: StorageTextureAccess,
+    ) -> Self {
+        self.with(
+            binding,
+            visibility,
+            BindingKind::StorageTexture {
+                view,
+                dimension: TextureViewDimension::D2,
+                format,
+                access,
+            },
+        )
+    }
+
+    pub fn sampler(
+        self,
+        binding: u32,
+        visibility: ShaderStages,
+        sampler: &'a Sampler,
+        ty: SamplerBindingType,
+    ) -> Self {
+        self.with(binding, visibility, BindingKind::Sampler { sampler, ty })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wgpu::{BindingType, BufferBindingType, Device};
+
+    use super::*;
+
+    fn device() -> Device {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::NOOP,
+            backend_options: wgpu::BackendOptions {
+                noop: wgpu::NoopBackendOptions { enable: true },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let adapter = pollster::block_on(instance.request_adapter(&Default::default())).unwrap();
+        pollster::block_on(adapter.request_device(&Default::default()))
+            .unwrap()
+            .0
+    }
+
+    fn buffer(device: &Device, usage: wgpu::BufferUsages) -> Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 256,
+            usage,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn texture(device: &Device, format: TextureFormat, usage: wgpu::TextureUsages) -> TextureView {
+        device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: 4,
+                    height: 4,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            })
+            .create_view(&Default::default())
+    }
+
+    #[test]
+    fn buffers_are_bound_by_their_kind() {
+        let device = device();
+        let uniform = buffer(&device, wgpu::BufferUsages::UNIFORM);
+        let storage = buffer(&device, wgpu::BufferUsages::STORAGE);
+        let size = NonZeroU64::new(64).unwrap();
+        let config = BindGroupConfig::new()
+            .uniform(0, ShaderStages::VERTEX, &uniform)
+            .dynamic_uniform(1, ShaderStages::FRAGMENT, &uniform, size)
+            .storage(2, ShaderStages::VERTEX_FRAGMENT, &storage, true)
+            .dynamic_storage(3, ShaderStages::COMPUTE, &storage, false, size);
+        let layouts: Vec<_> = config
+            .entries()
+            .iter()
+            .map(BindingEntry::layout_entry)
+            .collect();
+        let expected = [
+            (
+                ShaderStages::VERTEX,
+                BufferBindingType::Uniform,
+                false,
+                None,
+            ),
+            (
+                ShaderStages::FRAGMENT,
+                BufferBindingType::Uniform,
+                true,
+                Some(size),
+            ),
+            (
+                ShaderStages::VERTEX_FRAGMENT,
+                BufferBindingType::Storage { read_only: true },
+                false,
+                None,
+            ),
+            (
+                ShaderStages::COMPUTE,
+                BufferBindingType::Storage { read_only: false },
+                true,
+                Some(size),
+            ),
+        ];
+        for (idx, (layout, (visibility, ty, dynamic, min_size))) in
+            layouts.iter().zip(expected).enumerate()
+        {
+            assert_eq!(layout.binding, idx as u32);
+            assert_eq!(layout.visibility, visibility);
+            assert_eq!(layout.count, None);
+            assert_eq!(
+                layout.ty,
+                BindingType::Buffer {
+                    ty,
+                    has_dynamic_offset: dynamic,
+                    min_binding_size: min_size,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn textures_and_samplers_keep_their_types() {
+        let device = device();
+        let sampled = texture(
+            &device,
+            TextureFormat::Rgba8Unorm,
+            wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        let storage = texture(
+            &device,
+            TextureFormat::Rgba8Unorm,
+            wgpu::TextureUsages::STORAGE_BINDING,
+        );
+        let sampler = device.create_sampler(&Default::default());
+        let sample_type = TextureSampleType::Float { filterable: true };
+        let config = BindGroupConfig::new()
+            .texture(
+                0,
+                ShaderStages::FRAGMENT,
+                &sampled,
+                TextureViewDimension::D2,
+                sample_type,
+            )
+            .storage_texture(
+                1,
+                ShaderStages::COMPUTE,
+                &storage,
+                TextureFormat::Rgba8Unorm,
+                StorageTextureAccess::WriteOnly,
+            )
+            .sampler(
+                2,
+                ShaderStages::FRAGMENT,
+                &sampler,
+                SamplerBindingType::Filtering,
+            );
+        let layouts: Vec<_> = config
+            .entries()
+            .iter()
+            .map(BindingEntry::layout_entry)
+            .collect();
+        assert_eq!(
+            layouts[0].ty,
+            BindingType::Texture {
+                sample_type,
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            }
+        );
+        //Storage textures are always two dimensional
+        assert_eq!(
+            layouts[1].ty,
+            BindingType::StorageTexture {
+                access: StorageTextureAccess::WriteOnly,
+                format: TextureFormat::Rgba8Unorm,
+                view_dimension: TextureViewDimension::D2,
+            }
+        );
+        assert_eq!(
+            layouts[2].ty,
+            BindingType::Sampler(SamplerBindingType::Filtering)
+        );
+        assert_eq!(layouts[1].visibility, ShaderStages::COMPUTE);
+    }
+
+    #[test]
+    fn entries_keep_their_bindings_in_any_order() {
+        let device = device();
+        let uniform = buffer(&device, wgpu::BufferUsages::UNIFORM);
+        let sampler = device.create_sampler(&Default::default());
+        let config = BindGroupConfig::new()
+            .sampler(
+                3,
+                ShaderStages::FRAGMENT,
+                &sampler,
+                SamplerBindingType::Filtering,
+            )
+            .uniform(1, ShaderStages::VERTEX, &uniform);
+        let bindings: Vec<_> = config
+            .entries()
+            .iter()
+            .map(|entry| (entry.layout_entry().binding, entry.group_entry().binding))
+            .collect();
+        assert_eq!(bindings, vec![(3, 3), (1, 1)]);
+        //The layout and the group built from the same entries match, so the device accepts them
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &config
+                .entries()
+                .iter()
+                .map(BindingEntry::layout_entry)
+                .collect::<Vec<_>>(),
+        });
+        let _group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &layout,
+            entries: &config
+                .entries()
+                .iter()
+                .map(BindingEntry::group_entry)
+                .collect::<Vec<_>>(),
+        });
+    }
+
+    #[test]
+    fn dynamic_buffers_bind_only_their_size() {
+        let device = device();
+        let uniform = buffer(&device, wgpu::BufferUsages::UNIFORM);
+        let size = NonZeroU64::new(32).unwrap();
+        let config =
+            BindGroupConfig::new().dynamic_uniform(0, ShaderStages::VERTEX, &uniform, size);
+        match config.entries()[0].group_entry().resource {
+            wgpu::BindingResource::Buffer(binding) => {
+                assert_eq!(binding.offset, 0);
+                assert_eq!(binding.size, Some(size));
+            }
+            _ => panic!("Uniforms are bound as buffers"),
+        }
+    }
+}
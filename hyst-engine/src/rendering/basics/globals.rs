@@ -0,0 +1,99 @@
+use std::time::Instant;
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::{BindGroup, BindGroupLayout, Buffer, Device, Queue};
+
+use super::{BindGroupConfig, BindingEntry};
+
+///Uniforms shared by every pipeline, bound on group 0. In wgsl they're
+///`struct Globals { screen_size: vec2<f32>, cursor: vec2<f32>, scale_factor: f32, time: f32, frame: u32 }`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
+pub struct GlobalUniforms {
+    ///Size of the viewport in pixels
+    pub screen_size: [f32; 2],
+    ///Last position of the cursor in pixels, from the top left corner of the viewport
+    pub cursor: [f32; 2],
+    ///Amount of pixels of each logical pixel of the window
+    pub scale_factor: f32,
+    ///Seconds since the core was created, for animating effects
+    pub time: f32,
+    ///Index of the frame being drawn, starting at 0
+    pub frame: u32,
+    _padding: u32,
+}
+
+///Buffer and bind group of the global uniforms. They're written once per frame, before it's drawn
+#[derive(Debug)]
+pub(crate) struct Globals {
+    uniforms: GlobalUniforms,
+    buffer: Buffer,
+    layout: BindGroupLayout,
+    group: BindGroup,
+    start: Instant,
+}
+
+impl Globals {
+    pub fn new(device: &Device, screen_size: (u32, u32), scale_factor: f32) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("globals"),
+            size: std::mem::size_of::<GlobalUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let config =
+            BindGroupConfig::new().uniform(0, wgpu::ShaderStages::VERTEX_FRAGMENT, &buffer);
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("globals"),
+            entries: &config
+                .entries()
+                .iter()
+                .map(BindingEntry::layout_entry)
+                .collect::<Vec<_>>(),
+        });
+        let group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("globals"),
+            layout: &layout,
+            entries: &config
+                .entries()
+                .iter()
+                .map(BindingEntry::group_entry)
+                .collect::<Vec<_>>(),
+        });
+        Self {
+            uniforms: GlobalUniforms {
+                screen_size: [screen_size.0 as f32, screen_size.1 as f32],
+                scale_factor,
+                ..Default::default()
+            },
+            buffer,
+            layout,
+            group,
+            start: Instant::now(),
+        }
+    }
+
+    #[inline]
+    pub fn uniforms(&self) -> &GlobalUniforms {
+        &self.uniforms
+    }
+    #[inline]
+    pub fn uniforms_mut(&mut self) -> &mut GlobalUniforms {
+        &mut self.uniforms
+    }
+    #[inline]
+    pub fn layout(&self) -> &BindGroupLayout {
+        &self.layout
+    }
+    #[inline]
+    pub fn group(&self) -> &BindGroup {
+        &self.group
+    }
+
+    ///Writes the uniforms of the frame about to be drawn, and counts it
+    pub fn write(&mut self, queue: &Queue) {
+        self.uniforms.time = self.start.elapsed().as_secs_f32();
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&self.uniforms));
+        self.uniforms.frame = self.uniforms.frame.wrapping_add(1);
+    }
+}
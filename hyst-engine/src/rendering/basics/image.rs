@@ -1,6 +1,6 @@
 use wgpu::{Sampler, Texture, TextureView};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GpuImage {
     view: TextureView,
     sampler: Sampler,
@@ -21,4 +21,11 @@ impl GpuImage {
     pub fn sampler(&self) -> &Sampler {
         &self.sampler
     }
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+    ///Replaces the sampler the image is read with. Bind groups using the previous one must be created again
+    pub fn set_sampler(&mut self, sampler: Sampler) {
+        self.sampler = sampler;
+    }
 }
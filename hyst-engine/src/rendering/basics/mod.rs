@@ -1,5 +1,11 @@
 mod bind_group;
 pub use bind_group::*;
 
+mod globals;
+pub use globals::*;
+
 mod image;
-pub use image::*;
\ No newline at end of file
+pub use image::*;
+
+mod mipmaps;
+pub(crate) use mipmaps::*;
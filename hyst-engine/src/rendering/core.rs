@@ -6,22 +6,21 @@ use std::{
 
 use crate::rendering::basics::*;
 use bytemuck::{Pod, Zeroable};
-use hyst_math::vectors::{Rgba, Vec2f32, Vec4f32};
+use hyst_math::vectors::Rgba;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use wgpu::{
     Adapter, BackendOptions, Backends, BindGroup, BindGroupEntry, BindGroupLayout,
     BindGroupLayoutEntry, Buffer, BufferDescriptor, BufferUsages, Device, Instance, InstanceFlags,
-    Origin3d, Queue, RenderPipeline, ShaderModule, ShaderStages, Surface, SurfaceConfiguration,
+    Origin3d, Queue, RenderPipeline, ShaderModule, Surface, SurfaceConfiguration,
     TextureDescriptor, TextureViewDescriptor, VertexBufferLayout,
 };
 use winit::window::Window;
 
-use crate::shaders::{HystConstructor, ShaderCreationOptions};
+use crate::shaders::{HystConstructor, STENCIL_FORMAT, ShaderCreationOptions, StencilMode};
 
 use super::{
-    elements::{HystElement, HystText},
-    meshes::text::Text,
-    text::TextManager,
+    effects::{EffectRenderer, Effects, Frame, TARGET_FORMAT, Target},
+    text::{PreparedText, TextManager},
 };
 
 pub struct RenderingCore {
@@ -34,6 +33,16 @@ pub struct RenderingCore {
     pipelines: HashMap<&'static str, Arc<RenderPipeline>>,
     shaders: HashMap<&'static str, Arc<ShaderModule>>,
     text_renderer: TextManager,
+    ///Stencil buffer of the surface, used for clipping elements to rounded rects
+    stencil: wgpu::TextureView,
+    ///Pipelines used for applying effects
+    effects: EffectRenderer,
+    ///Offscreen targets the frames are drawn on, kept between frames
+    targets: Vec<Target>,
+    ///Fills the mip chains of the images when they're created
+    mipmaps: MipmapGenerator,
+    ///Uniforms shared by every pipeline on group 0, written once per frame
+    globals: Globals,
 }
 
 impl RenderingCore {
@@ -60,27 +69,55 @@ impl RenderingCore {
             compatible_surface: Some(&surface),
         }))
         .unwrap();
+        //Storage buffers, storage textures and push constants are enabled as far as the adapter supports them, for custom shaders
+        let supported = adapter.limits();
+        let push_constants = adapter.features() & wgpu::Features::PUSH_CONSTANTS;
         let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
             label: None,
-            required_features: wgpu::Features::POLYGON_MODE_LINE,
+            required_features: wgpu::Features::POLYGON_MODE_LINE | push_constants,
             // Make sure we use the texture resolution limits from the adapter, so we can support images the size of the swapchain.
-            required_limits:
-                wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits()),
+            required_limits: wgpu::Limits {
+                max_storage_buffers_per_shader_stage:
+                    supported.max_storage_buffers_per_shader_stage,
+                max_storage_textures_per_shader_stage:
+                    supported.max_storage_textures_per_shader_stage,
+                max_storage_buffer_binding_size: supported.max_storage_buffer_binding_size,
+                max_dynamic_storage_buffers_per_pipeline_layout:
+                    supported.max_dynamic_storage_buffers_per_pipeline_layout,
+                max_push_constant_size: match push_constants.is_empty() {
+                    true => 0,
+                    false => supported.max_push_constant_size,
+                },
+                ..wgpu::Limits::downlevel_webgl2_defaults().using_resolution(supported.clone())
+            },
             memory_hints: wgpu::MemoryHints::MemoryUsage,
             trace: wgpu::Trace::Off,
         }))
         .unwrap();
         let size = window.inner_size();
-        let config = surface
+        let mut config = surface
             .get_default_config(&adapter, size.width, size.height)
             .unwrap();
+        //Frames without effects are drawn directly on the surface, which requires it to have the format of the pipelines.
+        //Backdrops copy what's drawn behind them
+        let capabilities = surface.get_capabilities(&adapter);
+        if capabilities.formats.contains(&TARGET_FORMAT) {
+            config.format = TARGET_FORMAT;
+        }
+        config.usage |= capabilities.usages & wgpu::TextureUsages::COPY_SRC;
         surface.configure(&device, &config);
+        let stencil = Self::create_stencil(&device, config.width, config.height);
         Self {
-            text_renderer: TextManager::new(
+            stencil,
+            //Texts are drawn with the format of the targets, which the surface has too when frames are drawn directly on it
+            text_renderer: TextManager::new(&device, &queue, TARGET_FORMAT),
+            effects: EffectRenderer::new(&device, config.format),
+            targets: Vec::new(),
+            mipmaps: MipmapGenerator::new(&device),
+            globals: Globals::new(
                 &device,
-                &queue,
-                config.format,
-                (size.width as f32, size.height as f32),
+                (config.width, config.height),
+                window.scale_factor() as f32,
             ),
             instance,
             surface,
@@ -111,23 +148,29 @@ impl RenderingCore {
 
     ///Creates the given Shader type based on the given `options`.
     ///If some of it was already created, they will share the same pipeline and modules via Arc, but their bindgroups not.
+    ///The bind group of the global uniforms is the first of the shader, so the ones of the options start at group 1
     pub fn create_shader<S>(&mut self, options: ShaderCreationOptions) -> S
     where
         S: HystConstructor + Sized,
     {
-        let (bind_groups, layouts) =
+        let (mut bind_groups, mut layouts) =
             self.create_bind_groups_and_layouts(options.bind_group_configs, Some(&options.name));
+        bind_groups.insert(0, self.globals.group().clone());
+        layouts.insert(0, self.globals.layout().clone());
         if let Some(module) = self.shaders.get(S::name()) {
             let pipeline = self.pipelines.get(S::name()).unwrap();
             S::new(module.clone(), bind_groups, layouts, pipeline.clone())
         } else {
-            let module = Arc::new(self.create_module(&options.source, Some(&options.name)));
+            let module = Arc::new(self.create_module(options.source, Some(&options.name)));
             let pipeline = self.create_default_pipeline(
                 &module,
                 Some(&options.name),
                 options.rendering_style.get_primitive_state(),
-                &layouts.iter().map(|l| l).collect::<Vec<&_>>(),
+                &layouts.iter().collect::<Vec<&_>>(),
+                &options.push_constants,
                 S::shader_inputs(),
+                S::stencil(),
+                S::blend(),
             );
             let pipeline = Arc::new(pipeline);
             self.pipelines.insert(S::name(), pipeline.clone());
@@ -141,18 +184,65 @@ impl RenderingCore {
         (self.config.width, self.config.height)
     }
 
+    #[inline]
+    ///Gets the global uniforms the next frame is drawn with
+    pub fn globals(&self) -> &GlobalUniforms {
+        self.globals.uniforms()
+    }
+
+    #[inline]
+    ///Gets the layout of the bind group of the global uniforms, for pipelines created without create_shader
+    pub fn globals_layout(&self) -> &BindGroupLayout {
+        self.globals.layout()
+    }
+
+    #[inline]
+    ///Gets the bind group of the global uniforms, which must be bound on group 0
+    pub fn globals_bind_group(&self) -> &BindGroup {
+        self.globals.group()
+    }
+
+    ///Sets the position of the cursor in pixels, given to the shaders on the next frame
+    pub fn set_cursor(&mut self, x: f32, y: f32) {
+        self.globals.uniforms_mut().cursor = [x, y];
+    }
+
+    ///Sets the amount of pixels of each logical pixel of the window, given to the shaders on the next frame
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.globals.uniforms_mut().scale_factor = scale_factor;
+    }
+
+    #[inline]
+    ///Gets the biggest amount of bytes of push constants a shader can have. It's 0 when the device doesn't support them
+    pub fn max_push_constant_size(&self) -> u32 {
+        self.device.limits().max_push_constant_size
+    }
+
+    #[inline]
+    ///Gets the biggest width or height a texture can have on the device
+    pub fn max_texture_size(&self) -> u32 {
+        self.device.limits().max_texture_dimension_2d
+    }
+
     ///Used to create a default pipeline for the given shader module. It has default configs, but may not be used for every shader
     /// Default configs are:
     /// Entry points: vertex_main and fragment_main
+    /// Push constants are defined by the given 'push_constants' ranges
     /// Shader input is defined by the given 'inputs' parameter
     /// The way is going to draw is defined by the given 'draw_method'
+    /// How it clips to rounded elements is defined by the given 'stencil'
+    /// How it blends with the target is defined by the given 'blend'
+    #[allow(clippy::too_many_arguments)]
     pub fn create_default_pipeline(
         &self,
         module: &wgpu::ShaderModule,
         label: Option<&str>,
         draw_method: wgpu::PrimitiveState,
         layouts: &[&BindGroupLayout],
+        push_constants: &[wgpu::PushConstantRange],
         inputs: Vec<VertexBufferLayout<'_>>,
+        stencil: StencilMode,
+        blend: wgpu::BlendState,
     ) -> wgpu::RenderPipeline {
         self.device
             .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -160,8 +250,8 @@ impl RenderingCore {
                 layout: Some(&self.device.create_pipeline_layout(
                     &wgpu::PipelineLayoutDescriptor {
                         label,
-                        bind_group_layouts: &layouts,
-                        push_constant_ranges: &[],
+                        bind_group_layouts: layouts,
+                        push_constant_ranges: push_constants,
                     },
                 )),
                 vertex: wgpu::VertexState {
@@ -176,11 +266,14 @@ impl RenderingCore {
                     compilation_options: wgpu::PipelineCompilationOptions::default(),
                     targets: &[Some(wgpu::ColorTargetState {
                         format: wgpu::TextureFormat::Bgra8UnormSrgb,
-                        write_mask: wgpu::ColorWrites::ALL,
-                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: match stencil.writes_color() {
+                            true => wgpu::ColorWrites::ALL,
+                            false => wgpu::ColorWrites::empty(),
+                        },
+                        blend: Some(blend),
                     })],
                 }),
-                depth_stencil: None,
+                depth_stencil: Some(stencil.depth_stencil_state()),
                 multisample: wgpu::MultisampleState {
                     count: 1,
                     mask: !0,
@@ -206,16 +299,16 @@ impl RenderingCore {
     /// 0 and 1, so their index is their number order on the wgsl
     pub fn create_bind_groups_and_layouts<'a>(
         &self,
-        configs: Vec<Vec<BindGroupAndLayoutConfig<'a>>>,
+        configs: Vec<BindGroupConfig<'a>>,
         label: Option<&str>,
     ) -> (Vec<BindGroup>, Vec<BindGroupLayout>) {
         configs
             .iter()
             .enumerate()
-            .map(|(idx, configs)| {
+            .map(|(idx, config)| {
                 self.create_bindgroup_and_layout(
                     label.map(|v| format!("{v} at idx {idx}")).as_deref(),
-                    configs.as_slice(),
+                    config,
                 )
             })
             .unzip()
@@ -224,71 +317,25 @@ impl RenderingCore {
     pub fn create_bindgroup_and_layout(
         &self,
         label: Option<&str>,
-        configs: &[BindGroupAndLayoutConfig],
+        config: &BindGroupConfig,
     ) -> (BindGroup, BindGroupLayout) {
         let layout = self
             .device
             .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label,
-                entries: &configs
+                entries: &config
+                    .entries()
                     .iter()
-                    .enumerate()
-                    .map(|(binding, config)| match config {
-                        BindGroupAndLayoutConfig::Uniform(visibility, _) => BindGroupLayoutEntry {
-                            binding: binding as u32,
-                            visibility: *visibility,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                        BindGroupAndLayoutConfig::Texutre(dimension, sample_type, _) => {
-                            BindGroupLayoutEntry {
-                                binding: binding as u32,
-                                visibility: ShaderStages::FRAGMENT,
-                                ty: wgpu::BindingType::Texture {
-                                    sample_type: *sample_type,
-                                    view_dimension: *dimension,
-                                    multisampled: false,
-                                },
-                                count: None,
-                            }
-                        }
-                        BindGroupAndLayoutConfig::Sampler(config, _) => BindGroupLayoutEntry {
-                            binding: binding as u32,
-                            visibility: ShaderStages::FRAGMENT,
-                            ty: wgpu::BindingType::Sampler(*config),
-                            count: None,
-                        },
-                    })
+                    .map(BindingEntry::layout_entry)
                     .collect::<Vec<BindGroupLayoutEntry>>(),
             });
         let bindgroup = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label,
             layout: &layout,
-            entries: &configs
+            entries: &config
+                .entries()
                 .iter()
-                .enumerate()
-                .map(|(binding, config)| match config {
-                    BindGroupAndLayoutConfig::Uniform(_, buffer) => BindGroupEntry {
-                        binding: binding as u32,
-                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                            buffer,
-                            offset: 0,
-                            size: None,
-                        }),
-                    },
-                    BindGroupAndLayoutConfig::Texutre(_, _, txt) => BindGroupEntry {
-                        binding: binding as u32,
-                        resource: wgpu::BindingResource::TextureView(txt),
-                    },
-                    BindGroupAndLayoutConfig::Sampler(_, sampler) => BindGroupEntry {
-                        binding: binding as u32,
-                        resource: wgpu::BindingResource::Sampler(sampler),
-                    },
-                })
+                .map(BindingEntry::group_entry)
                 .collect::<Vec<BindGroupEntry>>(),
         });
         (bindgroup, layout)
@@ -298,13 +345,48 @@ impl RenderingCore {
         self.config.width = width;
         self.config.height = height;
         self.surface.configure(&self.device, &self.config);
+        self.stencil = Self::create_stencil(&self.device, width, height);
+        self.targets.clear();
         self.text_renderer.resize(&self.queue, width, height);
+        self.globals.uniforms_mut().screen_size = [width as f32, height as f32];
+    }
+
+    ///Creates a stencil buffer with the given size
+    fn create_stencil(device: &Device, width: u32, height: u32) -> wgpu::TextureView {
+        device
+            .create_texture(&TextureDescriptor {
+                label: Some("stencil"),
+                size: wgpu::Extent3d {
+                    width: width.max(1),
+                    height: height.max(1),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: STENCIL_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&TextureViewDescriptor::default())
     }
 
     pub fn device(&self) -> &Device {
         &self.device
     }
 
+    #[inline]
+    ///Gets the instance the surface was created from
+    pub fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    #[inline]
+    ///Gets the adapter of the device, to check which features and limits custom pipelines can use
+    pub fn adapter(&self) -> &Adapter {
+        &self.adapter
+    }
+
     pub fn create_vertex_buffer<T>(&self, data: &[T], label: Option<&str>) -> Buffer
     where
         T: Pod + Zeroable,
@@ -321,7 +403,20 @@ impl RenderingCore {
     }
 
     pub fn create_index_buffer(&self, data: &[u16], label: Option<&str>) -> Buffer {
-        let byte_data = bytemuck::cast_slice(&data);
+        let byte_data = bytemuck::cast_slice(data);
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label,
+            size: byte_data.len() as u64,
+            usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.queue.write_buffer(&buffer, 0, byte_data);
+        buffer
+    }
+
+    ///Creates an index buffer with 32 bits indices, for meshes with more vertices than 16 bits can index
+    pub fn create_index_buffer_u32(&self, data: &[u32], label: Option<&str>) -> Buffer {
+        let byte_data = bytemuck::cast_slice(data);
         let buffer = self.device.create_buffer(&BufferDescriptor {
             label,
             size: byte_data.len() as u64,
@@ -347,7 +442,20 @@ impl RenderingCore {
         buffer
     }
 
+    ///Creates an image with the given `size` from the given rgba `data`, generating it's whole mip chain so it looks smooth when
+    ///scaled down. The size must be at most the max texture size of the device
     pub fn create_image(&self, size: (u32, u32), data: &[u8]) -> GpuImage {
+        self.upload_image(size, data, mip_level_count(size))
+    }
+
+    ///Same as `create_image`, but without mip levels. Used for atlases whose areas are drawn on their own, since the smaller levels
+    ///would blend the areas next to each other
+    pub fn create_atlas_image(&self, size: (u32, u32), data: &[u8]) -> GpuImage {
+        self.upload_image(size, data, 1)
+    }
+
+    ///Creates an image with the given amount of mip `levels` from the given rgba `data`, generating the ones below the first
+    fn upload_image(&self, size: (u32, u32), data: &[u8], levels: u32) -> GpuImage {
         let size3d = wgpu::Extent3d {
             width: size.0,
             height: size.1,
@@ -356,11 +464,14 @@ impl RenderingCore {
         let texture = self.device.create_texture(&TextureDescriptor {
             label: None,
             size: size3d,
-            mip_level_count: 1,
+            mip_level_count: levels,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            format: IMAGE_FORMAT,
+            //Mip levels are drawn from the previous ones
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
             view_formats: &[],
         });
         self.queue.write_texture(
@@ -378,63 +489,102 @@ impl RenderingCore {
             },
             size3d,
         );
+        self.mipmaps.generate(&self.device, &self.queue, &texture);
         let view = texture.create_view(&TextureViewDescriptor::default());
-        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+        let sampler = self.create_sampler(wgpu::FilterMode::Nearest);
+        GpuImage::new(texture, sampler, view)
+    }
+
+    ///Creates a sampler which clamps to the edges and uses the given `filter` when the texture is scaled, also between mip levels,
+    ///so linear filtering is trilinear
+    pub fn create_sampler(&self, filter: wgpu::FilterMode) -> wgpu::Sampler {
+        self.device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: filter,
+            min_filter: filter,
+            mipmap_filter: filter,
+            ..Default::default()
+        })
+    }
+
+    ///Creates an image with the given `size` which can be drawn on, with the same format of the frames
+    pub fn create_render_image(&self, (width, height): (u32, u32)) -> GpuImage {
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("render image"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TARGET_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        //It's drawn pixel by pixel, so there's nothing to filter
+        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
             mag_filter: wgpu::FilterMode::Nearest,
             min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
             ..Default::default()
         });
         GpuImage::new(texture, sampler, view)
     }
 
     #[inline]
-    pub(crate) fn prepare_texts(&mut self, texts: Vec<(glyphon::Buffer, Vec2f32, Vec4f32)>) {
-        self.text_renderer.prepare(&self.device, &self.queue, texts);
+    ///Removes every loaded font, including the system ones. If the `default-font` feature is enabled, the bundled font is loaded again.
+    ///Texts must be set again before they're drawn, which HystUi::clear_fonts does.
+    pub fn clear_fonts(&mut self) {
+        self.text_renderer.clear_fonts(&self.device, &self.queue);
     }
 
-    pub fn draw(&self, elements: &[&Box<dyn HystElement>], bg: Rgba) {
-        let frame = self.surface.get_current_texture().unwrap();
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear({
-                            wgpu::Color {
-                                r: bg.x() as f64,
-                                g: bg.y() as f64,
-                                b: bg.z() as f64,
-                                a: bg.w() as f64,
-                            }
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-            for element in elements {
-                element.render(&mut render_pass);
-            }
+    #[inline]
+    pub(crate) fn prepare_texts(&mut self, layers: Vec<Vec<PreparedText>>) {
+        self.text_renderer
+            .prepare(&self.device, &self.queue, layers);
+    }
 
-            self.text_renderer.draw_texts(&mut render_pass);
-        }
+    #[inline]
+    ///Whether frames can be drawn directly on the surface, instead of on a target of their own which is then drawn on it
+    fn draws_directly(&self) -> bool {
+        self.config.format == TARGET_FORMAT
+            && self.config.usage.contains(wgpu::TextureUsages::COPY_SRC)
+    }
 
+    ///Draws a frame cleared with the given `bg` color. The given function renders the elements on the frame, which is then drawn
+    ///on the surface through the given `effects`. Frames without effects are drawn directly on the surface when it allows it.
+    pub fn draw<F>(&mut self, bg: Rgba, effects: &Effects, render: F)
+    where
+        F: FnOnce(&mut Frame),
+    {
+        self.globals.write(&self.queue);
+        let surface = self.surface.get_current_texture().unwrap();
+        let view = surface
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let direct = !effects.filters_content() && self.draws_directly();
+        let mut frame = Frame::new(
+            &self.device,
+            &self.text_renderer,
+            &self.effects,
+            &self.stencil,
+            std::mem::take(&mut self.targets),
+            (self.config.width, self.config.height),
+            direct.then(|| (surface.texture.clone(), view.clone())),
+        );
+        frame.clear(bg);
+        render(&mut frame);
+        let (encoder, targets) = frame.finish(&view, effects);
+        self.targets = targets;
         self.queue.submit(Some(encoder.finish()));
-        frame.present();
+        surface.present();
     }
 }
 
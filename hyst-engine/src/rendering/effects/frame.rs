@@ -0,0 +1,461 @@
+use hyst_math::{Rect, vectors::Rgba};
+use wgpu::{CommandEncoder, Device, LoadOp, RenderPass, RenderPipeline, TextureView};
+
+use crate::text::TextManager;
+
+use super::{BlurUniform, CachedLayer, CompositeUniform, EffectRenderer, Effects, Target};
+
+///Texture a frame draws on
+enum Attachment {
+    ///Target of the frame, by it's index
+    Pooled(usize),
+    ///Texture of a cached layer
+    Layer(wgpu::Texture, TextureView),
+    ///Texture of the surface, drawn on directly when there are no effects applied to the whole frame
+    Surface(wgpu::Texture, TextureView),
+}
+
+///Blurs with a bigger sigma, in pixels, are done on downscaled copies of the target, so each pass samples at most 25 texels
+const MAX_SIGMA: f32 = 4.0;
+///Biggest amount the targets are downscaled by for blurring them
+const MAX_DOWNSCALE: u32 = 32;
+
+///A frame being drawn. Elements are drawn on the pass of the current target, which is either the frame itself or an offscreen target
+///pushed for applying effects to some elements.
+pub struct Frame<'a> {
+    device: &'a Device,
+    texts: &'a TextManager,
+    effects: &'a EffectRenderer,
+    stencil: &'a TextureView,
+    encoder: CommandEncoder,
+    pass: Option<RenderPass<'static>>,
+    ///Targets which can be drawn on. They're kept between frames, until the screen is resized
+    targets: Vec<Target>,
+    in_use: Vec<bool>,
+    ///Textures being drawn on, the current last. The first is the target of the frame itself, or the surface
+    stack: Vec<Attachment>,
+    ///Color the current target is cleared with when a pass begins on it
+    clear: Option<wgpu::Color>,
+    ///Whether the stencil was already cleared on this frame. It's shared by every target, so masks pushed on one apply to the others
+    stencil_cleared: bool,
+    size: (u32, u32),
+}
+
+impl<'a> Frame<'a> {
+    ///Creates a frame with the given `size`. When there's a `surface`, it's drawn directly on it, so it can't be drawn through
+    ///effects once finished. Otherwise it's drawn on a target of it's own
+    pub(crate) fn new(
+        device: &'a Device,
+        texts: &'a TextManager,
+        effects: &'a EffectRenderer,
+        stencil: &'a TextureView,
+        targets: Vec<Target>,
+        size: (u32, u32),
+        surface: Option<(wgpu::Texture, TextureView)>,
+    ) -> Self {
+        let mut frame = Self {
+            device,
+            texts,
+            effects,
+            stencil,
+            encoder: device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None }),
+            pass: None,
+            in_use: vec![false; targets.len()],
+            targets,
+            stack: Vec::new(),
+            clear: None,
+            stencil_cleared: false,
+            size,
+        };
+        let scene = match surface {
+            Some((texture, view)) => Attachment::Surface(texture, view),
+            None => Attachment::Pooled(frame.acquire()),
+        };
+        frame.stack.push(scene);
+        frame
+    }
+
+    ///Clears the current target with the given `color` when it's pass begins
+    pub(crate) fn clear(&mut self, color: Rgba) {
+        self.pass = None;
+        self.clear = Some(wgpu::Color {
+            r: color.x() as f64,
+            g: color.y() as f64,
+            b: color.z() as f64,
+            a: color.w() as f64,
+        });
+    }
+
+    ///Gets the size of the frame
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    ///Gets the pass of the current target, beginning it if required
+    pub fn pass(&mut self) -> &mut RenderPass<'static> {
+        if self.pass.is_none() {
+            let view = self.current_view().clone();
+            let pass = self
+                .encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("frame"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: match self.clear.take() {
+                                Some(color) => LoadOp::Clear(color),
+                                None => LoadOp::Load,
+                            },
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: self.stencil,
+                        depth_ops: None,
+                        stencil_ops: Some(wgpu::Operations {
+                            load: match std::mem::replace(&mut self.stencil_cleared, true) {
+                                true => LoadOp::Load,
+                                false => LoadOp::Clear(0),
+                            },
+                            store: wgpu::StoreOp::Store,
+                        }),
+                    }),
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                })
+                .forget_lifetime();
+            self.pass = Some(pass);
+        }
+        self.pass.as_mut().unwrap()
+    }
+
+    ///Draws the prepared texts of the given `layer` on the current target, clipped to the rounded clips they're inside of,
+    ///which are `depth`
+    pub fn draw_texts(&mut self, layer: usize, depth: u32) {
+        let (width, height) = self.size;
+        let texts = self.texts;
+        let pass = self.pass();
+        pass.set_scissor_rect(0, 0, width, height);
+        pass.set_stencil_reference(depth);
+        texts.draw_texts(pass, layer);
+    }
+
+    ///Starts drawing on a new transparent target, until it's popped
+    pub fn push_target(&mut self) {
+        self.pass = None;
+        let target = self.acquire();
+        self.stack.push(Attachment::Pooled(target));
+        self.clear = Some(wgpu::Color::TRANSPARENT);
+    }
+
+    ///Starts drawing on the texture of the given cached `layer`, which is cleared, until it's popped
+    pub(crate) fn push_layer(&mut self, layer: &CachedLayer) {
+        self.pass = None;
+        self.stack.push(Attachment::Layer(
+            layer.texture().clone(),
+            layer.view().clone(),
+        ));
+        self.clear = Some(wgpu::Color::TRANSPARENT);
+    }
+
+    ///Stops drawing on the texture of the current cached layer
+    pub(crate) fn pop_layer(&mut self) {
+        self.pass = None;
+        if matches!(self.stack.last(), Some(Attachment::Layer(..))) {
+            self.stack.pop();
+        }
+    }
+
+    ///Stops drawing on the current target, drawing it on the one below through the given `effects`
+    pub fn pop_target(&mut self, effects: &Effects) {
+        self.pass = None;
+        if self.stack.len() < 2 {
+            return;
+        }
+        let Some(Attachment::Pooled(source)) = self.stack.pop() else {
+            return;
+        };
+        if effects.blur > 0.0 {
+            self.blur(source, effects.blur, None);
+        }
+        let destination = self.current_view().clone();
+        let uniform = self.composite_uniform(effects, None, 0.0);
+        let pipeline = self.effects.composite();
+        self.fullscreen_pass(pipeline, source, &destination, &uniform, None);
+        self.in_use[source] = false;
+    }
+
+    ///Blurs what's already drawn on the current target inside of the given `rect`, rounded by the given `radius`, so it's seen
+    ///blurred through the element drawn next. Nothing outside of the given `clip` is changed
+    pub fn backdrop(&mut self, rect: Rect, radius: f32, sigma: f32, clip: Rect) {
+        let Some(area) = rect.intersection(&clip) else {
+            return;
+        };
+        self.pass = None;
+        let (texture, view) = match self.stack.last().unwrap() {
+            Attachment::Pooled(idx) => (
+                self.targets[*idx].texture.clone(),
+                self.targets[*idx].view.clone(),
+            ),
+            Attachment::Layer(texture, view) | Attachment::Surface(texture, view) => {
+                (texture.clone(), view.clone())
+            }
+        };
+        let copy = self.acquire();
+        let (width, height) = self.size;
+        self.encoder.copy_texture_to_texture(
+            texture.as_image_copy(),
+            self.targets[copy].texture.as_image_copy(),
+            wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+        );
+        self.blur(copy, sigma, Some(area));
+        let uniform = self.composite_uniform(&Effects::default(), Some(rect), radius);
+        let pipeline = self.effects.composite();
+        self.fullscreen_pass(pipeline, copy, &view, &uniform, Some(area));
+        self.in_use[copy] = false;
+    }
+
+    ///Ends the frame, drawing it on the given surface `view` through the given `effects`, unless it was drawn directly on it
+    pub(crate) fn finish(
+        mut self,
+        view: &TextureView,
+        effects: &Effects,
+    ) -> (CommandEncoder, Vec<Target>) {
+        self.pass = None;
+        let Attachment::Pooled(scene) = self.stack[0] else {
+            return (self.encoder, self.targets);
+        };
+        if effects.blur > 0.0 {
+            self.blur(scene, effects.blur, None);
+        }
+        let uniform = self.composite_uniform(effects, None, 0.0);
+        {
+            let mut pass = self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("present"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(self.effects.present());
+            pass.set_bind_group(0, &uniform, &[]);
+            pass.set_bind_group(1, &self.targets[scene].bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        (self.encoder, self.targets)
+    }
+
+    ///Gets the view of the texture being drawn on
+    fn current_view(&self) -> &TextureView {
+        match self.stack.last().unwrap() {
+            Attachment::Pooled(idx) => &self.targets[*idx].view,
+            Attachment::Layer(_, view) | Attachment::Surface(_, view) => view,
+        }
+    }
+
+    #[inline]
+    ///Gets a target the size of the frame which isn't being used, creating it if there's none
+    fn acquire(&mut self) -> usize {
+        self.acquire_sized(self.size)
+    }
+
+    ///Gets a target with the given `size` which isn't being used, creating it if there's none
+    fn acquire_sized(&mut self, size: (u32, u32)) -> usize {
+        let free = (0..self.targets.len())
+            .find(|idx| !self.in_use[*idx] && self.targets[*idx].size == size);
+        match free {
+            Some(idx) => {
+                self.in_use[idx] = true;
+                idx
+            }
+            None => {
+                let target = self.effects.create_target(self.device, size);
+                self.targets.push(target);
+                self.in_use.push(true);
+                self.targets.len() - 1
+            }
+        }
+    }
+
+    ///Blurs the given `target` with a gaussian of the given `sigma`. If `rect` is given, only the pixels inside of it are blurred.
+    ///Wide blurs are done on a copy downscaled by halves, which is scaled up again when drawn back on the target
+    fn blur(&mut self, target: usize, sigma: f32, rect: Option<Rect>) {
+        let mut factor = 1;
+        while sigma / factor as f32 > MAX_SIGMA && factor < MAX_DOWNSCALE {
+            factor *= 2;
+        }
+        if factor == 1 {
+            self.gaussian(target, sigma, rect);
+            return;
+        }
+        let (width, height) = self.size;
+        let mut levels = Vec::new();
+        let mut source = target;
+        let mut scale = 1;
+        while scale < factor {
+            scale *= 2;
+            let level = self.acquire_sized(((width / scale).max(1), (height / scale).max(1)));
+            self.copy(source, level, None);
+            levels.push(level);
+            source = level;
+        }
+        self.gaussian(source, sigma / factor as f32, None);
+        self.copy(source, target, rect);
+        for level in levels {
+            self.in_use[level] = false;
+        }
+    }
+
+    ///Blurs the given `target` with a gaussian of the given `sigma`, in texels of the target, on each axis separately.
+    ///If `rect` is given, only the pixels inside of it are blurred
+    fn gaussian(&mut self, target: usize, sigma: f32, rect: Option<Rect>) {
+        let size = self.targets[target].size;
+        let scratch = self.acquire_sized(size);
+        let reach = (sigma * 3.0).ceil();
+        //The vertical pass reads the pixels above and below the rect, so they're blurred horizontally too
+        let expanded = rect.map(|rect| {
+            Rect::from_xywh(
+                rect.position().x(),
+                rect.position().y() - reach,
+                rect.size().x(),
+                rect.size().y() + reach * 2.0,
+            )
+        });
+        let horizontal = self.effects.create_uniform(
+            self.device,
+            &BlurUniform {
+                direction: [1.0 / size.0.max(1) as f32, 0.0],
+                sigma,
+                _padding: 0.0,
+            },
+        );
+        let vertical = self.effects.create_uniform(
+            self.device,
+            &BlurUniform {
+                direction: [0.0, 1.0 / size.1.max(1) as f32],
+                sigma,
+                _padding: 0.0,
+            },
+        );
+        let pipeline = self.effects.blur();
+        let (target_view, scratch_view) = (
+            self.targets[target].view.clone(),
+            self.targets[scratch].view.clone(),
+        );
+        self.fullscreen_pass(pipeline, target, &scratch_view, &horizontal, expanded);
+        self.fullscreen_pass(pipeline, scratch, &target_view, &vertical, rect);
+        self.in_use[scratch] = false;
+    }
+
+    ///Draws the `source` target scaled to fill the `destination` one, filtered linearly, limited to the given `rect`
+    fn copy(&mut self, source: usize, destination: usize, rect: Option<Rect>) {
+        //The blur shader copies when there's no sigma
+        let uniform = self.effects.create_uniform(
+            self.device,
+            &BlurUniform {
+                direction: [0.0; 2],
+                sigma: 0.0,
+                _padding: 0.0,
+            },
+        );
+        let view = self.targets[destination].view.clone();
+        self.fullscreen_pass(self.effects.blur(), source, &view, &uniform, rect);
+    }
+
+    fn composite_uniform(
+        &self,
+        effects: &Effects,
+        rect: Option<Rect>,
+        radius: f32,
+    ) -> wgpu::BindGroup {
+        let (width, height) = self.size;
+        let rect = rect.map_or([0.0; 4], |rect| {
+            [
+                rect.position().x(),
+                rect.position().y(),
+                rect.size().x(),
+                rect.size().y(),
+            ]
+        });
+        self.effects.create_uniform(
+            self.device,
+            &CompositeUniform {
+                screen_size: [width as f32, height as f32],
+                radius,
+                masked: (rect[2] > 0.0) as u32 as f32,
+                rect,
+                brightness: effects.brightness,
+                contrast: effects.contrast,
+                saturation: effects.saturation,
+                hue: effects.hue_rotate.to_radians(),
+                grayscale: effects.grayscale.clamp(0.0, 1.0),
+                opacity: effects.opacity.clamp(0.0, 1.0),
+                _padding: [0.0; 2],
+            },
+        )
+    }
+
+    ///Draws the `source` target on the `destination` texture with the given `pipeline`, limited to the given `rect`. Rects are
+    ///only given for destinations the size of the frame
+    fn fullscreen_pass(
+        &mut self,
+        pipeline: &RenderPipeline,
+        source: usize,
+        destination: &TextureView,
+        uniform: &wgpu::BindGroup,
+        rect: Option<Rect>,
+    ) {
+        let scissor = match rect.map(|rect| self.scissor_of(rect)) {
+            Some(None) => return,
+            Some(scissor) => scissor,
+            None => None,
+        };
+        let mut pass = self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("effect"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: destination,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        if let Some((x, y, width, height)) = scissor {
+            pass.set_scissor_rect(x, y, width, height);
+        }
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, uniform, &[]);
+        pass.set_bind_group(1, &self.targets[source].bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+
+    ///Converts the given `rect` into a scissor rect, clamped to the frame. None if it's empty
+    fn scissor_of(&self, rect: Rect) -> Option<(u32, u32, u32, u32)> {
+        let (width, height) = self.size;
+        let left = rect.position().x().floor().clamp(0.0, width as f32) as u32;
+        let top = rect.position().y().floor().clamp(0.0, height as f32) as u32;
+        let right = (rect.position().x() + rect.size().x())
+            .ceil()
+            .clamp(0.0, width as f32) as u32;
+        let bottom = (rect.position().y() + rect.size().y())
+            .ceil()
+            .clamp(0.0, height as f32) as u32;
+        (right > left && bottom > top).then_some((left, top, right - left, bottom - top))
+    }
+}
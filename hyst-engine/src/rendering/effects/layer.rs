@@ -0,0 +1,104 @@
+use std::cell::Cell;
+
+use hyst_math::{Affine, Rect};
+use wgpu::{RenderPass, Texture, TextureView};
+
+use crate::{
+    core::RenderingCore,
+    meshes::{Mesh, image::Image},
+};
+
+///Subtree of elements drawn on it's own texture, which is drawn as an image on the frame. The elements are only drawn again when
+///something inside of the subtree changes, so moving or fading the whole subtree is cheap.
+///The texture has the size of the screen. The elements are drawn on it where they're laid out, without the transform and the
+///opacity of the root of the subtree, which are applied to the image instead.
+#[derive(Debug)]
+pub(crate) struct CachedLayer {
+    image: Image,
+    texture: Texture,
+    view: TextureView,
+    size: (u32, u32),
+    ///Transform, opacity and clip the image was placed with. None until it's placed
+    placement: Option<(Affine, f32, Rect)>,
+    ///Whether the elements must be drawn again on the next frame
+    dirty: Cell<bool>,
+}
+
+impl CachedLayer {
+    pub fn new(core: &mut RenderingCore) -> Self {
+        let size = core.size();
+        let gpu_image = core.create_render_image(size);
+        let (texture, view) = (gpu_image.texture().clone(), gpu_image.view().clone());
+        let rect = Rect::from_xywh(0.0, 0.0, size.0 as f32, size.1 as f32);
+        Self {
+            image: Image::from_gpu_image(core, rect, gpu_image, true),
+            texture,
+            view,
+            size,
+            placement: None,
+            dirty: Cell::new(true),
+        }
+    }
+
+    ///Recreates the texture if the screen was resized, since it must have it's size
+    pub fn resize(&mut self, core: &mut RenderingCore) {
+        if self.size != core.size() {
+            *self = Self::new(core);
+        }
+    }
+
+    ///Places the image on the target it's drawn on with the given `transform` and `opacity`, clipped to the given `clip`.
+    /// # Returns
+    /// Whether the placement changed, so the target the image is drawn on must be drawn again
+    pub fn place(
+        &mut self,
+        core: &RenderingCore,
+        transform: &Affine,
+        opacity: f32,
+        clip: Rect,
+    ) -> bool {
+        let placement = (*transform, opacity, clip);
+        if self.placement == Some(placement) {
+            return false;
+        }
+        self.image.set_transform(core, transform);
+        self.image.set_opacity(core, opacity);
+        self.placement = Some(placement);
+        true
+    }
+
+    #[inline]
+    ///Gets the area the image is clipped to on the target it's drawn on
+    pub fn clip(&self) -> Option<Rect> {
+        self.placement.map(|(_, _, clip)| clip)
+    }
+
+    #[inline]
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+    #[inline]
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+
+    #[inline]
+    ///Requests the elements to be drawn again on the next frame
+    pub fn invalidate(&self) {
+        self.dirty.set(true);
+    }
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+    #[inline]
+    ///Tells the elements were drawn on the texture
+    pub fn mark_clean(&self) {
+        self.dirty.set(false);
+    }
+
+    ///Draws the texture on the given `pass`
+    pub fn draw(&self, pass: &mut RenderPass) {
+        self.image.draw(pass);
+    }
+}
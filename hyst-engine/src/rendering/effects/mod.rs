@@ -0,0 +1,92 @@
+//Effects are applied by drawing elements on offscreen targets, which are then drawn on the target below them through filters.
+mod frame;
+pub use frame::*;
+mod layer;
+pub(crate) use layer::*;
+mod renderer;
+pub(crate) use renderer::*;
+
+///Filters applied to an element and it's children, or to the whole frame. The default values change nothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Effects {
+    ///Standard deviation, in pixels, of the gaussian blur applied to the content
+    pub blur: f32,
+    ///Standard deviation, in pixels, of the gaussian blur applied to what's behind the element, seen through it's background.
+    ///Ignored for the whole frame
+    pub backdrop_blur: f32,
+    ///Multiplier of the color of the content
+    pub brightness: f32,
+    pub contrast: f32,
+    pub saturation: f32,
+    ///Rotation of the hue of the content, in degrees
+    pub hue_rotate: f32,
+    ///How much of the color of the content is replaced by it's luminance, between 0 and 1
+    pub grayscale: f32,
+    ///Opacity of the content as a whole, so overlapping children don't show through each other
+    pub opacity: f32,
+}
+
+impl Default for Effects {
+    fn default() -> Self {
+        Self {
+            blur: 0.0,
+            backdrop_blur: 0.0,
+            brightness: 1.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            hue_rotate: 0.0,
+            grayscale: 0.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl Effects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn blur(mut self, sigma: f32) -> Self {
+        self.blur = sigma;
+        self
+    }
+    pub fn backdrop_blur(mut self, sigma: f32) -> Self {
+        self.backdrop_blur = sigma;
+        self
+    }
+    pub fn brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness;
+        self
+    }
+    pub fn contrast(mut self, contrast: f32) -> Self {
+        self.contrast = contrast;
+        self
+    }
+    pub fn saturation(mut self, saturation: f32) -> Self {
+        self.saturation = saturation;
+        self
+    }
+    pub fn hue_rotate(mut self, degrees: f32) -> Self {
+        self.hue_rotate = degrees;
+        self
+    }
+    pub fn grayscale(mut self, amount: f32) -> Self {
+        self.grayscale = amount;
+        self
+    }
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    ///Whether some of the filters changes the content, so it must be drawn on an offscreen target
+    pub fn filters_content(&self) -> bool {
+        let identity = Self::default();
+        self.blur > 0.0
+            || self.brightness != identity.brightness
+            || self.contrast != identity.contrast
+            || self.saturation != identity.saturation
+            || self.hue_rotate != identity.hue_rotate
+            || self.grayscale > 0.0
+            || self.opacity < 1.0
+    }
+}
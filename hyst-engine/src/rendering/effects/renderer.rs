@@ -0,0 +1,218 @@
+use bytemuck::{Pod, Zeroable};
+use wgpu::{
+    BindGroup, BindGroupLayout, Device, RenderPipeline, Sampler, Texture, TextureFormat,
+    TextureView, util::DeviceExt,
+};
+
+///Format of the offscreen targets, the same every pipeline draws with
+pub(crate) const TARGET_FORMAT: TextureFormat = TextureFormat::Bgra8UnormSrgb;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub(crate) struct BlurUniform {
+    pub direction: [f32; 2],
+    pub sigma: f32,
+    pub _padding: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub(crate) struct CompositeUniform {
+    pub screen_size: [f32; 2],
+    pub radius: f32,
+    pub masked: f32,
+    pub rect: [f32; 4],
+    pub brightness: f32,
+    pub contrast: f32,
+    pub saturation: f32,
+    pub hue: f32,
+    pub grayscale: f32,
+    pub opacity: f32,
+    pub _padding: [f32; 2],
+}
+
+///Texture the size of the screen which elements can be drawn on, and sampled from by the effects
+#[derive(Debug)]
+pub(crate) struct Target {
+    pub texture: Texture,
+    pub size: (u32, u32),
+    pub view: TextureView,
+    ///Binds the texture for sampling
+    pub bind_group: BindGroup,
+}
+
+///Pipelines used for applying effects to offscreen targets
+#[derive(Debug)]
+pub(crate) struct EffectRenderer {
+    blur: RenderPipeline,
+    composite: RenderPipeline,
+    ///Draws the frame on the surface, replacing it's content
+    present: RenderPipeline,
+    uniform_layout: BindGroupLayout,
+    texture_layout: BindGroupLayout,
+    sampler: Sampler,
+}
+
+impl EffectRenderer {
+    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+        let uniform_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("effect uniforms"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let texture_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("effect source"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("effects"),
+            bind_group_layouts: &[&uniform_layout, &texture_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |source: &str, format, blend| {
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("effect"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("effect"),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: Some("vertex_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: Some("fragment_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        let blur = std::fs::read_to_string("./shaders/blur.wgsl").unwrap();
+        let composite = std::fs::read_to_string("./shaders/composite.wgsl").unwrap();
+        Self {
+            blur: pipeline(&blur, TARGET_FORMAT, None),
+            //Targets are drawn on transparent, so their content is premultiplied by it's alpha
+            composite: pipeline(
+                &composite,
+                TARGET_FORMAT,
+                Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            ),
+            present: pipeline(&composite, surface_format, None),
+            sampler: device.create_sampler(&wgpu::SamplerDescriptor {
+                label: Some("effect source"),
+                mag_filter: wgpu::FilterMode::Linear,
+                min_filter: wgpu::FilterMode::Linear,
+                ..Default::default()
+            }),
+            uniform_layout,
+            texture_layout,
+        }
+    }
+
+    pub fn blur(&self) -> &RenderPipeline {
+        &self.blur
+    }
+    pub fn composite(&self) -> &RenderPipeline {
+        &self.composite
+    }
+    pub fn present(&self) -> &RenderPipeline {
+        &self.present
+    }
+
+    ///Creates a target with the given size, which is usually the one of the screen, or smaller for downscaled blurs
+    pub fn create_target(&self, device: &Device, (width, height): (u32, u32)) -> Target {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("effect target"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TARGET_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("effect source"),
+            layout: &self.texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+        Target {
+            texture,
+            size: (width, height),
+            view,
+            bind_group,
+        }
+    }
+
+    ///Creates the bind group of the given `uniform`. Each pass has it's own, since they're all submitted at once
+    pub fn create_uniform<T: Pod>(&self, device: &Device, uniform: &T) -> BindGroup {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("effect uniform"),
+            contents: bytemuck::bytes_of(uniform),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("effect uniform"),
+            layout: &self.uniform_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        })
+    }
+}
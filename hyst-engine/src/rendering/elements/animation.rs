@@ -0,0 +1,249 @@
+use std::{
+    sync::mpsc::{self, Receiver, TryRecvError},
+    time::Duration,
+};
+
+use hyst_math::{Affine, Rect, vectors::Rgba};
+use taffy::NodeId;
+
+use crate::{
+    core::RenderingCore,
+    meshes::{
+        Mesh,
+        image::{
+            AnimationFrame, AnimationSource, DecodedAnimation, Image, ImageDisplay, ImageError,
+            ImageStatus,
+        },
+    },
+    ui::{HystElementKey, pulse::Pulse},
+};
+
+use super::HystElement;
+
+pub struct HystAnimationCreationOption {
+    pub source: AnimationSource,
+    pub display: ImageDisplay,
+    pub max_size: Option<u32>,
+    pub placeholder: Rgba,
+    pub playing: Pulse<bool>,
+    pub looping: Pulse<bool>,
+    pub rect: Rect,
+    pub parent: Option<HystElementKey>,
+    pub style: NodeId,
+    pub key: HystElementKey,
+}
+
+///Image which plays the frames of an animation, such as a gif or a sprite sheet. Every frame is on the same texture, so
+///advancing only changes the area of it which is shown
+pub struct HystAnimation {
+    img: Image,
+    key: HystElementKey,
+    parent: Option<HystElementKey>,
+    children: Vec<HystElementKey>,
+    style: NodeId,
+    ///Display of the frames. It's source rect is replaced by the one of the current frame
+    display: ImageDisplay,
+    status: ImageStatus,
+    ///Receives the frames from the thread decoding them
+    pending: Option<Receiver<Result<DecodedAnimation, ImageError>>>,
+    frames: Vec<AnimationFrame>,
+    current: usize,
+    ///Time the current frame has been shown for
+    elapsed: Duration,
+    ///Whether the animation stopped on it's last frame, so playing it again starts over
+    finished: bool,
+    ///Whether the frames advance. It's set to false when an animation which doesn't loop reaches it's last frame
+    playing: Pulse<bool>,
+    ///Whether the animation starts again after it's last frame
+    looping: Pulse<bool>,
+}
+
+impl HystAnimation {
+    ///Creates an animation which shows the placeholder while it's frames are decoded on a background thread
+    pub fn new(core: &mut RenderingCore, options: HystAnimationCreationOption) -> Self {
+        let placeholder = [
+            options.placeholder.x(),
+            options.placeholder.y(),
+            options.placeholder.z(),
+            options.placeholder.w(),
+        ]
+        .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+        let limit = core
+            .max_texture_size()
+            .min(options.max_size.unwrap_or(u32::MAX));
+        let (tx, rx) = mpsc::channel();
+        let source = options.source;
+        std::thread::spawn(move || {
+            //The animation may be gone before it finishes, so nobody waits for it
+            let _ = tx.send(source.decode(limit));
+        });
+        Self {
+            img: Image::new(core, options.rect, (1, 1), &placeholder),
+            key: options.key,
+            parent: options.parent,
+            children: Vec::new(),
+            style: options.style,
+            display: options.display,
+            status: ImageStatus::Loading,
+            pending: Some(rx),
+            frames: Vec::new(),
+            current: 0,
+            elapsed: Duration::ZERO,
+            finished: false,
+            playing: options.playing,
+            looping: options.looping,
+        }
+    }
+
+    ///Checks whether the thread decoding the frames finished, uploading them if so.
+    /// # Returns
+    /// Whether the animation stopped loading on this call
+    pub fn poll(&mut self, core: &mut RenderingCore) -> bool {
+        let Some(ref pending) = self.pending else {
+            return false;
+        };
+        let result = match pending.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => Err(ImageError::Interrupted),
+        };
+        self.pending = None;
+        self.status = match result {
+            Ok(decoded) => {
+                let image = core.create_atlas_image(decoded.atlas.dimensions(), &decoded.atlas);
+                self.img.set_gpu_image(core, image);
+                self.frames = decoded.frames;
+                self.show(core, 0);
+                ImageStatus::Ready
+            }
+            Err(error) => ImageStatus::Failed(error),
+        };
+        true
+    }
+
+    ///Advances the frames by the given `dt`, if the animation is playing.
+    /// # Returns
+    /// Whether the shown frame changed, and whether the animation keeps playing
+    pub fn tick(&mut self, core: &mut RenderingCore, dt: Duration) -> (bool, bool) {
+        if !*self.playing.read() || self.frames.len() < 2 {
+            return (false, false);
+        }
+        if self.finished {
+            self.finished = false;
+            self.seek(core, 0);
+            return (true, true);
+        }
+        self.elapsed += dt;
+        let mut current = self.current;
+        while self.elapsed >= self.frames[current].delay {
+            self.elapsed -= self.frames[current].delay;
+            if current + 1 < self.frames.len() {
+                current += 1;
+            } else if *self.looping.read() {
+                current = 0;
+            } else {
+                self.elapsed = Duration::ZERO;
+                self.finished = true;
+                self.playing.mutate(|mut playing| *playing = false);
+                break;
+            }
+        }
+        let changed = current != self.current;
+        if changed {
+            self.show(core, current);
+        }
+        (changed, *self.playing.read())
+    }
+
+    ///Shows the frame with the given `index`, clamped to the last one
+    fn show(&mut self, core: &mut RenderingCore, index: usize) {
+        let Some(last) = self.frames.len().checked_sub(1) else {
+            return;
+        };
+        self.current = index.min(last);
+        let rect = self.frames[self.current].rect;
+        self.img.set_display(core, self.display.source_rect(rect));
+    }
+
+    ///Shows the frame with the given `index` from it's start, whether the animation is playing or not
+    pub fn seek(&mut self, core: &mut RenderingCore, index: usize) {
+        self.elapsed = Duration::ZERO;
+        self.finished = false;
+        self.show(core, index);
+    }
+
+    #[inline]
+    ///Gets the index of the frame being shown
+    pub fn frame(&self) -> usize {
+        self.current
+    }
+
+    #[inline]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    #[inline]
+    pub fn status(&self) -> &ImageStatus {
+        &self.status
+    }
+
+    #[inline]
+    pub fn is_loading(&self) -> bool {
+        matches!(self.status, ImageStatus::Loading)
+    }
+
+    #[inline]
+    pub fn playing(&self) -> &Pulse<bool> {
+        &self.playing
+    }
+
+    #[inline]
+    pub fn looping(&self) -> &Pulse<bool> {
+        &self.looping
+    }
+
+    ///Changes how the frames are shown inside of the rect. It's source rect is ignored, since each frame defines it's own
+    pub fn set_display(&mut self, core: &mut RenderingCore, display: ImageDisplay) {
+        self.display = display;
+        if matches!(self.status, ImageStatus::Ready) {
+            self.show(core, self.current);
+        }
+    }
+
+    pub fn style(&self) -> NodeId {
+        self.style
+    }
+
+    pub fn parent(&self) -> Option<&HystElementKey> {
+        self.parent.as_ref()
+    }
+}
+
+impl HystElement for HystAnimation {
+    fn id(&self) -> HystElementKey {
+        self.key
+    }
+    fn layout(&self) -> NodeId {
+        self.style
+    }
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        self.img.set_transform(core, transform);
+    }
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        self.img.set_opacity(core, opacity);
+    }
+    fn resize(&mut self, core: &mut RenderingCore, layout: &taffy::Layout) {
+        self.img.resize(core, layout);
+    }
+    fn children(&self) -> &Vec<HystElementKey> {
+        &self.children
+    }
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
+        &mut self.children
+    }
+    fn update(&mut self, _core: &mut RenderingCore) {}
+    fn render(&self, pass: &mut wgpu::RenderPass) {
+        self.img.draw(pass);
+    }
+}
@@ -0,0 +1,155 @@
+use hyst_math::{
+    Affine,
+    vectors::{Rgba, Vec2f32},
+};
+use taffy::NodeId;
+
+use crate::{
+    core::RenderingCore,
+    meshes::shapes::Shapes,
+    path::{FillRule, Path, StrokeStyle},
+    ui::{HystElementKey, pulse::Pulse},
+};
+
+use super::HystElement;
+
+///Path drawn on a canvas, filled, stroked or both
+#[derive(Debug, Clone)]
+pub struct CanvasShape {
+    pub path: Path,
+    pub fill: Option<Rgba>,
+    pub fill_rule: FillRule,
+    ///Color and style of the outline, drawn above the fill
+    pub stroke: Option<(Rgba, StrokeStyle)>,
+}
+
+impl CanvasShape {
+    ///Creates a shape with the given `path`, which draws nothing until it's given a fill or a stroke
+    pub fn new(path: Path) -> Self {
+        Self {
+            path,
+            fill: None,
+            fill_rule: FillRule::NonZero,
+            stroke: None,
+        }
+    }
+    pub fn fill(mut self, color: Rgba) -> Self {
+        self.fill = Some(color);
+        self
+    }
+    pub fn fill_rule(mut self, rule: FillRule) -> Self {
+        self.fill_rule = rule;
+        self
+    }
+    pub fn stroke(mut self, color: Rgba, style: StrokeStyle) -> Self {
+        self.stroke = Some((color, style));
+        self
+    }
+}
+
+pub struct HystCanvasCreationOption {
+    pub shapes: Pulse<Vec<CanvasShape>>,
+    pub parent: Option<HystElementKey>,
+    pub style: NodeId,
+    pub key: HystElementKey,
+}
+
+///Element which draws vector shapes, in pixels relative to it's top left corner. They're tessellated into triangles whenever
+///their pulse changes, and drawn in a single draw call
+pub struct HystCanvas {
+    mesh: Shapes,
+    shapes: Pulse<Vec<CanvasShape>>,
+    ///Top left corner of the canvas on the screen, which the shapes are moved to
+    position: Vec2f32,
+    ///Transform of the canvas composed with the ones of it's ancestors
+    world: Affine,
+    key: HystElementKey,
+    parent: Option<HystElementKey>,
+    children: Vec<HystElementKey>,
+    style: NodeId,
+}
+
+impl HystCanvas {
+    pub fn new(core: &mut RenderingCore, options: HystCanvasCreationOption) -> Self {
+        let mut shapes = options.shapes;
+        shapes.add_dependency(options.key);
+        let mut this = Self {
+            mesh: Shapes::new(core),
+            shapes,
+            position: Vec2f32::default(),
+            world: Affine::IDENTITY,
+            key: options.key,
+            parent: options.parent,
+            children: Vec::new(),
+            style: options.style,
+        };
+        this.tessellate(core);
+        this
+    }
+
+    ///Tessellates the fills and the strokes of the shapes, in the order they're drawn
+    fn tessellate(&mut self, core: &RenderingCore) {
+        let shapes = self.shapes.read();
+        let mut triangles = Vec::new();
+        for shape in shapes.iter() {
+            if let Some(color) = shape.fill {
+                triangles.push((shape.path.fill(shape.fill_rule), color));
+            }
+            if let Some((color, ref stroke)) = shape.stroke {
+                triangles.push((shape.path.stroke(stroke), color));
+            }
+        }
+        self.mesh.set_shapes(core, &triangles);
+    }
+
+    #[inline]
+    pub fn shapes(&self) -> &Pulse<Vec<CanvasShape>> {
+        &self.shapes
+    }
+
+    pub fn style(&self) -> NodeId {
+        self.style
+    }
+
+    pub fn parent(&self) -> Option<&HystElementKey> {
+        self.parent.as_ref()
+    }
+
+    ///Writes the transform of the canvas, moving the shapes to it's position
+    fn write_transform(&mut self, core: &RenderingCore) {
+        let local = Affine::translation(self.position.x(), self.position.y());
+        self.mesh.set_transform(core, &self.world.then(&local));
+    }
+}
+
+impl HystElement for HystCanvas {
+    fn id(&self) -> HystElementKey {
+        self.key
+    }
+    fn layout(&self) -> NodeId {
+        self.style
+    }
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        self.world = *transform;
+        self.write_transform(core);
+    }
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        self.mesh.set_opacity(core, opacity);
+    }
+    fn resize(&mut self, core: &mut RenderingCore, layout: &taffy::Layout) {
+        self.position = Vec2f32::new(layout.location.x, layout.location.y);
+        self.write_transform(core);
+    }
+    fn children(&self) -> &Vec<HystElementKey> {
+        &self.children
+    }
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
+        &mut self.children
+    }
+    fn update(&mut self, core: &mut RenderingCore) {
+        self.tessellate(core);
+    }
+    fn render(&self, pass: &mut wgpu::RenderPass) {
+        self.mesh.draw(pass);
+    }
+}
@@ -0,0 +1,35 @@
+use taffy::NodeId;
+
+use crate::ui::HystElementKey;
+
+///Options given to the function building a custom element, so it knows where it is on the ui
+///
+///Elements defined outside of the crate are inserted through HystUi::insert_custom, which allocates their key and their layout.
+///Their layout is measured, so leaves whose size depends on their content get it from HystElement::measure.
+///Those which draw something of their own usually do it through a custom pipeline:
+///
+/// * A type implementing HystShader and HystConstructor holds the pipeline and the bind groups. It's name must be unique, since the
+///   pipelines are cached by it and shared by every shader with the same name.
+/// * Uniforms are AbstractBuffers created with BufferType::Uniform, added to a BindGroupConfig along with the binding and the
+///   stages they're visible on. Storage buffers, textures and samplers are added the same way, and push constants on the
+///   ShaderCreationOptions themselves. RenderingCore::create_shader creates the shader with them, each bind group on it's index
+///   plus one, since group 0 holds the global uniforms shared by every pipeline.
+/// * The wgsl source must have the vertex_main and fragment_main entry points, and draw on the Bgra8UnormSrgb target. It's
+///   pipeline is clipped to the rounded ancestors through the stencil buffer, and blends it's colors with ALPHA_OVER, unless
+///   HystConstructor::stencil and HystConstructor::blend say otherwise.
+/// * Positions are in pixels on the screen. The screen size, the cursor, the scale factor, the time and the index of the frame are
+///   the GlobalUniforms of group 0, so animated effects can be drawn from them. The transform and the opacity of the element,
+///   composed with the ones of it's ancestors up to the cached layer it's drawn on, if any, are given on set_transform and
+///   set_opacity. Triangles aren't culled, since negative
+///   scales mirror them, unless ShaderCreationOptions::rendering_style says otherwise.
+/// * The element sets it's pipeline, bind groups and buffers on render. The scissor and the stencil reference are already set.
+///
+///The meshes of the crate, such as Shapes, Quads and Image, are built the same way, so they're examples of it.
+#[derive(Debug, Clone, Copy)]
+pub struct HystCustomCreationOption {
+    pub parent: Option<HystElementKey>,
+    ///Layout of the element, returned by it's HystElement::layout
+    pub style: NodeId,
+    ///Key of the element, returned by it's HystElement::id. Pulses which update the element require it as a dependency
+    pub key: HystElementKey,
+}
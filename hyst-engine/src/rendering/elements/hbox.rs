@@ -5,7 +5,7 @@ use crate::{
     meshes::{Mesh, container::Container},
     ui::HystElementKey,
 };
-use hyst_math::Rect;
+use hyst_math::{Affine, Rect};
 use taffy::NodeId;
 
 use super::HystElement;
@@ -13,6 +13,7 @@ use super::HystElement;
 pub struct HystBoxCreationOption {
     pub background: Background,
     pub rect: Rect,
+    pub radius: f32,
     pub parent: Option<HystElementKey>,
     pub style: NodeId,
     pub key: HystElementKey,
@@ -29,7 +30,7 @@ pub struct HystBox {
 
 impl HystBox {
     pub fn new(core: &mut RenderingCore, config: HystBoxCreationOption) -> Self {
-        let container = Container::new(core, config.background, config.rect);
+        let container = Container::new(core, config.background, config.rect, config.radius);
         Self {
             container,
             parent: config.parent,
@@ -39,10 +40,6 @@ impl HystBox {
         }
     }
 
-    pub fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
-        &mut self.children
-    }
-
     pub fn parent(&self) -> Option<&HystElementKey> {
         self.parent.as_ref()
     }
@@ -55,12 +52,13 @@ impl HystBox {
         &mut self.container
     }
 
-    pub fn rect(&mut self) -> &mut AbstractBuffer<Rect> {
-        self.container.area_buffer()
+    ///Fills this box with the given `bg`
+    pub fn set_background(&mut self, core: &RenderingCore, bg: &Background) {
+        self.container.set_background(core, bg);
     }
 
-    pub fn screen_size(&mut self) -> &mut AbstractBuffer<[f32; 2]> {
-        self.container.screen_size()
+    pub fn rect(&mut self) -> &mut AbstractBuffer<Rect> {
+        self.container.area_buffer()
     }
 }
 
@@ -71,13 +69,25 @@ impl HystElement for HystBox {
     fn layout(&self) -> NodeId {
         self.style
     }
-    fn resize(&mut self, core: &mut RenderingCore, size: (f32, f32), layout: &taffy::Layout) {
-        self.container.resize(core, size, layout);
+    fn resize(&mut self, core: &mut RenderingCore, layout: &taffy::Layout) {
+        self.container.resize(core, layout);
+    }
+    fn corner_radius(&self) -> f32 {
+        self.container.radius()
+    }
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        self.container.set_transform(core, transform);
+    }
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        self.container.set_opacity(core, opacity);
     }
     fn children(&self) -> &Vec<HystElementKey> {
         &self.children
     }
-    fn update(&mut self, core: &mut RenderingCore) {}
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
+        &mut self.children
+    }
+    fn update(&mut self, _core: &mut RenderingCore) {}
     fn render(&self, pass: &mut wgpu::RenderPass) {
         self.container.draw(pass);
     }
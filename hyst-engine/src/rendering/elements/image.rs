@@ -1,20 +1,33 @@
-use hyst_math::Rect;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use hyst_math::{Affine, Rect, vectors::Rgba};
 use taffy::NodeId;
 
 use crate::{
     core::RenderingCore,
     meshes::{
         Mesh,
-        image::{Image, ImageCreationOption},
+        image::{Image, ImageDisplay, ImageError, ImageSource, ImageStatus, LoadedImage},
     },
     ui::HystElementKey,
 };
 
+#[cfg(feature = "svg")]
+use crate::{GpuImage, meshes::image::Svg};
+
 use super::HystElement;
 
+///Amount of rasterizations of an svg kept for when it's rect gets one of their sizes again
+#[cfg(feature = "svg")]
+const SVG_CACHE_SIZE: usize = 8;
+
 pub struct HystImageCreationOption {
-    pub source: String,
+    pub source: ImageSource,
+    pub display: ImageDisplay,
+    pub max_size: Option<u32>,
+    pub placeholder: Rgba,
     pub rect: Rect,
+    pub parent: Option<HystElementKey>,
     pub style: NodeId,
     pub key: HystElementKey,
 }
@@ -25,23 +38,200 @@ pub struct HystImage {
     parent: Option<HystElementKey>,
     children: Vec<HystElementKey>,
     style: NodeId,
+    ///Display applied once the source is ready. The placeholder always fills the rect
+    display: ImageDisplay,
+    status: ImageStatus,
+    ///Receives the source from the thread decoding it
+    pending: Option<Receiver<Result<LoadedImage, ImageError>>>,
+    ///Biggest width or height the source is uploaded with
+    #[cfg(feature = "svg")]
+    limit: u32,
+    ///Color given to every pixel of svgs
+    #[cfg(feature = "svg")]
+    tint: Option<Rgba>,
+    #[cfg(feature = "svg")]
+    vector: Option<VectorImage>,
+}
+
+///Svg shown by an image, rasterized at the size it's drawn with
+#[cfg(feature = "svg")]
+#[derive(Debug)]
+struct VectorImage {
+    svg: Svg,
+    ///Rasterizations of the svg by their size, the most recently used last
+    cache: Vec<((u32, u32), GpuImage)>,
+    ///Size of the rasterization being shown
+    shown: (u32, u32),
 }
 
 impl HystImage {
+    ///Creates an image which shows the placeholder while it's source is decoded on a background thread.
+    ///Sources which are already decoded are uploaded right away
     pub fn new(core: &mut RenderingCore, options: HystImageCreationOption) -> Self {
-        Self {
-            img: Image::from_configs(
-                core,
-                ImageCreationOption {
-                    rect: options.rect,
-                    source: options.source,
-                },
-            )
-            .unwrap(),
+        let placeholder = [
+            options.placeholder.x(),
+            options.placeholder.y(),
+            options.placeholder.z(),
+            options.placeholder.w(),
+        ]
+        .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+        let limit = core
+            .max_texture_size()
+            .min(options.max_size.unwrap_or(u32::MAX));
+        let mut this = Self {
+            img: Image::new(core, options.rect, (1, 1), &placeholder),
             key: options.key,
-            parent: None,
+            parent: options.parent,
             children: Vec::new(),
             style: options.style,
+            display: options.display,
+            status: ImageStatus::Loading,
+            pending: None,
+            #[cfg(feature = "svg")]
+            limit,
+            #[cfg(feature = "svg")]
+            tint: match options.source {
+                ImageSource::Svg { tint, .. } => tint,
+                _ => None,
+            },
+            #[cfg(feature = "svg")]
+            vector: None,
+        };
+        let source = options.source;
+        if source.is_decoded() {
+            this.finish(core, source.load(limit));
+        } else {
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                //The image may be gone before it finishes, so nobody waits for it
+                let _ = tx.send(source.load(limit));
+            });
+            this.pending = Some(rx);
+        }
+        this
+    }
+
+    ///Uploads the decoded source, or keeps the placeholder if it failed
+    fn finish(&mut self, core: &mut RenderingCore, result: Result<LoadedImage, ImageError>) {
+        self.status = match result {
+            Ok(LoadedImage::Raster(rgba)) => {
+                let image = core.create_image(rgba.dimensions(), &rgba);
+                self.img.set_gpu_image(core, image);
+                self.img.set_display(core, self.display);
+                ImageStatus::Ready
+            }
+            #[cfg(feature = "svg")]
+            Ok(LoadedImage::Vector(svg)) => {
+                self.vector = Some(VectorImage {
+                    svg,
+                    cache: Vec::new(),
+                    shown: (0, 0),
+                });
+                self.img.set_display(core, self.display);
+                self.rasterize(core);
+                ImageStatus::Ready
+            }
+            Err(error) => ImageStatus::Failed(error),
+        };
+    }
+
+    ///Shows the svg rasterized at the size the display gives it inside of the rect, if the image shows one. Rasterizations are
+    ///cached by their size, so resizing back and forth doesn't rasterize it every time.
+    ///Displays with a source rect or a nine-slice rasterize it at it's own size, since they're in texels of it
+    #[cfg(feature = "svg")]
+    fn rasterize(&mut self, core: &mut RenderingCore) {
+        let Some(ref mut vector) = self.vector else {
+            return;
+        };
+        let natural = vector.svg.size();
+        let fitted = match self.display.source_rect.is_some() || self.display.nine_slice.is_some() {
+            true => natural,
+            false => {
+                let rect = self.img.area_buffer().inner();
+                self.display.fitted_size(natural, *rect.size())
+            }
+        };
+        let size = (
+            (fitted.x().ceil() as u32).clamp(1, self.limit),
+            (fitted.y().ceil() as u32).clamp(1, self.limit),
+        );
+        if size == vector.shown {
+            return;
+        }
+        let image = match vector.cache.iter().position(|(cached, _)| *cached == size) {
+            Some(idx) => {
+                let entry = vector.cache.remove(idx);
+                let image = entry.1.clone();
+                vector.cache.push(entry);
+                image
+            }
+            None => {
+                let rgba = vector.svg.rasterize(size, self.tint);
+                let image = core.create_image(size, &rgba);
+                if vector.cache.len() == SVG_CACHE_SIZE {
+                    vector.cache.remove(0);
+                }
+                vector.cache.push((size, image.clone()));
+                image
+            }
+        };
+        vector.shown = size;
+        self.img.set_gpu_image(core, image);
+    }
+
+    #[cfg(not(feature = "svg"))]
+    fn rasterize(&mut self, _core: &mut RenderingCore) {}
+
+    ///Checks whether the thread decoding the source finished, uploading it if so.
+    /// # Returns
+    /// Whether the image stopped loading on this call
+    pub fn poll(&mut self, core: &mut RenderingCore) -> bool {
+        let Some(ref pending) = self.pending else {
+            return false;
+        };
+        let result = match pending.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => Err(ImageError::Interrupted),
+        };
+        self.pending = None;
+        self.finish(core, result);
+        true
+    }
+
+    #[inline]
+    pub fn status(&self) -> &ImageStatus {
+        &self.status
+    }
+
+    #[inline]
+    pub fn is_loading(&self) -> bool {
+        matches!(self.status, ImageStatus::Loading)
+    }
+
+    ///Changes how the image is shown inside of it's rect. While the source isn't ready, it's applied once it is
+    pub fn set_display(&mut self, core: &mut RenderingCore, display: ImageDisplay) {
+        self.display = display;
+        if matches!(self.status, ImageStatus::Ready) {
+            self.img.set_display(core, display);
+            self.rasterize(core);
+        }
+    }
+
+    #[cfg(feature = "svg")]
+    #[inline]
+    pub fn tint(&self) -> Option<Rgba> {
+        self.tint
+    }
+
+    ///Changes the color given to every pixel of the svg, rasterizing it again. Raster images aren't tinted
+    #[cfg(feature = "svg")]
+    pub fn set_tint(&mut self, core: &mut RenderingCore, tint: Option<Rgba>) {
+        self.tint = tint;
+        if let Some(ref mut vector) = self.vector {
+            vector.cache.clear();
+            vector.shown = (0, 0);
+            self.rasterize(core);
         }
     }
 
@@ -73,18 +263,23 @@ impl HystElement for HystImage {
     fn layout(&self) -> NodeId {
         self.style
     }
-    fn resize(
-        &mut self,
-        core: &mut RenderingCore,
-        screen_size: (f32, f32),
-        layout: &taffy::Layout,
-    ) {
-        self.img.resize(core, screen_size, layout);
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        self.img.set_transform(core, transform);
+    }
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        self.img.set_opacity(core, opacity);
+    }
+    fn resize(&mut self, core: &mut RenderingCore, layout: &taffy::Layout) {
+        self.img.resize(core, layout);
+        self.rasterize(core);
     }
     fn children(&self) -> &Vec<HystElementKey> {
         &self.children
     }
-    fn update(&mut self, core: &mut RenderingCore) {}
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
+        &mut self.children
+    }
+    fn update(&mut self, _core: &mut RenderingCore) {}
     fn render(&self, pass: &mut wgpu::RenderPass) {
         self.img.draw(pass);
     }
@@ -0,0 +1,224 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use hyst_math::{Affine, vectors::Vec2f32};
+use taffy::{LengthPercentage, NodeId, Rect as TaffyRect, Style};
+
+use crate::{
+    core::RenderingCore,
+    error::LayoutError,
+    ui::{HystElementKey, HystUi, pulse::Pulse},
+};
+
+use super::{HystElement, HystScroll, HystScrollCreationOption};
+
+///Creates and updates the rows of a HystList. Only the rows visible on the viewport of the list exist, and rows which are scrolled
+///out of view are reused for other items.
+pub trait RowBuilder: 'static {
+    ///Creates the elements of a new row as a child of the given `list`, returning the key of the row.
+    ///Only called when there's no row to be reused.
+    fn create(
+        &mut self,
+        ui: &mut HystUi,
+        list: HystElementKey,
+    ) -> Result<HystElementKey, LayoutError>;
+
+    ///Makes the given `row` show the item at the given `index`, normally by modifying the pulses of it's elements.
+    fn bind(&mut self, ui: &mut HystUi, row: HystElementKey, index: usize);
+}
+
+pub struct HystListCreationOption {
+    pub scroll: HystScrollCreationOption,
+    pub count: Pulse<usize>,
+    pub estimated_height: f32,
+    pub overscan: usize,
+}
+
+///A vertical scroll which shows `count` items, but only has elements for the ones which are visible.
+///The space of the items which aren't visible is reserved by padding the content, based on their measured height or on the estimated one
+///if they were never visible.
+pub struct HystList {
+    scroll: HystScroll,
+    count: Pulse<usize>,
+    estimated_height: f32,
+    ///Amount of rows materialized before and after the visible ones, so fast scrolls don't show empty space
+    overscan: usize,
+    ///Height of each item. Items which were never measured have the estimated height
+    heights: Vec<f32>,
+    ///Rows which are currently materialized, by the index of the item they show
+    rows: BTreeMap<usize, HystElementKey>,
+    ///Rows which aren't showing any item and can be reused
+    pool: Vec<HystElementKey>,
+}
+
+impl HystList {
+    pub fn new(core: &mut RenderingCore, config: HystListCreationOption) -> Self {
+        let mut count = config.count;
+        count.add_dependency(config.scroll.key);
+        let len = *count.read();
+        Self {
+            scroll: HystScroll::new(core, config.scroll),
+            count,
+            estimated_height: config.estimated_height,
+            overscan: config.overscan,
+            heights: vec![config.estimated_height; len],
+            rows: BTreeMap::new(),
+            pool: Vec::new(),
+        }
+    }
+
+    pub fn scroll(&self) -> &HystScroll {
+        &self.scroll
+    }
+
+    pub fn count(&self) -> &Pulse<usize> {
+        &self.count
+    }
+
+    ///Gets the key of the row showing the item at the given `index`, if it's materialized
+    pub fn row_of(&self, index: usize) -> Option<HystElementKey> {
+        self.rows.get(&index).copied()
+    }
+
+    ///Gets the materialized rows, by the index of the item they show
+    pub fn rows(&self) -> &BTreeMap<usize, HystElementKey> {
+        &self.rows
+    }
+
+    ///Gets the distance from the top of the content to the item at the given `index`
+    pub fn offset_of(&self, index: usize) -> f32 {
+        self.heights.iter().take(index).sum()
+    }
+
+    ///Gets the range of the items which are visible on the viewport, including the overscan
+    pub fn visible_range(&self) -> Range<usize> {
+        let top = self.scroll.offset().read().y();
+        let bottom = top + self.scroll.rect().size().y();
+        let (mut start, mut end, mut y) = (self.heights.len(), self.heights.len(), 0.0);
+        for (idx, height) in self.heights.iter().enumerate() {
+            if start == self.heights.len() && y + height > top {
+                start = idx;
+            }
+            if y >= bottom {
+                end = idx;
+                break;
+            }
+            y += height;
+        }
+        start.saturating_sub(self.overscan)..(end + self.overscan).min(self.heights.len())
+    }
+
+    ///Moves the rows which aren't inside the given `range` to the pool, so they can be reused.
+    /// # Returns
+    /// * Whether some row was moved
+    pub(crate) fn recycle_outside(&mut self, range: &Range<usize>) -> bool {
+        let outside = self
+            .rows
+            .keys()
+            .filter(|idx| !range.contains(idx))
+            .copied()
+            .collect::<Vec<_>>();
+        for idx in outside.iter() {
+            if let Some(row) = self.rows.remove(idx) {
+                self.pool.push(row);
+            }
+        }
+        !outside.is_empty()
+    }
+
+    ///Gets the indices inside the given `range` which have no row
+    pub(crate) fn missing(&self, range: Range<usize>) -> Vec<usize> {
+        range.filter(|idx| !self.rows.contains_key(idx)).collect()
+    }
+
+    ///Takes a row which can be reused from the pool
+    pub(crate) fn take_recycled(&mut self) -> Option<HystElementKey> {
+        self.pool.pop()
+    }
+
+    pub(crate) fn insert_row(&mut self, index: usize, row: HystElementKey) {
+        self.rows.insert(index, row);
+    }
+
+    ///Sets the measured `height` of the item at the given `index`.
+    /// # Returns
+    /// * Whether the height changed
+    pub(crate) fn set_height(&mut self, index: usize, height: f32) -> bool {
+        match self.heights.get_mut(index) {
+            Some(current) if (*current - height).abs() > 0.5 => {
+                *current = height;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    ///The style of the content of this list, which reserves the space of the items which have no row as padding
+    pub(crate) fn content_style(&self) -> Style {
+        let style = self.scroll.axis().content_style();
+        let first = self.rows.keys().next().copied().unwrap_or(0);
+        let last = self.rows.keys().next_back().map_or(0, |idx| idx + 1);
+        let top = self.offset_of(first);
+        let bottom = self.heights.iter().skip(last.max(first)).sum();
+        Style {
+            padding: TaffyRect {
+                top: LengthPercentage::length(top),
+                bottom: LengthPercentage::length(bottom),
+                ..style.padding
+            },
+            ..style
+        }
+    }
+}
+
+impl HystElement for HystList {
+    fn id(&self) -> HystElementKey {
+        self.scroll.id()
+    }
+    fn layout(&self) -> NodeId {
+        self.scroll.layout()
+    }
+    fn children_layout(&self) -> NodeId {
+        self.scroll.children_layout()
+    }
+    fn children_offset(&self) -> Vec2f32 {
+        self.scroll.children_offset()
+    }
+    fn clips_children(&self) -> bool {
+        true
+    }
+    fn as_scroll(&self) -> Option<&HystScroll> {
+        Some(&self.scroll)
+    }
+    fn as_scroll_mut(&mut self) -> Option<&mut HystScroll> {
+        Some(&mut self.scroll)
+    }
+    fn children(&self) -> &Vec<HystElementKey> {
+        self.scroll.children()
+    }
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
+        self.scroll.children_mut()
+    }
+    fn resize(&mut self, core: &mut RenderingCore, layout: &taffy::Layout) {
+        self.scroll.resize(core, layout);
+    }
+    fn update(&mut self, core: &mut RenderingCore) {
+        let count = *self.count.read();
+        self.heights.resize(count, self.estimated_height);
+        self.scroll.update(core);
+    }
+    fn render(&self, pass: &mut wgpu::RenderPass) {
+        self.scroll.render(pass);
+    }
+    fn draws_overlay(&self) -> bool {
+        self.scroll.draws_overlay()
+    }
+    fn render_overlay(&self, pass: &mut wgpu::RenderPass) {
+        self.scroll.render_overlay(pass);
+    }
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        self.scroll.set_transform(core, transform);
+    }
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        self.scroll.set_opacity(core, opacity);
+    }
+}
@@ -7,10 +7,29 @@ pub use hbox::*;
 mod image;
 pub use image::*;
 
+mod animation;
+pub use animation::*;
+
+mod canvas;
+pub use canvas::*;
+
+mod painter;
+pub use painter::*;
+
+mod custom;
+pub use custom::*;
+
 mod text;
 pub use text::*;
 
-use taffy::{Layout, NodeId};
+mod scroll;
+pub use scroll::*;
+
+mod list;
+pub use list::*;
+
+use hyst_math::{Affine, vectors::Vec2f32};
+use taffy::{AvailableSpace, Layout, NodeId, Size};
 use wgpu::RenderPass;
 
 use super::{core::RenderingCore, ui::HystElementKey};
@@ -21,15 +40,80 @@ pub trait HystElement: Any {
     ///Retrieves the Id for the layout of this element. Used for positioning and how the element will be displayed on the window
     fn layout(&self) -> NodeId;
 
-    ///Event called when the window is resized.
+    ///Event called when the window is resized. The size of the screen is on the global uniforms of the core.
     /// # Arguments
-    /// * `screen_size` - The new size of the screen.
     /// * `layout` - The new layout computed for this element.
-    fn resize(&mut self, core: &mut RenderingCore, screen_size: (f32, f32), layout: &Layout);
+    fn resize(&mut self, core: &mut RenderingCore, layout: &Layout);
     fn children(&self) -> &Vec<HystElementKey>;
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey>;
+
+    ///Retrieves the Id of the layout the layouts of the children of this element are created in. Normally it's the layout of the element itself.
+    fn children_layout(&self) -> NodeId {
+        self.layout()
+    }
+
+    ///Offset applied to the position of the children of this element, such as the scroll of a HystScroll.
+    fn children_offset(&self) -> Vec2f32 {
+        Vec2f32::new(0.0, 0.0)
+    }
+
+    ///Whether the children of this element are always clipped to it's rect. Elements whose style has a overflow other than visible
+    ///clip their children too.
+    fn clips_children(&self) -> bool {
+        false
+    }
+
+    ///Whether what this element draws is clipped to it's own rect, besides the area defined by it's ancestors. Transformed
+    ///elements are clipped to the bounds of their transformed rect
+    fn clips_content(&self) -> bool {
+        false
+    }
+
+    ///Gets this element as a scroll, if it's content can be scrolled
+    fn as_scroll(&self) -> Option<&HystScroll> {
+        None
+    }
+
+    fn as_scroll_mut(&mut self) -> Option<&mut HystScroll> {
+        None
+    }
+
+    ///Radius of the corners of the rect of this element. Children which are clipped are clipped to the rounded corners.
+    fn corner_radius(&self) -> f32 {
+        0.0
+    }
+
+    ///Measures the size this element requires based on it's content. Only called for elements whose layout was marked as measured,
+    ///such as texts and custom elements, while they have no children.
+    /// # Arguments
+    /// * `known` - The dimensions already defined by the style of this element.
+    /// * `available` - The space available on the parent of this element.
+    fn measure(
+        &mut self,
+        _core: &mut RenderingCore,
+        _known: Size<Option<f32>>,
+        _available: Size<AvailableSpace>,
+    ) -> Size<f32> {
+        Size::ZERO
+    }
 
     ///Used for when the ui requests this Element to update. Normally due to a Pulse dependency update.
     fn update(&mut self, core: &mut RenderingCore);
 
     fn render(&self, pass: &mut RenderPass);
+
+    ///Renders things which must be drawn above the children of this element, such as scrollbars.
+    fn render_overlay(&self, _pass: &mut RenderPass) {}
+
+    ///Applies the given `transform`, in pixels, to what this element draws. It's composed from the transforms of it's ancestors.
+    ///Called after resizing.
+    fn set_transform(&mut self, _core: &RenderingCore, _transform: &Affine) {}
+
+    ///Sets the `opacity` of what this element draws. It's multiplied with the opacities of it's ancestors. Called after resizing.
+    fn set_opacity(&mut self, _core: &RenderingCore, _opacity: f32) {}
+
+    ///Whether this element draws something on `render_overlay`, so it's drawn above the texts of it's children
+    fn draws_overlay(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,416 @@
+use glyphon::Buffer;
+use hyst_math::{
+    Affine, Rect,
+    vectors::{Rgba, Vec2f32},
+};
+use taffy::NodeId;
+
+use crate::{
+    GpuImage,
+    core::RenderingCore,
+    meshes::{
+        shapes::Shapes,
+        sprites::{Sprite, Sprites},
+    },
+    path::{FillRule, Path, StrokeStyle, Triangles},
+    text::TextStyle,
+    ui::HystElementKey,
+};
+
+use super::HystElement;
+
+///Function which draws the content of a painter each time it's dirty
+pub type PaintCallback = Box<dyn FnMut(&mut DrawContext)>;
+
+///How a text drawn on a painter looks
+#[derive(Debug, Clone)]
+pub struct TextPaint {
+    pub font_size: f32,
+    pub color: Rgba,
+    ///Width the lines are wrapped at. If None, they're only broken where the text breaks them
+    pub max_width: Option<f32>,
+    pub style: TextStyle,
+}
+
+impl TextPaint {
+    pub fn new(font_size: f32, color: Rgba) -> Self {
+        Self {
+            font_size,
+            color,
+            max_width: None,
+            style: TextStyle::default(),
+        }
+    }
+    pub fn max_width(mut self, width: f32) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+///Text drawn by a painter, whose glyphs are drawn by the text renderer of it's layer
+pub(crate) struct PaintedText {
+    pub buffer: Buffer,
+    ///Position of the top left corner of the text, relative to the painter
+    pub position: Vec2f32,
+    pub color: Rgba,
+}
+
+///Consecutive commands drawn with the same mesh
+enum PaintBatch {
+    Shapes(Vec<(Triangles, Rgba)>),
+    Sprites(GpuImage, Vec<Sprite>),
+}
+
+///Commands drawn by a painter. Positions are in pixels, relative to the top left corner of the painter, and everything outside of
+///it's rect is clipped. Consecutive shapes are drawn in a single draw call, as are consecutive images of the same texture, while
+///texts are drawn above everything else, along with the other texts of the ui
+pub struct DrawContext<'a> {
+    core: &'a mut RenderingCore,
+    size: Vec2f32,
+    batches: Vec<PaintBatch>,
+    texts: Vec<PaintedText>,
+}
+
+impl<'a> DrawContext<'a> {
+    fn new(core: &'a mut RenderingCore, size: Vec2f32) -> Self {
+        Self {
+            core,
+            size,
+            batches: Vec::new(),
+            texts: Vec::new(),
+        }
+    }
+
+    #[inline]
+    ///Gets the size of the painter, as laid out
+    pub fn size(&self) -> Vec2f32 {
+        self.size
+    }
+
+    #[inline]
+    ///Gets the rendering core, so images drawn by the painter can be created
+    pub fn core(&mut self) -> &mut RenderingCore {
+        self.core
+    }
+
+    fn push_triangles(&mut self, triangles: Triangles, color: Rgba) {
+        if triangles.is_empty() {
+            return;
+        }
+        match self.batches.last_mut() {
+            Some(PaintBatch::Shapes(shapes)) => shapes.push((triangles, color)),
+            _ => self
+                .batches
+                .push(PaintBatch::Shapes(vec![(triangles, color)])),
+        }
+    }
+
+    ///Fills the given `path` with the given `color`, by the given fill `rule`
+    pub fn fill_path(&mut self, path: &Path, rule: FillRule, color: Rgba) {
+        self.push_triangles(path.fill(rule), color);
+    }
+
+    ///Draws the outline of the given `path` with the given `color` and `style`
+    pub fn stroke_path(&mut self, path: &Path, color: Rgba, style: &StrokeStyle) {
+        self.push_triangles(path.stroke(style), color);
+    }
+
+    pub fn fill_rect(&mut self, rect: &Rect, color: Rgba) {
+        self.fill_path(&Path::rect(rect), FillRule::NonZero, color);
+    }
+
+    ///Draws the outline of the given `rect`, centered on it's edges
+    pub fn stroke_rect(&mut self, rect: &Rect, color: Rgba, width: f32) {
+        self.stroke_path(&Path::rect(rect), color, &StrokeStyle::new(width));
+    }
+
+    pub fn fill_rounded_rect(&mut self, rect: &Rect, radius: f32, color: Rgba) {
+        self.fill_path(&Path::rounded_rect(rect, radius), FillRule::NonZero, color);
+    }
+
+    ///Draws the outline of the given `rect` with corners of the given `radius`, centered on it's edges
+    pub fn stroke_rounded_rect(&mut self, rect: &Rect, radius: f32, color: Rgba, width: f32) {
+        self.stroke_path(
+            &Path::rounded_rect(rect, radius),
+            color,
+            &StrokeStyle::new(width),
+        );
+    }
+
+    pub fn fill_circle(&mut self, x: f32, y: f32, radius: f32, color: Rgba) {
+        self.fill_path(&Path::circle(x, y, radius), FillRule::NonZero, color);
+    }
+
+    ///Draws the outline of the given circle, centered on it's edge
+    pub fn stroke_circle(&mut self, x: f32, y: f32, radius: f32, color: Rgba, width: f32) {
+        self.stroke_path(&Path::circle(x, y, radius), color, &StrokeStyle::new(width));
+    }
+
+    ///Draws a line between the given points, with butt caps
+    pub fn line(&mut self, from: Vec2f32, to: Vec2f32, color: Rgba, width: f32) {
+        self.polyline(&[from, to], color, &StrokeStyle::new(width));
+    }
+
+    ///Draws lines joining the given `points` in order, such as the values of a graph
+    pub fn polyline(&mut self, points: &[Vec2f32], color: Rgba, style: &StrokeStyle) {
+        self.stroke_path(&Path::polyline(points), color, style);
+    }
+
+    ///Draws the whole given `image` stretched over the given `rect`
+    pub fn image(&mut self, image: &GpuImage, rect: &Rect) {
+        let size = image.texture().size();
+        let source = Rect::from_xywh(0.0, 0.0, size.width as f32, size.height as f32);
+        self.sprite(image, &source, rect, Rgba::new(1.0, 1.0, 1.0, 1.0));
+    }
+
+    ///Draws the area of the given `image` inside of `source`, in pixels of the image, stretched over the given `rect`.
+    ///It's colors are multiplied by the given `tint`
+    pub fn sprite(&mut self, image: &GpuImage, source: &Rect, rect: &Rect, tint: Rgba) {
+        let size = image.texture().size();
+        let (width, height) = (size.width.max(1) as f32, size.height.max(1) as f32);
+        let sprite = Sprite {
+            rect: *rect,
+            source: Rect::from_xywh(
+                source.position().x() / width,
+                source.position().y() / height,
+                source.size().x() / width,
+                source.size().y() / height,
+            ),
+            tint,
+        };
+        match self.batches.last_mut() {
+            Some(PaintBatch::Sprites(last, sprites)) if last.texture() == image.texture() => {
+                sprites.push(sprite)
+            }
+            _ => self
+                .batches
+                .push(PaintBatch::Sprites(image.clone(), vec![sprite])),
+        }
+    }
+
+    ///Draws the given `text` with it's top left corner at (`x`, `y`).
+    /// # Returns
+    /// The size of the text, so other things can be placed around it
+    pub fn text(&mut self, text: &str, x: f32, y: f32, paint: &TextPaint) -> Vec2f32 {
+        let mut buffer = self.core.create_text_buffer(
+            paint.font_size,
+            paint.font_size * 1.2,
+            text,
+            &paint.style,
+        );
+        let size = self
+            .core
+            .measure(&mut buffer, paint.max_width, false, paint.style.max_lines);
+        self.texts.push(PaintedText {
+            buffer,
+            position: Vec2f32::new(x, y),
+            color: paint.color,
+        });
+        size
+    }
+}
+
+///Mesh drawing a batch of the commands of a painter, kept between paints so it's buffers are reused
+enum PaintMesh {
+    Shapes(Shapes),
+    Sprites(Sprites),
+}
+
+impl PaintMesh {
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        match self {
+            Self::Shapes(shapes) => shapes.set_transform(core, transform),
+            Self::Sprites(sprites) => sprites.set_transform(core, transform),
+        }
+    }
+
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        match self {
+            Self::Shapes(shapes) => shapes.set_opacity(core, opacity),
+            Self::Sprites(sprites) => sprites.set_opacity(core, opacity),
+        }
+    }
+
+    fn draw(&self, pass: &mut wgpu::RenderPass) {
+        match self {
+            Self::Shapes(shapes) => shapes.draw(pass),
+            Self::Sprites(sprites) => sprites.draw(pass),
+        }
+    }
+}
+
+pub struct HystPainterCreationOption {
+    pub paint: PaintCallback,
+    pub parent: Option<HystElementKey>,
+    pub style: NodeId,
+    pub key: HystElementKey,
+}
+
+///Element whose content is drawn by a callback, such as graphs and waveforms. The callback is called again when the painter is
+///resized or asked to repaint, and what it draws is clipped to the rect of the painter
+pub struct HystPainter {
+    paint: PaintCallback,
+    meshes: Vec<PaintMesh>,
+    texts: Vec<PaintedText>,
+    ///Whether the content must be drawn again on the next resize
+    dirty: bool,
+    size: Vec2f32,
+    ///Top left corner of the painter on the screen, which the commands are moved to
+    position: Vec2f32,
+    ///Transform of the painter composed with the ones of it's ancestors
+    world: Affine,
+    opacity: f32,
+    key: HystElementKey,
+    parent: Option<HystElementKey>,
+    children: Vec<HystElementKey>,
+    style: NodeId,
+}
+
+impl HystPainter {
+    ///Creates a painter which is drawn once it's laid out
+    pub fn new(options: HystPainterCreationOption) -> Self {
+        Self {
+            paint: options.paint,
+            meshes: Vec::new(),
+            texts: Vec::new(),
+            dirty: true,
+            size: Vec2f32::default(),
+            position: Vec2f32::default(),
+            world: Affine::IDENTITY,
+            opacity: 1.0,
+            key: options.key,
+            parent: options.parent,
+            children: Vec::new(),
+            style: options.style,
+        }
+    }
+
+    ///Calls the callback and uploads what it draws. Meshes of the previous paint are reused for the batches drawn with the same
+    ///kind of mesh, and the same texture
+    fn repaint(&mut self, core: &mut RenderingCore) {
+        self.dirty = false;
+        let mut context = DrawContext::new(core, self.size);
+        (self.paint)(&mut context);
+        let DrawContext { batches, texts, .. } = context;
+        self.texts = texts;
+        let mut previous = std::mem::take(&mut self.meshes).into_iter();
+        for batch in batches {
+            let reused = previous.next();
+            let mesh = match (batch, reused) {
+                (PaintBatch::Shapes(triangles), Some(PaintMesh::Shapes(mut shapes))) => {
+                    shapes.set_shapes(core, &triangles);
+                    PaintMesh::Shapes(shapes)
+                }
+                (PaintBatch::Shapes(triangles), _) => {
+                    let mut shapes = Shapes::new(core);
+                    shapes.set_shapes(core, &triangles);
+                    PaintMesh::Shapes(shapes)
+                }
+                (PaintBatch::Sprites(image, list), Some(PaintMesh::Sprites(mut sprites)))
+                    if sprites.image().texture() == image.texture() =>
+                {
+                    sprites.set_sprites(core, &list);
+                    PaintMesh::Sprites(sprites)
+                }
+                (PaintBatch::Sprites(image, list), _) => {
+                    let mut sprites = Sprites::new(core, image);
+                    sprites.set_sprites(core, &list);
+                    PaintMesh::Sprites(sprites)
+                }
+            };
+            self.meshes.push(mesh);
+        }
+        let transform = self.transform();
+        for mesh in self.meshes.iter_mut() {
+            mesh.set_transform(core, &transform);
+            mesh.set_opacity(core, self.opacity);
+        }
+    }
+
+    ///Draws the content again on the next resize
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    ///Gets the transform moving the commands to the position of the painter
+    fn transform(&self) -> Affine {
+        let local = Affine::translation(self.position.x(), self.position.y());
+        self.world.then(&local)
+    }
+
+    ///Whether the last paint drew some text
+    pub fn has_texts(&self) -> bool {
+        !self.texts.is_empty()
+    }
+
+    pub(crate) fn texts(&self) -> &[PaintedText] {
+        &self.texts
+    }
+
+    #[inline]
+    ///Gets the position of the top left corner of the painter on the screen, before being transformed
+    pub fn position(&self) -> Vec2f32 {
+        self.position
+    }
+
+    pub fn style(&self) -> NodeId {
+        self.style
+    }
+
+    pub fn parent(&self) -> Option<&HystElementKey> {
+        self.parent.as_ref()
+    }
+}
+
+impl HystElement for HystPainter {
+    fn id(&self) -> HystElementKey {
+        self.key
+    }
+    fn layout(&self) -> NodeId {
+        self.style
+    }
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        self.world = *transform;
+        let transform = self.transform();
+        for mesh in self.meshes.iter_mut() {
+            mesh.set_transform(core, &transform);
+        }
+    }
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        self.opacity = opacity;
+        for mesh in self.meshes.iter_mut() {
+            mesh.set_opacity(core, opacity);
+        }
+    }
+    fn resize(&mut self, core: &mut RenderingCore, layout: &taffy::Layout) {
+        let size = Vec2f32::new(layout.size.width, layout.size.height);
+        self.dirty |= size != self.size;
+        self.size = size;
+        self.position = Vec2f32::new(layout.location.x, layout.location.y);
+        if self.dirty {
+            self.repaint(core);
+        }
+    }
+    fn clips_content(&self) -> bool {
+        true
+    }
+    fn children(&self) -> &Vec<HystElementKey> {
+        &self.children
+    }
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
+        &mut self.children
+    }
+    fn update(&mut self, _core: &mut RenderingCore) {
+        //The callback needs the size of the painter, so it's called once it's laid out again
+        self.invalidate();
+    }
+    fn render(&self, pass: &mut wgpu::RenderPass) {
+        for mesh in self.meshes.iter() {
+            mesh.draw(pass);
+        }
+    }
+}
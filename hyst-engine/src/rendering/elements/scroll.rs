@@ -0,0 +1,391 @@
+use std::time::Instant;
+
+use hyst_math::{
+    Affine, Rect,
+    vectors::{Rgba, Vec2f32},
+};
+use taffy::{FlexDirection, NodeId, Overflow, Point, Style};
+use winit::event::{MouseScrollDelta, TouchPhase};
+
+use crate::{
+    core::RenderingCore,
+    meshes::quads::Quads,
+    ui::{HystElementKey, pulse::Pulse},
+};
+
+use super::HystElement;
+
+///Amount of pixels scrolled by each line of a mouse wheel
+const LINE_HEIGHT: f32 = 40.0;
+///Rate at which the velocity of a scroll decays per second when gliding
+const DECELERATION: f32 = 4.0;
+///Velocity, in pixels per second, under which a gliding scroll stops
+const MIN_VELOCITY: f32 = 10.0;
+///Thickness of the scrollbars, in pixels
+const SCROLLBAR_THICKNESS: f32 = 6.0;
+
+///The axis in which the content of a scroll can be moved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScrollAxis {
+    #[default]
+    Vertical,
+    Horizontal,
+    Both,
+}
+
+impl ScrollAxis {
+    fn vertical(&self) -> bool {
+        matches!(self, Self::Vertical | Self::Both)
+    }
+    fn horizontal(&self) -> bool {
+        matches!(self, Self::Horizontal | Self::Both)
+    }
+    ///Removes the parts of the given `delta` which aren't on this axis
+    fn filter(&self, delta: Vec2f32) -> Vec2f32 {
+        Vec2f32::new(
+            if self.horizontal() { delta.x() } else { 0.0 },
+            if self.vertical() { delta.y() } else { 0.0 },
+        )
+    }
+
+    ///The style of the viewport of a scroll on this axis, based on the style defined by the user
+    pub(crate) fn viewport_style(&self, style: &Style) -> Style {
+        Style {
+            overflow: Point {
+                x: if self.horizontal() {
+                    Overflow::Scroll
+                } else {
+                    Overflow::Hidden
+                },
+                y: if self.vertical() {
+                    Overflow::Scroll
+                } else {
+                    Overflow::Hidden
+                },
+            },
+            //Scrollbars are drawn above the content, so no space is reserved for them
+            scrollbar_width: 0.0,
+            flex_direction: match self {
+                Self::Horizontal => FlexDirection::Row,
+                _ => FlexDirection::Column,
+            },
+            ..style.clone()
+        }
+    }
+
+    ///The style of the node the children of a scroll on this axis are laid out in. It's never shrunk, so it grows with it's children.
+    pub(crate) fn content_style(&self) -> Style {
+        Style {
+            flex_direction: match self {
+                Self::Horizontal => FlexDirection::Row,
+                _ => FlexDirection::Column,
+            },
+            flex_shrink: 0.0,
+            align_self: match self {
+                Self::Both => Some(taffy::AlignSelf::FlexStart),
+                _ => None,
+            },
+            ..Default::default()
+        }
+    }
+}
+
+pub struct HystScrollCreationOption {
+    pub key: HystElementKey,
+    pub viewport: NodeId,
+    pub content: NodeId,
+    pub axis: ScrollAxis,
+    pub offset: Pulse<Vec2f32>,
+    pub inertia: bool,
+    pub scrollbar: Option<Rgba>,
+}
+
+///An element whose children are laid out in an unbounded axis and clipped to it's rect. The content is moved by the offset of the scroll.
+pub struct HystScroll {
+    key: HystElementKey,
+    viewport: NodeId,
+    content: NodeId,
+    children: Vec<HystElementKey>,
+    axis: ScrollAxis,
+    ///Amount of pixels the content is scrolled by. Always between zero and the size of the content minus the size of the viewport.
+    offset: Pulse<Vec2f32>,
+    inertia: bool,
+    ///Velocity of the content, in pixels per second, used when inertia is enabled
+    velocity: Vec2f32,
+    gliding: bool,
+    last_event: Option<Instant>,
+    rect: Rect,
+    content_size: Vec2f32,
+    scrollbar: Option<Rgba>,
+    bars: Quads,
+}
+
+impl HystScroll {
+    pub fn new(core: &mut RenderingCore, config: HystScrollCreationOption) -> Self {
+        let mut offset = config.offset;
+        offset.add_dependency(config.key);
+        Self {
+            key: config.key,
+            viewport: config.viewport,
+            content: config.content,
+            children: Vec::new(),
+            axis: config.axis,
+            offset,
+            inertia: config.inertia,
+            velocity: Vec2f32::default(),
+            gliding: false,
+            last_event: None,
+            rect: Rect::from_xywh(0.0, 0.0, 0.0, 0.0),
+            content_size: Vec2f32::default(),
+            scrollbar: config.scrollbar,
+            bars: Quads::new(core),
+        }
+    }
+
+    pub fn axis(&self) -> ScrollAxis {
+        self.axis
+    }
+
+    pub fn offset(&self) -> &Pulse<Vec2f32> {
+        &self.offset
+    }
+
+    ///The rect of the viewport of this scroll, in screen coordinates
+    pub fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    ///The size of the content of this scroll
+    pub fn content_size(&self) -> Vec2f32 {
+        self.content_size
+    }
+
+    ///The maximum offset the content can be scrolled by
+    pub fn max_offset(&self) -> Vec2f32 {
+        self.axis
+            .filter(self.content_size - *self.rect.size())
+            .max(&Vec2f32::default())
+    }
+
+    fn clamp(&self, offset: Vec2f32) -> Vec2f32 {
+        offset.max(&Vec2f32::default()).min(&self.max_offset())
+    }
+
+    ///Moves the content by the given `delta`, in pixels.
+    /// # Returns
+    /// * Whether the offset of this scroll changed
+    pub fn scroll_by(&mut self, delta: Vec2f32) -> bool {
+        let current = self.offset.cloned();
+        let target = self.clamp(current + self.axis.filter(delta));
+        if target == current {
+            return false;
+        }
+        self.offset.mutate(|mut offset| *offset = target);
+        true
+    }
+
+    ///Scrolls the minimum amount required for the given `target` rect, in screen coordinates, to be fully visible.
+    /// # Returns
+    /// * Whether the offset of this scroll changed
+    pub fn scroll_into_view(&mut self, target: &Rect) -> bool {
+        self.stop();
+        let current = self.offset.cloned();
+        let fit = |offset: f32, start: f32, len: f32, viewport_start: f32, viewport_len: f32| {
+            //Position of the target relative to the start of the content
+            let start = start - viewport_start + offset;
+            if start < offset || len > viewport_len {
+                start
+            } else if start + len > offset + viewport_len {
+                start + len - viewport_len
+            } else {
+                offset
+            }
+        };
+        let (position, size) = (self.rect.position(), self.rect.size());
+        let delta = Vec2f32::new(
+            fit(
+                current.x(),
+                target.position().x(),
+                target.size().x(),
+                position.x(),
+                size.x(),
+            ),
+            fit(
+                current.y(),
+                target.position().y(),
+                target.size().y(),
+                position.y(),
+                size.y(),
+            ),
+        ) - current;
+        self.scroll_by(delta)
+    }
+
+    ///Scrolls the content to the given `offset`, clamped to the size of the content.
+    /// # Returns
+    /// * Whether the offset of this scroll changed
+    pub fn scroll_to_offset(&mut self, offset: Vec2f32) -> bool {
+        self.stop();
+        let current = self.offset.cloned();
+        self.scroll_by(offset - current)
+    }
+
+    ///Stops the gliding of this scroll
+    pub fn stop(&mut self) {
+        self.gliding = false;
+        self.velocity = Vec2f32::default();
+    }
+
+    ///Handles a mouse wheel or touchpad scroll event.
+    /// # Returns
+    /// * Whether the event was consumed by this scroll
+    pub fn on_wheel(&mut self, delta: MouseScrollDelta, phase: TouchPhase) -> bool {
+        let now = Instant::now();
+        let elapsed = self
+            .last_event
+            .map_or(0.0, |last| now.duration_since(last).as_secs_f32());
+        self.last_event = Some(now);
+        match delta {
+            MouseScrollDelta::LineDelta(x, y) => {
+                let delta = Vec2f32::new(-x, -y) * LINE_HEIGHT;
+                if !self.inertia {
+                    return self.scroll_by(delta);
+                }
+                //Lines are glided through, so the distance travelled by the velocity until it stops is the delta
+                let before = self.velocity;
+                self.velocity = self.velocity + self.axis.filter(delta) * DECELERATION;
+                self.gliding = true;
+                self.tick(0.0) || before != self.velocity
+            }
+            MouseScrollDelta::PixelDelta(position) => {
+                let delta = Vec2f32::new(-position.x as f32, -position.y as f32);
+                if self.inertia {
+                    match phase {
+                        TouchPhase::Started => self.stop(),
+                        TouchPhase::Moved if elapsed > 0.0 => {
+                            self.velocity = self.velocity * 0.2 + delta * (0.8 / elapsed);
+                        }
+                        TouchPhase::Ended => {
+                            self.gliding = true;
+                            return self.velocity != Vec2f32::default();
+                        }
+                        _ => {}
+                    }
+                }
+                self.scroll_by(delta)
+            }
+        }
+    }
+
+    ///Moves the content based on it's velocity, `dt` seconds after the last tick.
+    /// # Returns
+    /// * Whether this scroll is still gliding
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if !self.gliding {
+            return false;
+        }
+        let moved = self.scroll_by(self.velocity * dt);
+        self.velocity = self.velocity * (-DECELERATION * dt).exp();
+        let speed = self.velocity.x().hypot(self.velocity.y());
+        if (!moved && dt > 0.0) || speed < MIN_VELOCITY {
+            self.stop();
+        }
+        self.gliding
+    }
+
+    ///Recreates the scrollbars based on the current offset
+    fn refresh_bars(&mut self, core: &RenderingCore) {
+        let Some(color) = self.scrollbar else {
+            return;
+        };
+        let (max, offset) = (self.max_offset(), self.offset.cloned());
+        let (position, size) = (*self.rect.position(), *self.rect.size());
+        let mut bars = Vec::new();
+        if max.y() > 0.0 {
+            let len = (size.y() * size.y() / self.content_size.y()).max(SCROLLBAR_THICKNESS * 2.0);
+            bars.push((
+                Rect::from_xywh(
+                    position.x() + size.x() - SCROLLBAR_THICKNESS,
+                    position.y() + (size.y() - len) * offset.y() / max.y(),
+                    SCROLLBAR_THICKNESS,
+                    len,
+                ),
+                color,
+            ));
+        }
+        if max.x() > 0.0 {
+            let len = (size.x() * size.x() / self.content_size.x()).max(SCROLLBAR_THICKNESS * 2.0);
+            bars.push((
+                Rect::from_xywh(
+                    position.x() + (size.x() - len) * offset.x() / max.x(),
+                    position.y() + size.y() - SCROLLBAR_THICKNESS,
+                    len,
+                    SCROLLBAR_THICKNESS,
+                ),
+                color,
+            ));
+        }
+        self.bars.set_quads(core, &bars);
+    }
+}
+
+impl HystElement for HystScroll {
+    fn id(&self) -> HystElementKey {
+        self.key
+    }
+    fn layout(&self) -> NodeId {
+        self.viewport
+    }
+    fn children_layout(&self) -> NodeId {
+        self.content
+    }
+    fn children_offset(&self) -> Vec2f32 {
+        Vec2f32::default() - self.offset.cloned()
+    }
+    fn clips_children(&self) -> bool {
+        true
+    }
+    fn as_scroll(&self) -> Option<&HystScroll> {
+        Some(self)
+    }
+    fn as_scroll_mut(&mut self) -> Option<&mut HystScroll> {
+        Some(self)
+    }
+    fn children(&self) -> &Vec<HystElementKey> {
+        &self.children
+    }
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
+        &mut self.children
+    }
+    fn resize(&mut self, core: &mut RenderingCore, layout: &taffy::Layout) {
+        self.rect = Rect::from_xywh(
+            layout.location.x,
+            layout.location.y,
+            layout.size.width,
+            layout.size.height,
+        );
+        self.content_size = Vec2f32::new(layout.content_size.width, layout.content_size.height);
+        let clamped = self.clamp(self.offset.cloned());
+        if clamped != self.offset.cloned() {
+            //The content shrunk, so the offset is fixed without requesting another update
+            unsafe { *self.offset.write() = clamped };
+        }
+        self.refresh_bars(core);
+    }
+    fn update(&mut self, core: &mut RenderingCore) {
+        self.refresh_bars(core);
+    }
+    fn render(&self, _: &mut wgpu::RenderPass) {}
+    fn draws_overlay(&self) -> bool {
+        self.scrollbar.is_some()
+    }
+    fn render_overlay(&self, pass: &mut wgpu::RenderPass) {
+        self.bars.draw(pass);
+    }
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        self.bars.set_transform(core, transform);
+    }
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        self.bars.set_opacity(core, opacity);
+    }
+}
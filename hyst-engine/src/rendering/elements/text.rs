@@ -1,20 +1,59 @@
-use hyst_math::vectors::{Vec2f32, Vec4f32};
-use taffy::NodeId;
+use std::ops::Range;
+
+use hyst_math::{
+    Affine, Rect,
+    vectors::{Vec2f32, Vec4f32},
+};
+use taffy::{AvailableSpace, NodeId, Size};
 
 use crate::{
     core::RenderingCore,
-    meshes::text::Text,
+    meshes::{quads::Quads, text::Text},
+    text::{RichText, TextStyle},
     ui::{HystElementKey, pulse::Pulse},
 };
 
 use super::HystElement;
 
+///The content of a HystText. Either plain text, drawn with the attributes of the HystText, or spans with their own attributes.
+#[derive(Clone)]
+pub enum TextContent {
+    Plain(Pulse<String>),
+    Rich(Pulse<RichText>),
+}
+
+impl TextContent {
+    fn add_dependency(&mut self, key: HystElementKey) {
+        match self {
+            Self::Plain(pulse) => pulse.add_dependency(key),
+            Self::Rich(pulse) => pulse.add_dependency(key),
+        }
+    }
+}
+
+impl From<Pulse<String>> for TextContent {
+    fn from(value: Pulse<String>) -> Self {
+        Self::Plain(value)
+    }
+}
+impl From<Pulse<RichText>> for TextContent {
+    fn from(value: Pulse<RichText>) -> Self {
+        Self::Rich(value)
+    }
+}
+
 pub struct HystText {
     key: HystElementKey,
     layout: NodeId,
     inner: Text,
-    content: Pulse<String>,
+    content: TextContent,
     color: Pulse<Vec4f32>,
+    ///Selection highlights, underlines and strikethroughs of rich texts
+    decorations: Quads,
+    ///Range of byte offsets of the selected characters
+    selection: Option<Range<usize>>,
+    ///Transform applied to the text along with the ones of it's ancestors, as computed on the last resize
+    world: Affine,
     children: Vec<HystElementKey>,
 }
 
@@ -23,27 +62,39 @@ pub struct TextCreationOption {
     pub(crate) layout: NodeId,
     pub(crate) font_size: f32,
     pub(crate) line_height: f32,
-    pub(crate) position: Vec2f32,
-    pub(crate) content: Pulse<String>,
+    pub(crate) content: TextContent,
     pub(crate) color: Pulse<Vec4f32>,
+    pub(crate) style: TextStyle,
 }
 
 impl HystText {
     pub fn new(core: &mut RenderingCore, config: TextCreationOption) -> Self {
         let mut content = config.content;
         content.add_dependency(config.key);
-        let text = Text::new(
-            core,
-            config.position,
-            &content.read(),
-            config.font_size,
-            config.line_height,
-        );
+        let text = match content {
+            TextContent::Plain(ref pulse) => Text::new(
+                core,
+                &pulse.read(),
+                config.font_size,
+                config.line_height,
+                config.style,
+            ),
+            TextContent::Rich(ref pulse) => Text::rich(
+                core,
+                &pulse.read(),
+                config.font_size,
+                config.line_height,
+                config.style,
+            ),
+        };
         Self {
             children: Vec::new(),
             key: config.key,
             layout: config.layout,
             color: config.color,
+            decorations: Quads::new(core),
+            selection: None,
+            world: Affine::default(),
             inner: text,
             content,
         }
@@ -51,20 +102,119 @@ impl HystText {
     pub fn inner(&self) -> &Text {
         &self.inner
     }
+    pub fn content(&self) -> &TextContent {
+        &self.content
+    }
     pub fn color(&self) -> &Pulse<Vec4f32> {
         &self.color
     }
     pub fn color_mut(&mut self) -> &mut Pulse<Vec4f32> {
         &mut self.color
     }
+
+    ///Sets the transform of the text on the screen, used for converting screen positions. It differs from the one it's drawn with
+    ///when it's inside of a cached layer
+    pub(crate) fn set_world(&mut self, world: Affine) {
+        self.world = world;
+    }
+
+    ///Converts the given screen position into the coordinates of the layout of this text, undoing it's transforms.
+    ///None if they collapse the text
+    fn to_local(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let local = self.world.inverse()?.apply(Vec2f32::new(x, y));
+        Some((local.x(), local.y()))
+    }
+
+    ///Gets the byte offset of the character under the given screen position
+    pub fn hit(&self, x: f32, y: f32) -> Option<usize> {
+        let (x, y) = self.to_local(x, y)?;
+        self.inner.hit(x, y)
+    }
+
+    ///Gets the rect of the caret placed before the character at the given byte `offset`, in screen coordinates. When the text is
+    ///rotated or skewed, it's the smallest rect aligned to the axes which contains the caret
+    pub fn caret_rect(&self, offset: usize, width: f32) -> Option<Rect> {
+        let rect = self.inner.caret_rect(offset, width)?;
+        Some(self.world.bounds_of(&rect))
+    }
+
+    ///Gets the rects that highlight the characters inside the given `range` of byte offsets, in screen coordinates. When the text
+    ///is rotated or skewed, they're the smallest rects aligned to the axes which contain the highlights
+    pub fn selection_rects(&self, range: Range<usize>) -> Vec<Rect> {
+        self.inner
+            .selection_rects(range)
+            .iter()
+            .map(|rect| self.world.bounds_of(rect))
+            .collect()
+    }
+
+    pub fn selection(&self) -> Option<&Range<usize>> {
+        self.selection.as_ref()
+    }
+
+    ///Selects the characters inside the given `range` of byte offsets, highlighting them. None clears the selection.
+    pub fn set_selection(&mut self, core: &mut RenderingCore, range: Option<Range<usize>>) {
+        self.selection = range;
+        self.refresh_quads(core);
+    }
+
+    ///Selects the characters between the given screen positions, such as the start and the end of a mouse drag.
+    pub fn select_between(&mut self, core: &mut RenderingCore, start: (f32, f32), end: (f32, f32)) {
+        let range = self
+            .to_local(start.0, start.1)
+            .zip(self.to_local(end.0, end.1))
+            .and_then(|(start, end)| self.inner.range_between(start, end));
+        self.set_selection(core, range);
+    }
+
+    ///Gets the content of the selected characters, so it can be copied.
+    pub fn selected_text(&self) -> Option<String> {
+        let range = self.selection.clone()?;
+        Some(self.inner.text_in(range))
+    }
+
+    ///Recreates the selection highlights and decorations of this text. They're in the coordinates of the layout, since the quads
+    ///are transformed when drawn
+    fn refresh_quads(&mut self, core: &mut RenderingCore) {
+        let mut quads = match self.selection {
+            Some(ref range) => {
+                let color = self.inner.style().selection_color;
+                self.inner
+                    .selection_rects(range.clone())
+                    .into_iter()
+                    .map(|rect| (rect, color))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        if let TextContent::Rich(ref pulse) = self.content {
+            quads.append(&mut self.inner.decorations(&pulse.read(), self.color.cloned()));
+        }
+        self.decorations.set_quads(core, &quads);
+    }
+
+    ///Gets the link id of the span at the given screen position. Always None for plain texts.
+    pub fn link_at(&self, x: f32, y: f32) -> Option<u32> {
+        let (x, y) = self.to_local(x, y)?;
+        match self.content {
+            TextContent::Plain(_) => None,
+            TextContent::Rich(ref pulse) => self.inner.link_at(&pulse.read(), x, y),
+        }
+    }
 }
 
 impl HystElement for HystText {
     fn children(&self) -> &Vec<HystElementKey> {
         &self.children
     }
+    fn children_mut(&mut self) -> &mut Vec<HystElementKey> {
+        &mut self.children
+    }
     fn update(&mut self, core: &mut RenderingCore) {
-        core.set_text(&mut self.inner.buffer_mut(), &self.content.read());
+        match self.content {
+            TextContent::Plain(ref pulse) => self.inner.set_text(core, &pulse.read()),
+            TextContent::Rich(ref pulse) => self.inner.set_rich_text(core, &pulse.read()),
+        }
     }
     fn id(&self) -> HystElementKey {
         self.key
@@ -72,14 +222,26 @@ impl HystElement for HystText {
     fn layout(&self) -> taffy::NodeId {
         self.layout
     }
-    fn resize(
+    fn measure(
         &mut self,
         core: &mut RenderingCore,
-        screen_size: (f32, f32),
-        layout: &taffy::Layout,
-    ) {
+        known: Size<Option<f32>>,
+        available: Size<AvailableSpace>,
+    ) -> Size<f32> {
+        self.inner.measure(core, known, available)
+    }
+    fn resize(&mut self, core: &mut RenderingCore, layout: &taffy::Layout) {
+        self.inner.resize(core, layout);
+        self.refresh_quads(core);
+    }
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        self.decorations.set_transform(core, transform);
+    }
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        self.decorations.set_opacity(core, opacity);
     }
     fn render(&self, pass: &mut wgpu::RenderPass) {
-        //Not implemented by the text itself
+        //The glyphs are drawn by the text renderer, only selections and decorations are drawn by the text itself
+        self.decorations.draw(pass);
     }
 }
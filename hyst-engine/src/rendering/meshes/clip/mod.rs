@@ -0,0 +1,95 @@
+mod shader;
+pub use shader::*;
+
+use crate::{
+    AbstractBuffer, BindGroupConfig, BufferType,
+    core::RenderingCore,
+    meshes::container::ContainerInput,
+    shaders::{HystShader, ShaderCreationOptions},
+};
+use hyst_math::{Affine, Rect};
+
+///Rounded rect drawn on the stencil buffer, so the children of an element are clipped to it's rounded corners.
+///The stencil value of a pixel is the amount of rounded clips it's inside of, elements are only drawn where it equals their own amount.
+#[derive(Debug)]
+pub struct ClipMask {
+    push: ClipShader<false>,
+    pop: ClipShader<true>,
+    vertices: wgpu::Buffer,
+    index: wgpu::Buffer,
+    rect_buf: AbstractBuffer<Rect>,
+    radius: AbstractBuffer<[f32; 4]>,
+    transform: AbstractBuffer<Affine>,
+}
+
+impl ClipMask {
+    pub fn new(core: &mut RenderingCore) -> Self {
+        let rect_buf = AbstractBuffer::new(
+            core,
+            Rect::from_xywh(0.0, 0.0, 0.0, 0.0),
+            BufferType::Uniform,
+        );
+        let radius = AbstractBuffer::new(core, [0.0; 4], BufferType::Uniform);
+        let transform = AbstractBuffer::new(core, Affine::IDENTITY, BufferType::Uniform);
+        let source = std::fs::read_to_string("./shaders/clip.wgsl").unwrap();
+        let config = || {
+            BindGroupConfig::new()
+                .uniform(
+                    0,
+                    wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    rect_buf.inner_buffer(),
+                )
+                .uniform(1, wgpu::ShaderStages::FRAGMENT, radius.inner_buffer())
+                .uniform(2, wgpu::ShaderStages::VERTEX, transform.inner_buffer())
+        };
+        let push =
+            core.create_shader(ShaderCreationOptions::new("clip_push", &source).group(config()));
+        let pop =
+            core.create_shader(ShaderCreationOptions::new("clip_pop", &source).group(config()));
+        Self {
+            push,
+            pop,
+            vertices: core.create_vertex_buffer(
+                &[
+                    ContainerInput::transparent(-1.0, 1.0),
+                    ContainerInput::transparent(1.0, 1.0),
+                    ContainerInput::transparent(-1.0, -1.0),
+                    ContainerInput::transparent(1.0, -1.0),
+                ],
+                None,
+            ),
+            index: core.create_index_buffer(&[0, 1, 2, 2, 1, 3], None),
+            rect_buf,
+            radius,
+            transform,
+        }
+    }
+
+    ///Moves the mask to the given `rect`, with corners of the given `radius`, transformed by the given `transform`
+    pub fn resize(&mut self, core: &RenderingCore, rect: Rect, radius: f32, transform: &Affine) {
+        self.rect_buf.write_with(core, rect);
+        self.radius.write_with(core, [radius; 4]);
+        self.transform.write_with(core, *transform);
+    }
+
+    fn draw(&self, pass: &mut wgpu::RenderPass, shader: &dyn HystShader, reference: u32) {
+        pass.set_pipeline(shader.pipeline());
+        for (idx, bind_group) in shader.bind_groups().iter().enumerate() {
+            pass.set_bind_group(idx as u32, bind_group, &[]);
+        }
+        pass.set_stencil_reference(reference);
+        pass.set_index_buffer(self.index.slice(..), wgpu::IndexFormat::Uint16);
+        pass.set_vertex_buffer(0, self.vertices.slice(..));
+        pass.draw_indexed(0..6, 0, 0..1);
+    }
+
+    ///Adds a clip level inside of the rounded rect. `depth` is the amount of rounded clips the rect itself is inside of.
+    pub fn push(&self, pass: &mut wgpu::RenderPass, depth: u32) {
+        self.draw(pass, &self.push, depth);
+    }
+
+    ///Removes the clip level added by `push` with the same `depth`, so elements drawn after it aren't clipped.
+    pub fn pop(&self, pass: &mut wgpu::RenderPass, depth: u32) {
+        self.draw(pass, &self.pop, depth + 1);
+    }
+}
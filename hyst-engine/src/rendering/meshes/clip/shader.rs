@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::meshes::container::ContainerInput;
+use crate::shaders::{HystConstructor, HystShader, ShaderInput, StencilMode};
+
+///Shader which draws a rounded rect on the stencil buffer. When `POP` is false, it adds a clip level inside of the rect, otherwise removes it.
+#[derive(Debug)]
+pub struct ClipShader<const POP: bool> {
+    module: Arc<wgpu::ShaderModule>,
+    bindgroups: Vec<wgpu::BindGroup>,
+    pipeline: std::sync::Arc<wgpu::RenderPipeline>,
+}
+
+impl<const POP: bool> HystConstructor for ClipShader<POP> {
+    fn new(
+        module: Arc<wgpu::ShaderModule>,
+        bindgroups: Vec<wgpu::BindGroup>,
+        _: Vec<wgpu::BindGroupLayout>,
+        pipeline: std::sync::Arc<wgpu::RenderPipeline>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            module,
+            bindgroups,
+            pipeline,
+        }
+    }
+
+    fn name() -> &'static str {
+        match POP {
+            true => "clip_pop",
+            false => "clip_push",
+        }
+    }
+    fn shader_inputs() -> Vec<wgpu::VertexBufferLayout<'static>> {
+        vec![ContainerInput::LAYOUT]
+    }
+    fn stencil() -> StencilMode {
+        match POP {
+            true => StencilMode::PopClip,
+            false => StencilMode::PushClip,
+        }
+    }
+}
+
+impl<const POP: bool> HystShader for ClipShader<POP> {
+    fn module(&self) -> &Arc<wgpu::ShaderModule> {
+        &self.module
+    }
+    fn pipeline(&self) -> &wgpu::RenderPipeline {
+        &self.pipeline
+    }
+    fn bind_groups(&self) -> &[wgpu::BindGroup] {
+        &self.bindgroups
+    }
+}
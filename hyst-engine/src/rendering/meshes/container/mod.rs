@@ -3,13 +3,13 @@ pub use input::*;
 use taffy::{Point, Size};
 mod shader;
 use crate::{
-    AbstractBuffer, BindGroupAndLayoutConfig, BufferType,
+    AbstractBuffer, BindGroupConfig, BufferType,
     background::Background,
     core::RenderingCore,
     meshes::Mesh,
-    shaders::{HystShader, ShaderRenderMethod},
+    shaders::{HystShader, ShaderCreationOptions},
 };
-use hyst_math::Rect;
+use hyst_math::{Affine, Rect};
 pub use shader::*;
 
 #[derive(Debug)]
@@ -19,67 +19,90 @@ pub struct Container {
     index: wgpu::Buffer,
     indices_len: u32,
     rect_buf: AbstractBuffer<Rect>,
-    screen_size: AbstractBuffer<[f32; 2]>,
+    ///Radius of each corner, clockwise from the top left one
+    radius: AbstractBuffer<[f32; 4]>,
+    transform: AbstractBuffer<Affine>,
+    opacity: AbstractBuffer<f32>,
 }
 
 impl Container {
-    pub fn new(core: &mut RenderingCore, bg: Background, rect: Rect) -> Self {
-        let size = core.size();
+    pub fn new(core: &mut RenderingCore, bg: Background, rect: Rect, radius: f32) -> Self {
         let rect_buf = AbstractBuffer::new(core, rect, BufferType::Uniform);
-        let screen_size =
-            AbstractBuffer::new(core, [size.0 as f32, size.1 as f32], BufferType::Uniform);
-        let vertices = AbstractBuffer::new(
-            core,
-            match bg {
-                Background::Transparent => [
-                    ContainerInput::transparent(-1.0, 1.0),
-                    ContainerInput::transparent(1.0, 1.0),
-                    ContainerInput::transparent(-1.0, -1.0),
-                    ContainerInput::transparent(1.0, -1.0),
-                ],
-                Background::Solid(rgba) => [
-                    ContainerInput::solid(-1.0, 1.0, rgba),
-                    ContainerInput::solid(1.0, 1.0, rgba),
-                    ContainerInput::solid(-1.0, -1.0, rgba),
-                    ContainerInput::solid(1.0, -1.0, rgba),
-                ],
-                Background::Gradient {
-                    top_left,
-                    top_right,
-                    bottom_left,
-                    bottom_right,
-                } => [
-                    ContainerInput::solid(-1.0, 1.0, top_left),
-                    ContainerInput::solid(1.0, 1.0, top_right),
-                    ContainerInput::solid(-1.0, -1.0, bottom_left),
-                    ContainerInput::solid(1.0, -1.0, bottom_right),
-                ],
-            },
-            BufferType::Vertex,
-        );
+        let radius = AbstractBuffer::new(core, [radius; 4], BufferType::Uniform);
+        let vertices = AbstractBuffer::new(core, Self::vertices_of(&bg), BufferType::Vertex);
+        let transform = AbstractBuffer::new(core, Affine::IDENTITY, BufferType::Uniform);
+        let opacity = AbstractBuffer::new(core, 1.0, BufferType::Uniform);
         Self {
             indices_len: 6,
             vertices,
             index: core.create_index_buffer(&[0, 1, 2, 2, 1, 3], None),
-            shader: core.create_shader(crate::shaders::ShaderCreationOptions {
-                source: &std::fs::read_to_string("./shaders/container.wgsl").unwrap(),
-                bind_group_configs: vec![vec![
-                    BindGroupAndLayoutConfig::Uniform(
-                        wgpu::ShaderStages::VERTEX,
-                        screen_size.inner_buffer(),
-                    ),
-                    BindGroupAndLayoutConfig::Uniform(
-                        wgpu::ShaderStages::VERTEX,
-                        rect_buf.inner_buffer(),
-                    ),
-                ]],
-                rendering_style: ShaderRenderMethod::TriangleCcwBack,
-                name: "container".to_string(),
-            }),
-            screen_size,
+            shader: core.create_shader(
+                ShaderCreationOptions::new(
+                    "container",
+                    &std::fs::read_to_string("./shaders/container.wgsl").unwrap(),
+                )
+                .group(
+                    BindGroupConfig::new()
+                        .uniform(
+                            0,
+                            wgpu::ShaderStages::VERTEX_FRAGMENT,
+                            rect_buf.inner_buffer(),
+                        )
+                        .uniform(1, wgpu::ShaderStages::FRAGMENT, radius.inner_buffer())
+                        .uniform(2, wgpu::ShaderStages::VERTEX, transform.inner_buffer())
+                        .uniform(3, wgpu::ShaderStages::FRAGMENT, opacity.inner_buffer()),
+                ),
+            ),
             rect_buf,
+            radius,
+            transform,
+            opacity,
         }
     }
+
+    ///Gets the vertices of a container filled with the given `bg`
+    fn vertices_of(bg: &Background) -> [ContainerInput; 4] {
+        match *bg {
+            Background::Transparent => [
+                ContainerInput::transparent(-1.0, 1.0),
+                ContainerInput::transparent(1.0, 1.0),
+                ContainerInput::transparent(-1.0, -1.0),
+                ContainerInput::transparent(1.0, -1.0),
+            ],
+            Background::Solid(rgba) => [
+                ContainerInput::solid(-1.0, 1.0, rgba),
+                ContainerInput::solid(1.0, 1.0, rgba),
+                ContainerInput::solid(-1.0, -1.0, rgba),
+                ContainerInput::solid(1.0, -1.0, rgba),
+            ],
+            Background::Gradient {
+                top_left,
+                top_right,
+                bottom_left,
+                bottom_right,
+            } => [
+                ContainerInput::solid(-1.0, 1.0, top_left),
+                ContainerInput::solid(1.0, 1.0, top_right),
+                ContainerInput::solid(-1.0, -1.0, bottom_left),
+                ContainerInput::solid(1.0, -1.0, bottom_right),
+            ],
+        }
+    }
+
+    ///Fills this container with the given `bg`
+    pub fn set_background(&mut self, core: &RenderingCore, bg: &Background) {
+        self.vertices.write_with(core, Self::vertices_of(bg));
+    }
+
+    ///Gets the radius of the corners of this container
+    pub fn radius(&self) -> f32 {
+        self.radius.inner()[0]
+    }
+
+    ///Sets the radius of the corners of this container
+    pub fn set_radius(&mut self, core: &RenderingCore, radius: f32) {
+        self.radius.write_with(core, [radius; 4]);
+    }
 }
 
 impl Mesh for Container {
@@ -87,26 +110,22 @@ impl Mesh for Container {
         &mut self.rect_buf
     }
 
-    fn screen_size(&mut self) -> &mut AbstractBuffer<[f32; 2]> {
-        &mut self.screen_size
+    fn transform_buffer(&mut self) -> &mut AbstractBuffer<Affine> {
+        &mut self.transform
+    }
+    fn opacity_buffer(&mut self) -> &mut AbstractBuffer<f32> {
+        &mut self.opacity
     }
     fn draw(&self, pass: &mut wgpu::RenderPass) {
         pass.set_pipeline(self.shader.pipeline());
-        {
-            let mut idx = 0;
-            for bind_group in self.shader.bind_groups() {
-                pass.set_bind_group(idx, bind_group, &[]);
-                idx += 1;
-            }
+        for (idx, bind_group) in self.shader.bind_groups().iter().enumerate() {
+            pass.set_bind_group(idx as u32, bind_group, &[]);
         }
         pass.set_index_buffer(self.index.slice(..), wgpu::IndexFormat::Uint16);
         pass.set_vertex_buffer(0, self.vertices.inner_buffer().slice(..));
         pass.draw_indexed(0..self.indices_len, 0, 0..1);
     }
-    fn resize(&mut self, core: &RenderingCore, screen_size: (f32, f32), layout: &taffy::Layout) {
-        self.screen_size
-            .write_with(core, [screen_size.0, screen_size.1]);
-
+    fn resize(&mut self, core: &RenderingCore, layout: &taffy::Layout) {
         let rect_buf = self.area_buffer();
         let rect_mut = rect_buf.inner_mut();
         let Size { width, height } = layout.size;
@@ -45,7 +45,7 @@ impl HystShader for ContainerShader {
         &self.pipeline
     }
     fn bind_group_layouts(&self) -> Option<&[wgpu::BindGroupLayout]> {
-        None
+        Some(&self.layouts)
     }
     fn bind_groups(&self) -> &[wgpu::BindGroup] {
         &self.bindgroups
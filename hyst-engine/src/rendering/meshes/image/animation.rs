@@ -0,0 +1,334 @@
+use std::{io::Cursor, time::Duration};
+
+use hyst_math::Rect;
+use image::{AnimationDecoder, RgbaImage, codecs::png::PngDecoder};
+
+use super::{ImageError, ImageSource};
+
+///Delay of the frames which don't define one, as browsers do for gifs
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+///Texels around each packed frame, which repeat it's edges so linear filtering never blends it with the frames next to it
+const PADDING: u32 = 1;
+
+///Where the frames of an animation come from
+#[derive(Debug, Clone)]
+pub enum AnimationSource {
+    ///Animated gif. Decoding it requires the gif feature
+    Gif(ImageSource),
+    ///Animated png. Pngs which aren't animated have a single frame
+    Apng(ImageSource),
+    SpriteSheet(SpriteSheet),
+}
+
+///Image with the frames of an animation laid out in a grid of cells of the same size, row by row from the top left one
+#[derive(Debug, Clone)]
+pub struct SpriteSheet {
+    pub source: ImageSource,
+    pub columns: u32,
+    pub rows: u32,
+    ///Amount of cells which have frames, when the last row isn't full. None uses every cell
+    pub frames: Option<u32>,
+    ///How long each frame is shown. Zero uses the default delay of 100ms
+    pub frame_duration: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationFrame {
+    ///Area of the atlas, in texels, which has the frame
+    pub rect: Rect,
+    ///How long the frame is shown
+    pub delay: Duration,
+}
+
+///Frames of an animation packed on a single image, so playing it only changes the area of the texture which is shown
+#[derive(Debug)]
+pub struct DecodedAnimation {
+    pub atlas: RgbaImage,
+    pub frames: Vec<AnimationFrame>,
+}
+
+impl AnimationSource {
+    ///Decodes every frame of the animation into an atlas, scaled down so neither of it's sides is bigger than `limit`
+    pub fn decode(self, limit: u32) -> Result<DecodedAnimation, ImageError> {
+        let frames = match self {
+            Self::SpriteSheet(sheet) => return sheet.decode(limit),
+            Self::Gif(source) => Self::gif_frames(source)?,
+            Self::Apng(source) => {
+                let bytes = source.encoded()?;
+                let decoder = PngDecoder::new(Cursor::new(&bytes[..]))?;
+                match decoder.is_apng()? {
+                    true => decoder.apng()?.into_frames().collect_frames()?,
+                    false => {
+                        let still = image::load_from_memory(&bytes)?;
+                        vec![image::Frame::new(still.into_rgba8())]
+                    }
+                }
+            }
+        };
+        let frames = frames
+            .into_iter()
+            .map(|frame| {
+                let delay = Duration::from(frame.delay());
+                (frame.into_buffer(), delay)
+            })
+            .collect();
+        Ok(pack(frames, limit))
+    }
+
+    #[cfg(feature = "gif")]
+    fn gif_frames(source: ImageSource) -> Result<Vec<image::Frame>, ImageError> {
+        let bytes = source.encoded()?;
+        let decoder = image::codecs::gif::GifDecoder::new(Cursor::new(&bytes[..]))?;
+        Ok(decoder.into_frames().collect_frames()?)
+    }
+
+    #[cfg(not(feature = "gif"))]
+    fn gif_frames(_source: ImageSource) -> Result<Vec<image::Frame>, ImageError> {
+        let format = image::error::ImageFormatHint::Exact(image::ImageFormat::Gif);
+        Err(ImageError::Decode(image::ImageError::Unsupported(
+            image::error::UnsupportedError::from_format_and_kind(
+                format.clone(),
+                image::error::UnsupportedErrorKind::Format(format),
+            ),
+        )))
+    }
+}
+
+impl SpriteSheet {
+    fn decode(self, limit: u32) -> Result<DecodedAnimation, ImageError> {
+        let atlas = self.source.decode(limit)?;
+        let (columns, rows) = (self.columns.max(1), self.rows.max(1));
+        let width = atlas.width() as f32 / columns as f32;
+        let height = atlas.height() as f32 / rows as f32;
+        let count = self.frames.unwrap_or(columns * rows).min(columns * rows);
+        //Cells are next to each other, so their areas are moved half a texel inwards for linear filtering not to reach the
+        //neighbouring ones
+        let frames = (0..count)
+            .map(|idx| AnimationFrame {
+                rect: Rect::from_xywh(
+                    (idx % columns) as f32 * width + 0.5,
+                    (idx / columns) as f32 * height + 0.5,
+                    (width - 1.0).max(0.0),
+                    (height - 1.0).max(0.0),
+                ),
+                delay: delay_or_default(self.frame_duration),
+            })
+            .collect();
+        Ok(DecodedAnimation { atlas, frames })
+    }
+}
+
+///Packs the given frames, which have the same size, on a grid as square as possible, with `PADDING` texels around each one.
+///Frames are scaled down when the grid would be bigger than `limit`
+fn pack(frames: Vec<(RgbaImage, Duration)>, limit: u32) -> DecodedAnimation {
+    let Some((first, _)) = frames.first() else {
+        return DecodedAnimation {
+            atlas: RgbaImage::new(1, 1),
+            frames: Vec::new(),
+        };
+    };
+    let count = frames.len() as u32;
+    let columns = (count as f32).sqrt().ceil() as u32;
+    let rows = count.div_ceil(columns);
+    let (mut width, mut height) = first.dimensions();
+    let scale = (limit.saturating_sub(columns * 2 * PADDING) as f32 / (columns * width) as f32)
+        .min(limit.saturating_sub(rows * 2 * PADDING) as f32 / (rows * height) as f32)
+        .min(1.0);
+    if scale < 1.0 {
+        width = ((width as f32 * scale) as u32).max(1);
+        height = ((height as f32 * scale) as u32).max(1);
+    }
+    let (cell_width, cell_height) = (width + 2 * PADDING, height + 2 * PADDING);
+    let mut atlas = RgbaImage::new(columns * cell_width, rows * cell_height);
+    let frames = frames
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (mut buffer, delay))| {
+            if buffer.dimensions() != (width, height) {
+                buffer = image::imageops::resize(
+                    &buffer,
+                    width,
+                    height,
+                    image::imageops::FilterType::Triangle,
+                );
+            }
+            let x = idx as u32 % columns * cell_width + PADDING;
+            let y = idx as u32 / columns * cell_height + PADDING;
+            image::imageops::replace(&mut atlas, &buffer, x as i64, y as i64);
+            extend_edges(&mut atlas, x, y, width, height);
+            AnimationFrame {
+                rect: Rect::from_xywh(x as f32, y as f32, width as f32, height as f32),
+                delay: delay_or_default(delay),
+            }
+        })
+        .collect();
+    DecodedAnimation { atlas, frames }
+}
+
+///Frames shown for no time would never be left, so they're shown for `DEFAULT_DELAY` instead
+fn delay_or_default(delay: Duration) -> Duration {
+    match delay.is_zero() {
+        true => DEFAULT_DELAY,
+        false => delay,
+    }
+}
+
+///Fills the `PADDING` texels around the frame at the given position of the `atlas` with the texels of it's closest edge
+fn extend_edges(atlas: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32) {
+    let (left, top) = (x - PADDING, y - PADDING);
+    for py in top..y + height + PADDING {
+        for px in left..x + width + PADDING {
+            let inside = (x..x + width).contains(&px) && (y..y + height).contains(&py);
+            if !inside {
+                let source = (px.clamp(x, x + width - 1), py.clamp(y, y + height - 1));
+                let texel = *atlas.get_pixel(source.0, source.1);
+                atlas.put_pixel(px, py, texel);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyst_math::vectors::Vec2f32;
+
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, image::Rgba([value, value, value, 255]))
+    }
+
+    fn overlap(a: &Rect, b: &Rect) -> bool {
+        a.position().x() < b.position().x() + b.size().x()
+            && b.position().x() < a.position().x() + a.size().x()
+            && a.position().y() < b.position().y() + b.size().y()
+            && b.position().y() < a.position().y() + a.size().y()
+    }
+
+    #[test]
+    fn packs_frames_apart_from_each_other() {
+        let frames = (0..5)
+            .map(|idx| (solid(8, 4, idx * 50), Duration::from_millis(40)))
+            .collect();
+        let decoded = pack(frames, 1024);
+        assert_eq!(decoded.frames.len(), 5);
+        //5 frames take a 3x2 grid of cells with a texel around each frame
+        assert_eq!(decoded.atlas.dimensions(), (3 * 10, 2 * 6));
+        for (idx, frame) in decoded.frames.iter().enumerate() {
+            let rect = frame.rect;
+            assert_eq!(*rect.size(), Vec2f32::new(8.0, 4.0));
+            assert!(rect.position().x() >= PADDING as f32 && rect.position().y() >= PADDING as f32);
+            assert!(rect.position().x() + rect.size().x() <= (30 - PADDING) as f32);
+            assert!(rect.position().y() + rect.size().y() <= (12 - PADDING) as f32);
+            for other in &decoded.frames[idx + 1..] {
+                //Grown by the padding, frames still don't touch each other
+                let grown = Rect::from_xywh(
+                    rect.position().x() - PADDING as f32,
+                    rect.position().y() - PADDING as f32,
+                    rect.size().x() + 2.0 * PADDING as f32,
+                    rect.size().y() + 2.0 * PADDING as f32,
+                );
+                assert!(!overlap(&grown, &other.rect));
+            }
+        }
+    }
+
+    #[test]
+    fn padding_repeats_the_edges_of_each_frame() {
+        let frames = (0..4)
+            .map(|idx| (solid(3, 3, 60 + idx * 60), Duration::ZERO))
+            .collect();
+        let decoded = pack(frames, 1024);
+        for (idx, frame) in decoded.frames.iter().enumerate() {
+            let (x, y) = (
+                frame.rect.position().x() as u32,
+                frame.rect.position().y() as u32,
+            );
+            let value = 60 + idx as u8 * 60;
+            //Every texel around the frame, corners included, has the color of the frame
+            for (px, py) in [
+                (x - 1, y - 1),
+                (x + 3, y + 1),
+                (x + 1, y + 3),
+                (x + 3, y + 3),
+            ] {
+                assert_eq!(
+                    decoded.atlas.get_pixel(px, py).0,
+                    [value, value, value, 255]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scales_frames_down_to_fit_the_limit_with_their_padding() {
+        let frames = (0..4)
+            .map(|_| (solid(100, 50, 255), Duration::from_millis(10)))
+            .collect();
+        let decoded = pack(frames, 64);
+        let (width, height) = decoded.atlas.dimensions();
+        assert!(width <= 64 && height <= 64);
+        let size = decoded.frames[0].rect.size();
+        //The aspect ratio is kept, up to the rounding of the texels
+        assert!((size.x() / size.y() - 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn frames_without_delay_use_the_default_one() {
+        let frames = vec![
+            (solid(2, 2, 0), Duration::ZERO),
+            (solid(2, 2, 0), Duration::from_millis(30)),
+        ];
+        let decoded = pack(frames, 1024);
+        assert_eq!(decoded.frames[0].delay, DEFAULT_DELAY);
+        assert_eq!(decoded.frames[1].delay, Duration::from_millis(30));
+    }
+
+    #[test]
+    fn no_frames_pack_an_empty_atlas() {
+        let decoded = pack(Vec::new(), 1024);
+        assert!(decoded.frames.is_empty());
+        assert_eq!(decoded.atlas.dimensions(), (1, 1));
+    }
+
+    #[test]
+    fn sprite_sheet_cells_are_moved_half_a_texel_inwards() {
+        let sheet = SpriteSheet {
+            source: ImageSource::Rgba {
+                size: (40, 20),
+                data: vec![255; 40 * 20 * 4],
+            },
+            columns: 4,
+            rows: 2,
+            frames: Some(7),
+            frame_duration: Duration::from_millis(50),
+        };
+        let decoded = sheet.decode(1024).unwrap();
+        assert_eq!(decoded.frames.len(), 7);
+        assert_eq!(
+            decoded.frames[5].rect,
+            Rect::from_xywh(10.5, 10.5, 9.0, 9.0)
+        );
+    }
+
+    #[test]
+    fn sprite_sheets_without_frame_duration_use_the_default_delay() {
+        let sheet = SpriteSheet {
+            source: ImageSource::Rgba {
+                size: (20, 10),
+                data: vec![255; 20 * 10 * 4],
+            },
+            columns: 2,
+            rows: 1,
+            frames: None,
+            frame_duration: Duration::ZERO,
+        };
+        let decoded = sheet.decode(1024).unwrap();
+        assert!(
+            decoded
+                .frames
+                .iter()
+                .all(|frame| frame.delay == DEFAULT_DELAY)
+        );
+    }
+}
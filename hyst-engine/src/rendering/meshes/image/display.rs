@@ -0,0 +1,241 @@
+use bytemuck::{Pod, Zeroable};
+use hyst_math::{Rect, vectors::Vec2f32};
+
+///How an image is sized inside of it's rect, the same as css' object-fit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ObjectFit {
+    ///Stretched to fill the whole rect
+    #[default]
+    Fill,
+    ///As big as possible while being fully visible, keeping it's aspect ratio
+    Contain,
+    ///As small as possible while covering the whole rect, keeping it's aspect ratio. What's outside of the rect is cut
+    Cover,
+    ///Kept at it's natural size
+    None,
+    ///The smallest between `None` and `Contain`
+    ScaleDown,
+}
+
+///How the texels of an image are sampled when it's scaled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageFilter {
+    ///Takes the closest texel, keeping the pixels sharp
+    #[default]
+    Nearest,
+    ///Blends the closest texels, so it looks smooth
+    Linear,
+}
+
+impl From<ImageFilter> for wgpu::FilterMode {
+    fn from(filter: ImageFilter) -> Self {
+        match filter {
+            ImageFilter::Nearest => wgpu::FilterMode::Nearest,
+            ImageFilter::Linear => wgpu::FilterMode::Linear,
+        }
+    }
+}
+
+///What's drawn on the parts of the rect the image doesn't cover
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageTiling {
+    ///Nothing, only the fitted image is drawn
+    #[default]
+    Clamp,
+    ///The image is repeated
+    Repeat,
+    ///The image is repeated, flipping every other copy so their edges match
+    Mirror,
+}
+
+impl ImageTiling {
+    fn id(&self) -> u32 {
+        match self {
+            Self::Clamp => 0,
+            Self::Repeat => 1,
+            Self::Mirror => 2,
+        }
+    }
+}
+
+///Insets, in texels of the source, of the borders of a nine-slice image. The corners keep their size, the edges are stretched
+///along them and the center is stretched on both axes, so the image can fill any rect without distorting it's borders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NineSlice {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    ///Scale the borders are drawn with, such as the scale factor of the window
+    pub scale: f32,
+}
+
+impl NineSlice {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+            scale: 1.0,
+        }
+    }
+    ///Creates a nine-slice with the same `inset` on every side
+    pub fn uniform(inset: f32) -> Self {
+        Self::new(inset, inset, inset, inset)
+    }
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+///How an image is shown inside of it's rect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageDisplay {
+    pub fit: ObjectFit,
+    ///Where the image is placed on the parts of the rect it doesn't fill, as a fraction of the free space. (0.5, 0.5) centers it
+    pub alignment: Vec2f32,
+    pub filter: ImageFilter,
+    ///What's drawn around the image. Tiles have the size the `fit` gives the image, so `ObjectFit::None` tiles it at it's natural size
+    pub tiling: ImageTiling,
+    ///Area of the source, in texels, which is shown, such as a sprite of a sheet. None shows the whole source
+    pub source_rect: Option<Rect>,
+    ///When defined, the image is drawn as a nine-slice filling the whole rect, ignoring the fit and the tiling
+    pub nine_slice: Option<NineSlice>,
+}
+
+impl Default for ImageDisplay {
+    fn default() -> Self {
+        Self {
+            fit: ObjectFit::Fill,
+            alignment: Vec2f32::new(0.5, 0.5),
+            filter: ImageFilter::Nearest,
+            tiling: ImageTiling::Clamp,
+            source_rect: None,
+            nine_slice: None,
+        }
+    }
+}
+
+impl ImageDisplay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn fit(mut self, fit: ObjectFit) -> Self {
+        self.fit = fit;
+        self
+    }
+    pub fn align(mut self, x: f32, y: f32) -> Self {
+        self.alignment = Vec2f32::new(x, y);
+        self
+    }
+    pub fn filter(mut self, filter: ImageFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+    pub fn tiling(mut self, tiling: ImageTiling) -> Self {
+        self.tiling = tiling;
+        self
+    }
+    pub fn source_rect(mut self, source_rect: Rect) -> Self {
+        self.source_rect = Some(source_rect);
+        self
+    }
+    pub fn nine_slice(mut self, nine_slice: NineSlice) -> Self {
+        self.nine_slice = Some(nine_slice);
+        self
+    }
+
+    ///Gets the size, in pixels, the image is drawn with inside of a rect with the given `size`, when it's natural size is `natural`
+    pub fn fitted_size(&self, natural: Vec2f32, size: Vec2f32) -> Vec2f32 {
+        if natural.x() <= 0.0 || natural.y() <= 0.0 {
+            return size;
+        }
+        let contain = (size.x() / natural.x()).min(size.y() / natural.y());
+        let scale = match self.fit {
+            ObjectFit::Fill => return size,
+            ObjectFit::Contain => contain,
+            ObjectFit::Cover => (size.x() / natural.x()).max(size.y() / natural.y()),
+            ObjectFit::None => 1.0,
+            ObjectFit::ScaleDown => contain.min(1.0),
+        };
+        Vec2f32::new(natural.x() * scale, natural.y() * scale)
+    }
+
+    ///Computes the values the shader needs for drawing a texture with the given `texture_size` on the given `rect`
+    pub(crate) fn params(&self, rect: &Rect, (width, height): (u32, u32)) -> ImageParams {
+        let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+        let source = self
+            .source_rect
+            .unwrap_or(Rect::from_xywh(0.0, 0.0, width, height));
+        let natural = *source.size();
+        let size = self.fitted_size(natural, *rect.size());
+        let free = *rect.size() - size;
+        let position = Vec2f32::new(
+            rect.position().x() + free.x() * self.alignment.x(),
+            rect.position().y() + free.y() * self.alignment.y(),
+        );
+        let mut params = ImageParams {
+            image: [position.x(), position.y(), size.x(), size.y()],
+            source: [
+                source.position().x() / width,
+                source.position().y() / height,
+                natural.x() / width,
+                natural.y() / height,
+            ],
+            slice_source: [0.0; 4],
+            slice_screen: [0.0; 4],
+            tiling: self.tiling.id(),
+            nine_slice: 0,
+            _padding: [0; 2],
+        };
+        if let Some(slice) = self.nine_slice {
+            //Borders bigger than the rect are shrunk, keeping the proportion between the opposite ones
+            let (horizontal, vertical) = (
+                (slice.left + slice.right) * slice.scale,
+                (slice.top + slice.bottom) * slice.scale,
+            );
+            let shrink_x = (rect.size().x() / horizontal.max(f32::EPSILON)).min(1.0) * slice.scale;
+            let shrink_y = (rect.size().y() / vertical.max(f32::EPSILON)).min(1.0) * slice.scale;
+            params.nine_slice = 1;
+            params.tiling = ImageTiling::Clamp.id();
+            params.image = [
+                rect.position().x(),
+                rect.position().y(),
+                rect.size().x(),
+                rect.size().y(),
+            ];
+            params.slice_source = [
+                slice.left / natural.x().max(1.0),
+                slice.top / natural.y().max(1.0),
+                slice.right / natural.x().max(1.0),
+                slice.bottom / natural.y().max(1.0),
+            ];
+            params.slice_screen = [
+                slice.left * shrink_x,
+                slice.top * shrink_y,
+                slice.right * shrink_x,
+                slice.bottom * shrink_y,
+            ];
+        }
+        params
+    }
+}
+
+///Uniform with what the image shader needs for placing the texture on the rect of the image
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub(crate) struct ImageParams {
+    ///Area the image covers, or one of it's tiles, in pixels
+    image: [f32; 4],
+    ///Area of the texture which is shown, in uv coordinates
+    source: [f32; 4],
+    ///Insets of the nine-slice on the source, as fractions of it. Left, top, right, bottom
+    slice_source: [f32; 4],
+    ///Insets of the nine-slice on the screen, in pixels
+    slice_screen: [f32; 4],
+    tiling: u32,
+    nine_slice: u32,
+    _padding: [u32; 2],
+}
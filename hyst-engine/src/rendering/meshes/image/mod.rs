@@ -1,55 +1,76 @@
 use super::Mesh;
 use crate::core::RenderingCore;
-use crate::shaders::ShaderRenderMethod;
-use crate::{AbstractBuffer, BindGroupAndLayoutConfig, BufferType, GpuImage, shaders::HystShader};
-use hyst_math::Rect;
+use crate::shaders::ShaderCreationOptions;
+use crate::{AbstractBuffer, BindGroupConfig, BufferType, GpuImage, shaders::HystShader};
+use hyst_math::{Affine, Rect};
 use image::GenericImageView;
 
+mod animation;
+pub use animation::*;
+mod display;
+pub use display::*;
 mod input;
 pub use input::*;
+mod source;
+pub use source::*;
+#[cfg(feature = "svg")]
+mod svg;
+#[cfg(feature = "svg")]
+pub use svg::*;
 mod shader;
 pub use shader::*;
 use taffy::{Point, Size};
 
 pub struct ImageCreationOption {
     pub rect: Rect,
-    pub source: String,
+    pub source: ImageSource,
+    pub display: ImageDisplay,
+    ///Biggest width or height the source is uploaded with. Bigger sources are scaled down on the cpu, keeping their aspect ratio.
+    ///Sources bigger than the max texture size of the device are always scaled down to it
+    pub max_size: Option<u32>,
 }
 
 #[derive(Debug)]
 pub struct Image {
     vertices: AbstractBuffer<[ImageInput; 4]>,
-    screen_size: AbstractBuffer<[f32; 2]>,
     indices: wgpu::Buffer,
     area: AbstractBuffer<Rect>,
+    premultiplied: AbstractBuffer<f32>,
+    transform: AbstractBuffer<Affine>,
+    opacity: AbstractBuffer<f32>,
+    params: AbstractBuffer<ImageParams>,
+    display: ImageDisplay,
     shader: ImageShader,
     indices_len: u32,
     image: GpuImage,
 }
 
 impl Image {
+    ///Decodes the source of the given `configs` on the current thread and creates an image showing it
     pub fn from_configs(
         core: &mut RenderingCore,
         configs: ImageCreationOption,
-    ) -> std::io::Result<Self> {
-        let img_bytes = std::fs::read(&configs.source)?;
-        let img = image::load_from_memory(&img_bytes).unwrap();
-        let dimensions = img.dimensions();
-        let rgba = img.into_rgba8();
-        Ok(Self::new(
-            core,
-            core.size(),
-            configs.rect,
-            dimensions,
-            &rgba,
-        ))
+    ) -> Result<Self, ImageError> {
+        let limit = core
+            .max_texture_size()
+            .min(configs.max_size.unwrap_or(u32::MAX));
+        let rgba = configs.source.decode(limit)?;
+        let mut image = Self::new(core, configs.rect, rgba.dimensions(), &rgba);
+        image.set_display(core, configs.display);
+        Ok(image)
+    }
+    pub fn new(core: &mut RenderingCore, rect: Rect, img_size: (u32, u32), data: &[u8]) -> Self {
+        let image = core.create_image(img_size, data);
+        Self::from_gpu_image(core, rect, image, false)
     }
-    pub fn new(
+
+    ///Creates an image which shows the given gpu `image`, such as an offscreen target. `premultiplied` tells whether it's colors are
+    ///already multiplied by their alpha
+    pub fn from_gpu_image(
         core: &mut RenderingCore,
-        core_size: (u32, u32),
         rect: Rect,
-        img_size: (u32, u32),
-        data: &[u8],
+        image: GpuImage,
+        premultiplied: bool,
     ) -> Self {
         let vertices = AbstractBuffer::new(
             core,
@@ -62,62 +83,159 @@ impl Image {
             BufferType::Vertex,
         );
         let indices = core.create_index_buffer(&[0, 1, 2, 2, 1, 3], None);
-        let screen_size = AbstractBuffer::new(
+        let area = AbstractBuffer::new(core, rect, BufferType::Uniform);
+        let premultiplied =
+            AbstractBuffer::new(core, premultiplied as u32 as f32, BufferType::Uniform);
+        let transform = AbstractBuffer::new(core, Affine::IDENTITY, BufferType::Uniform);
+        let opacity = AbstractBuffer::new(core, 1.0, BufferType::Uniform);
+        let display = ImageDisplay::default();
+        let params = AbstractBuffer::new(
             core,
-            [core_size.0 as f32, core_size.1 as f32],
+            display.params(&rect, Self::texture_size(&image)),
             BufferType::Uniform,
         );
-        let area = AbstractBuffer::new(core, rect, BufferType::Uniform);
-        let image = core.create_image(img_size, data);
-        let shader = core.create_shader(crate::shaders::ShaderCreationOptions {
-            source: &std::fs::read_to_string("./shaders/image.wgsl").unwrap(),
-            bind_group_configs: vec![
-                vec![
-                    BindGroupAndLayoutConfig::Uniform(
-                        wgpu::ShaderStages::VERTEX,
-                        screen_size.inner_buffer(),
-                    ),
-                    BindGroupAndLayoutConfig::Uniform(
-                        wgpu::ShaderStages::VERTEX,
-                        area.inner_buffer(),
-                    ),
-                ],
-                vec![
-                    BindGroupAndLayoutConfig::Texutre(
-                        wgpu::TextureViewDimension::D2,
-                        wgpu::TextureSampleType::Float { filterable: true },
-                        image.view(),
-                    ),
-                    BindGroupAndLayoutConfig::Sampler(
-                        wgpu::SamplerBindingType::Filtering,
-                        image.sampler(),
-                    ),
-                ],
+        let shader = Self::create_shader(
+            core,
+            [
+                area.inner_buffer(),
+                premultiplied.inner_buffer(),
+                transform.inner_buffer(),
+                opacity.inner_buffer(),
+                params.inner_buffer(),
             ],
-            rendering_style: ShaderRenderMethod::TriangleCcwBack,
-            name: "img".into(),
-        });
+            &image,
+        );
         Self {
             image,
             indices_len: 6,
             indices,
             vertices,
-            screen_size,
             area,
+            premultiplied,
+            transform,
+            opacity,
+            params,
+            display,
             shader,
         }
     }
+
+    ///Creates the shader which draws the given `image`, with the given `uniforms` in the order of their bindings
+    fn create_shader(
+        core: &mut RenderingCore,
+        uniforms: [&wgpu::Buffer; 5],
+        image: &GpuImage,
+    ) -> ImageShader {
+        let [area, premultiplied, transform, opacity, params] = uniforms;
+        core.create_shader(
+            ShaderCreationOptions::new(
+                "img",
+                &std::fs::read_to_string("./shaders/image.wgsl").unwrap(),
+            )
+            .group(
+                BindGroupConfig::new()
+                    .uniform(0, wgpu::ShaderStages::VERTEX_FRAGMENT, area)
+                    .uniform(1, wgpu::ShaderStages::FRAGMENT, premultiplied)
+                    .uniform(2, wgpu::ShaderStages::VERTEX, transform)
+                    .uniform(3, wgpu::ShaderStages::FRAGMENT, opacity)
+                    .uniform(4, wgpu::ShaderStages::FRAGMENT, params),
+            )
+            .group(
+                BindGroupConfig::new()
+                    .texture(
+                        0,
+                        wgpu::ShaderStages::FRAGMENT,
+                        image.view(),
+                        wgpu::TextureViewDimension::D2,
+                        wgpu::TextureSampleType::Float { filterable: true },
+                    )
+                    .sampler(
+                        1,
+                        wgpu::ShaderStages::FRAGMENT,
+                        image.sampler(),
+                        wgpu::SamplerBindingType::Filtering,
+                    ),
+            ),
+        )
+    }
+
+    fn texture_size(image: &GpuImage) -> (u32, u32) {
+        let size = image.texture().size();
+        (size.width, size.height)
+    }
+
+    #[inline]
+    ///Gets how the image is shown inside of it's rect
+    pub fn display(&self) -> &ImageDisplay {
+        &self.display
+    }
+
+    ///Changes how the image is shown inside of it's rect. Changing the filter creates a new sampler
+    pub fn set_display(&mut self, core: &mut RenderingCore, display: ImageDisplay) {
+        if display.filter != self.display.filter {
+            self.image
+                .set_sampler(core.create_sampler(display.filter.into()));
+            self.rebuild_shader(core);
+        }
+        self.display = display;
+        self.write_params(core);
+    }
+
+    ///Replaces the texture shown by the image with the given gpu `image`, sampled with the filter of the display
+    pub fn set_gpu_image(&mut self, core: &mut RenderingCore, mut image: GpuImage) {
+        image.set_sampler(core.create_sampler(self.display.filter.into()));
+        self.image = image;
+        self.rebuild_shader(core);
+        self.write_params(core);
+    }
+
+    ///Creates the shader again, since it's bind groups refer to the texture and to the sampler
+    fn rebuild_shader(&mut self, core: &mut RenderingCore) {
+        self.shader = Self::create_shader(
+            core,
+            [
+                self.area.inner_buffer(),
+                self.premultiplied.inner_buffer(),
+                self.transform.inner_buffer(),
+                self.opacity.inner_buffer(),
+                self.params.inner_buffer(),
+            ],
+            &self.image,
+        );
+    }
+
+    ///Writes where the texture is placed, based on the display and on the current rect
+    fn write_params(&mut self, core: &RenderingCore) {
+        let params = self
+            .display
+            .params(self.area.inner(), Self::texture_size(&self.image));
+        if *self.params.inner() != params {
+            self.params.write_with(core, params);
+        }
+    }
 }
 
-impl Mesh for Image {
-    fn screen_size(&mut self) -> &mut AbstractBuffer<[f32; 2]> {
-        &mut self.screen_size
+///Scales the given `img` down on the cpu so neither of it's sides is bigger than `limit`, keeping it's aspect ratio
+pub fn downscaled(img: image::DynamicImage, limit: u32) -> image::DynamicImage {
+    let (width, height) = img.dimensions();
+    if width <= limit && height <= limit {
+        return img;
     }
+    img.resize(limit, limit, image::imageops::FilterType::Lanczos3)
+}
+
+impl Mesh for Image {
     fn area_buffer(&mut self) -> &mut AbstractBuffer<Rect> {
         &mut self.area
     }
+    fn transform_buffer(&mut self) -> &mut AbstractBuffer<Affine> {
+        &mut self.transform
+    }
+    fn opacity_buffer(&mut self) -> &mut AbstractBuffer<f32> {
+        &mut self.opacity
+    }
     fn draw(&self, pass: &mut wgpu::RenderPass) {
-        pass.set_pipeline(&self.shader.pipeline());
+        pass.set_pipeline(self.shader.pipeline());
         for (idx, bindgroups) in self.shader.bind_groups().iter().enumerate() {
             pass.set_bind_group(idx as u32, bindgroups, &[]);
         }
@@ -125,10 +243,7 @@ impl Mesh for Image {
         pass.set_vertex_buffer(0, self.vertices.inner_buffer().slice(..));
         pass.draw_indexed(0..self.indices_len, 0, 0..1);
     }
-    fn resize(&mut self, core: &RenderingCore, screen_size: (f32, f32), layout: &taffy::Layout) {
-        self.screen_size
-            .write_with(core, [screen_size.0, screen_size.1]);
-
+    fn resize(&mut self, core: &RenderingCore, layout: &taffy::Layout) {
         let rect_buf = self.area_buffer();
         let rect_mut = rect_buf.inner_mut();
         let Size { width, height } = layout.size;
@@ -138,5 +253,6 @@ impl Mesh for Image {
         rect_mut.position_mut().set_coords(x, y);
 
         rect_buf.write(core);
+        self.write_params(core);
     }
 }
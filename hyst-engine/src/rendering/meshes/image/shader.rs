@@ -46,6 +46,10 @@ impl HystConstructor for ImageShader {
     fn shader_inputs() -> Vec<wgpu::VertexBufferLayout<'static>> {
         vec![ImageInput::LAYOUT]
     }
+    fn blend() -> wgpu::BlendState {
+        //The fragment shader multiplies the colors by their alpha, unless the texture already is
+        wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING
+    }
     fn name() -> &'static str {
         "image_shader"
     }
@@ -0,0 +1,204 @@
+use std::{path::PathBuf, sync::Arc};
+
+#[cfg(feature = "svg")]
+use hyst_math::vectors::Rgba;
+use image::{DynamicImage, RgbaImage};
+
+use super::downscaled;
+#[cfg(feature = "svg")]
+use super::{Svg, SvgSource};
+
+///Where the pixels of an image come from
+#[derive(Debug, Clone)]
+pub enum ImageSource {
+    ///Path of an encoded image file
+    Path(PathBuf),
+    ///Encoded image, such as the content of a png file
+    Bytes(Arc<[u8]>),
+    ///Decoded pixels, 4 bytes per pixel, row by row from the top left one
+    Rgba { size: (u32, u32), data: Vec<u8> },
+    ///Vector image, rasterized on the cpu. Images rasterize it again whenever the physical size of their rect changes, so it's
+    ///sharp on any scale factor. When there's a `tint`, every pixel gets it's color, so monochrome icons can be themed.
+    ///Requires the `svg` feature
+    #[cfg(feature = "svg")]
+    Svg {
+        source: SvgSource,
+        tint: Option<Rgba>,
+    },
+}
+
+///Source which was read, ready to be uploaded
+#[derive(Debug)]
+pub enum LoadedImage {
+    Raster(RgbaImage),
+    ///Parsed svg, rasterized once the size it's drawn with is known
+    #[cfg(feature = "svg")]
+    Vector(Svg),
+}
+
+impl From<&str> for ImageSource {
+    fn from(path: &str) -> Self {
+        Self::Path(path.into())
+    }
+}
+impl From<String> for ImageSource {
+    fn from(path: String) -> Self {
+        Self::Path(path.into())
+    }
+}
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl ImageSource {
+    ///Whether the source is already decoded, so it can be uploaded right away
+    pub fn is_decoded(&self) -> bool {
+        matches!(self, Self::Rgba { .. })
+    }
+
+    ///Gets the encoded bytes of the source, reading the file if it's a path. Fails if it was given as pixels
+    pub fn encoded(self) -> Result<Arc<[u8]>, ImageError> {
+        match self {
+            Self::Path(path) => Ok(std::fs::read(path)?.into()),
+            Self::Bytes(bytes) => Ok(bytes),
+            Self::Rgba { .. } => Err(ImageError::NotEncoded),
+            #[cfg(feature = "svg")]
+            Self::Svg { .. } => Err(ImageError::NotEncoded),
+        }
+    }
+
+    ///Reads the source, decoding it if it's a raster image or parsing it if it's an svg
+    pub fn load(self, limit: u32) -> Result<LoadedImage, ImageError> {
+        match self {
+            #[cfg(feature = "svg")]
+            Self::Svg { source, .. } => Ok(LoadedImage::Vector(source.parse()?)),
+            source => Ok(LoadedImage::Raster(source.decode(limit)?)),
+        }
+    }
+
+    ///Reads and decodes the source into rgba pixels, scaled down so neither of it's sides is bigger than `limit`.
+    ///The formats which can be decoded depend on the enabled features, png and jpeg always are. Svgs are rasterized at their own size
+    pub fn decode(self, limit: u32) -> Result<RgbaImage, ImageError> {
+        let img = match self {
+            Self::Path(path) => image::load_from_memory(&std::fs::read(path)?)?,
+            Self::Bytes(bytes) => image::load_from_memory(&bytes)?,
+            Self::Rgba { size, data } => {
+                let len = data.len();
+                //Textures can't be empty
+                let pixels = RgbaImage::from_raw(size.0, size.1, data)
+                    .filter(|_| size.0 > 0 && size.1 > 0)
+                    .ok_or(ImageError::InvalidSize { size, len })?;
+                DynamicImage::ImageRgba8(pixels)
+            }
+            #[cfg(feature = "svg")]
+            Self::Svg { source, tint } => {
+                let svg = source.parse()?;
+                return Ok(svg.rasterize(svg.size_within(limit), tint));
+            }
+        };
+        Ok(downscaled(img, limit).into_rgba8())
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    ///The file of the source couldn't be read
+    Io(std::io::Error),
+    ///The source isn't an image, or it's format isn't supported
+    Decode(image::ImageError),
+    ///The rgba data doesn't have 4 bytes for each pixel of the given size, or one of it's sides is 0
+    InvalidSize { size: (u32, u32), len: usize },
+    ///The source was given as pixels or as an svg where an encoded raster image was expected, such as for animations
+    NotEncoded,
+    ///The svg markup isn't valid, or it's size is missing or empty
+    #[cfg(feature = "svg")]
+    Svg(resvg::usvg::Error),
+    ///The thread decoding the image stopped before finishing
+    Interrupted,
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+#[cfg(feature = "svg")]
+impl From<resvg::usvg::Error> for ImageError {
+    fn from(error: resvg::usvg::Error) -> Self {
+        Self::Svg(error)
+    }
+}
+impl From<image::ImageError> for ImageError {
+    fn from(error: image::ImageError) -> Self {
+        Self::Decode(error)
+    }
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "couldn't read the image: {error}"),
+            Self::Decode(error) => write!(f, "couldn't decode the image: {error}"),
+            Self::InvalidSize { size, .. } if size.0 == 0 || size.1 == 0 => {
+                write!(f, "an image of {}x{} pixels is empty", size.0, size.1)
+            }
+            Self::InvalidSize { size, len } => write!(
+                f,
+                "{len} bytes of rgba data don't fit an image of {}x{} pixels",
+                size.0, size.1
+            ),
+            Self::NotEncoded => write!(f, "the image isn't an encoded raster image"),
+            #[cfg(feature = "svg")]
+            Self::Svg(error) => write!(f, "couldn't parse the svg: {error}"),
+            Self::Interrupted => write!(f, "the thread decoding the image stopped"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Decode(error) => Some(error),
+            #[cfg(feature = "svg")]
+            Self::Svg(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+///Whether the source of an image was decoded and uploaded
+#[derive(Debug)]
+pub enum ImageStatus {
+    ///The source is being decoded on a background thread, and the placeholder is shown meanwhile
+    Loading,
+    Ready,
+    ///The source couldn't be decoded, so the placeholder is kept
+    Failed(ImageError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_rgba_images_are_rejected() {
+        for size in [(0, 0), (0, 4), (4, 0)] {
+            let source = ImageSource::Rgba {
+                size,
+                data: Vec::new(),
+            };
+            assert!(matches!(
+                source.decode(1024),
+                Err(ImageError::InvalidSize { .. })
+            ));
+        }
+        let source = ImageSource::Rgba {
+            size: (2, 2),
+            data: vec![0; 16],
+        };
+        assert_eq!(source.decode(1024).unwrap().dimensions(), (2, 2));
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use hyst_math::vectors::{Rgba, Vec2f32};
+use image::RgbaImage;
+use resvg::{
+    tiny_skia::{Pixmap, Transform},
+    usvg::{self, fontdb},
+};
+
+use super::ImageError;
+
+///Fonts the texts of the svgs are drawn with. The fonts of the system are loaded the first time an svg is parsed, which happens
+///on the thread loading it's image
+static FONTS: OnceLock<Arc<fontdb::Database>> = OnceLock::new();
+
+///Where the markup of an svg comes from
+#[derive(Debug, Clone)]
+pub enum SvgSource {
+    ///Path of an svg file. Images and other files it refers to are relative to it's directory
+    Path(PathBuf),
+    ///Content of an svg file
+    Markup(Arc<str>),
+}
+
+impl SvgSource {
+    ///Reads the markup, if it's in a file, and parses it
+    pub fn parse(self) -> Result<Svg, ImageError> {
+        match self {
+            Self::Path(path) => Svg::parse_in(&std::fs::read(&path)?, path.parent()),
+            Self::Markup(markup) => Svg::parse(&markup),
+        }
+    }
+}
+
+///Parsed svg, which can be rasterized at any size. It's drawn by resvg, which supports the static features of svg 1.1 and some of
+///svg 2, such as gradients, patterns, clip paths, masks, filters, texts and stylesheets
+#[derive(Debug, Clone)]
+pub struct Svg {
+    tree: Arc<usvg::Tree>,
+}
+
+impl Svg {
+    ///Parses the given svg `markup`
+    pub fn parse(markup: &str) -> Result<Self, ImageError> {
+        Self::parse_in(markup.as_bytes(), None)
+    }
+
+    ///Parses the given svg `data`, which may be gzipped. The files it refers to are relative to the given `dir`
+    fn parse_in(data: &[u8], dir: Option<&Path>) -> Result<Self, ImageError> {
+        let fonts = FONTS.get_or_init(|| {
+            let mut fonts = fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        });
+        let options = usvg::Options {
+            resources_dir: dir.map(Path::to_path_buf),
+            fontdb: fonts.clone(),
+            ..Default::default()
+        };
+        Ok(Self {
+            tree: Arc::new(usvg::Tree::from_data(data, &options)?),
+        })
+    }
+
+    #[inline]
+    ///Gets the size the svg has when it isn't scaled, in pixels
+    pub fn size(&self) -> Vec2f32 {
+        let size = self.tree.size();
+        Vec2f32::new(size.width(), size.height())
+    }
+
+    ///Gets the size, in pixels, of the svg scaled down so neither of it's sides is bigger than `limit`
+    pub fn size_within(&self, limit: u32) -> (u32, u32) {
+        let size = self.tree.size();
+        let (width, height) = (size.width().max(1.0), size.height().max(1.0));
+        let scale = (limit as f32 / width).min(limit as f32 / height).min(1.0);
+        (
+            ((width * scale).ceil() as u32).clamp(1, limit.max(1)),
+            ((height * scale).ceil() as u32).clamp(1, limit.max(1)),
+        )
+    }
+
+    ///Rasterizes the svg on an image with the given `size`. It's scaled to fit inside of it, keeping it's aspect ratio, and centered.
+    ///When there's a `tint`, every pixel gets it's color, with their alpha multiplied by the one of the tint
+    pub fn rasterize(&self, (width, height): (u32, u32), tint: Option<Rgba>) -> RgbaImage {
+        let (width, height) = (width.max(1), height.max(1));
+        let Some(mut pixmap) = Pixmap::new(width, height) else {
+            return RgbaImage::new(width, height);
+        };
+        let size = self.tree.size();
+        let scale = (width as f32 / size.width()).min(height as f32 / size.height());
+        let transform = Transform::from_row(
+            scale,
+            0.0,
+            0.0,
+            scale,
+            (width as f32 - size.width() * scale) * 0.5,
+            (height as f32 - size.height() * scale) * 0.5,
+        );
+        resvg::render(&self.tree, transform, &mut pixmap.as_mut());
+        let tint = tint.map(|tint| {
+            [tint.x(), tint.y(), tint.z(), tint.w()].map(|channel| channel.clamp(0.0, 1.0))
+        });
+        let data = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                match tint {
+                    Some([red, green, blue, alpha]) => [
+                        (red * 255.0).round() as u8,
+                        (green * 255.0).round() as u8,
+                        (blue * 255.0).round() as u8,
+                        (color.alpha() as f32 * alpha).round() as u8,
+                    ],
+                    None => [color.red(), color.green(), color.blue(), color.alpha()],
+                }
+            })
+            .collect();
+        RgbaImage::from_raw(width, height, data).expect("The pixmap has 4 bytes per pixel")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(image: &RgbaImage, x: u32, y: u32) -> [u8; 4] {
+        image.get_pixel(x, y).0
+    }
+
+    #[test]
+    fn size_comes_from_the_view_box_when_missing() {
+        let svg =
+            Svg::parse(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 12"/>"#).unwrap();
+        assert_eq!(svg.size(), Vec2f32::new(24.0, 12.0));
+        let svg = Svg::parse(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="48" height="24" viewBox="0 0 24 12"/>"#,
+        )
+        .unwrap();
+        assert_eq!(svg.size(), Vec2f32::new(48.0, 24.0));
+    }
+
+    #[test]
+    fn size_within_keeps_the_aspect_ratio() {
+        let svg =
+            Svg::parse(r#"<svg xmlns="http://www.w3.org/2000/svg" width="400" height="100"/>"#)
+                .unwrap();
+        assert_eq!(svg.size_within(200), (200, 50));
+        assert_eq!(svg.size_within(1000), (400, 100));
+    }
+
+    #[test]
+    fn rasterizes_shapes_scaled_to_the_size() {
+        let svg = Svg::parse(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+                <rect x="0" y="0" width="5" height="10" fill="red"/>
+            </svg>"#,
+        )
+        .unwrap();
+        let image = svg.rasterize((20, 20), None);
+        assert_eq!(pixel(&image, 4, 10), [255, 0, 0, 255]);
+        assert_eq!(pixel(&image, 15, 10)[3], 0);
+    }
+
+    #[test]
+    fn centers_the_svg_when_the_aspect_ratio_differs() {
+        let svg = Svg::parse(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+                <rect width="10" height="10" fill="blue"/>
+            </svg>"#,
+        )
+        .unwrap();
+        let image = svg.rasterize((30, 10), None);
+        assert_eq!(pixel(&image, 2, 5)[3], 0);
+        assert_eq!(pixel(&image, 15, 5), [0, 0, 255, 255]);
+        assert_eq!(pixel(&image, 27, 5)[3], 0);
+    }
+
+    #[test]
+    fn draws_definitions_gradients_and_clip_paths() {
+        let svg = Svg::parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 10 10">
+                <defs>
+                    <linearGradient id="fade">
+                        <stop offset="0" stop-color="#000"/>
+                        <stop offset="1" stop-color="#fff"/>
+                    </linearGradient>
+                    <clipPath id="top"><rect width="10" height="5"/></clipPath>
+                    <rect id="square" width="10" height="10"/>
+                </defs>
+                <use xlink:href="#square" fill="url(#fade)" clip-path="url(#top)"/>
+            </svg>"##,
+        )
+        .unwrap();
+        let image = svg.rasterize((10, 10), None);
+        let (left, right) = (pixel(&image, 0, 2), pixel(&image, 9, 2));
+        assert_eq!(left[3], 255);
+        assert!(left[0] < right[0]);
+        assert_eq!(pixel(&image, 5, 8)[3], 0);
+    }
+
+    #[test]
+    fn tint_replaces_the_colors_and_multiplies_the_alpha() {
+        let svg = Svg::parse(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 4 4">
+                <rect width="4" height="4" fill="black"/>
+            </svg>"#,
+        )
+        .unwrap();
+        let image = svg.rasterize((4, 4), Some(Rgba::new(0.0, 1.0, 0.0, 0.5)));
+        assert_eq!(pixel(&image, 1, 1), [0, 255, 0, 128]);
+    }
+
+    #[test]
+    fn invalid_markup_fails() {
+        let error = Svg::parse("<svg").unwrap_err();
+        assert!(matches!(error, ImageError::Svg(_)));
+        assert!(error.to_string().starts_with("couldn't parse the svg"));
+        assert!(Svg::parse(r#"<svg xmlns="http://www.w3.org/2000/svg"/>"#).is_ok());
+        assert!(matches!(
+            Svg::parse(r#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="5"/>"#),
+            Err(ImageError::Svg(_))
+        ));
+    }
+}
@@ -2,7 +2,7 @@ use taffy::Layout;
 use wgpu::RenderPass;
 
 use crate::{core::RenderingCore, rendering::helpers::AbstractBuffer};
-use hyst_math::Rect;
+use hyst_math::{Affine, Rect};
 
 #[derive(Debug, Clone, Copy)]
 pub enum SizeMethod {
@@ -12,8 +12,25 @@ pub enum SizeMethod {
 
 pub trait Mesh {
     fn draw(&self, pass: &mut RenderPass);
-    ///Gets the screen_size uniform buffer. It's used for modifying the values of it when the window is resized
-    fn screen_size(&mut self) -> &mut AbstractBuffer<[f32; 2]>;
     fn area_buffer(&mut self) -> &mut AbstractBuffer<Rect>;
-    fn resize(&mut self, core: &RenderingCore, screen_size: (f32, f32), layout: &Layout);
+    ///Gets the transform uniform buffer, applied to the positions of the mesh in pixels
+    fn transform_buffer(&mut self) -> &mut AbstractBuffer<Affine>;
+    ///Applies the given `transform` to the mesh
+    fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        let buffer = self.transform_buffer();
+        if buffer.inner() != transform {
+            buffer.write_with(core, *transform);
+        }
+    }
+    ///Gets the opacity uniform buffer, which multiplies the alpha of everything the mesh draws
+    fn opacity_buffer(&mut self) -> &mut AbstractBuffer<f32>;
+    ///Sets the `opacity` the mesh is drawn with
+    fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        let buffer = self.opacity_buffer();
+        if *buffer.inner() != opacity {
+            buffer.write_with(core, opacity);
+        }
+    }
+    ///Moves the mesh to the given `layout`. The size of the screen is on the global uniforms
+    fn resize(&mut self, core: &RenderingCore, layout: &Layout);
 }
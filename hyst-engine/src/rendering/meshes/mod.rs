@@ -1,5 +1,9 @@
+pub mod clip;
 pub mod container;
 pub mod image;
 mod mesh;
+pub mod quads;
+pub mod shapes;
+pub mod sprites;
 pub mod text;
 pub use mesh::*;
@@ -0,0 +1,105 @@
+mod shader;
+pub use shader::*;
+
+use crate::{
+    AbstractBuffer, BufferType, core::RenderingCore, meshes::container::ContainerInput,
+    shaders::HystShader,
+};
+use hyst_math::{Affine, Rect, vectors::Rgba};
+
+///A batch of solid colored rectangles drawn in a single draw call. Rects are given in pixels, relative to the top left corner of the screen.
+///Used for small things that don't deserve an element for each, such as text decorations and selections.
+#[derive(Debug)]
+pub struct Quads {
+    shader: QuadsShader,
+    vertices: Option<wgpu::Buffer>,
+    index: Option<wgpu::Buffer>,
+    capacity: usize,
+    indices_len: u32,
+    transform: AbstractBuffer<Affine>,
+    opacity: AbstractBuffer<f32>,
+}
+
+impl Quads {
+    pub fn new(core: &mut RenderingCore) -> Self {
+        let transform = AbstractBuffer::new(core, Affine::IDENTITY, BufferType::Uniform);
+        let opacity = AbstractBuffer::new(core, 1.0, BufferType::Uniform);
+        Self {
+            shader: QuadsShader::create(core, &transform, &opacity),
+            vertices: None,
+            index: None,
+            capacity: 0,
+            indices_len: 0,
+            transform,
+            opacity,
+        }
+    }
+
+    ///Replaces the quads drawn by this batch with the given ones
+    pub fn set_quads(&mut self, core: &RenderingCore, quads: &[(Rect, Rgba)]) {
+        let vertices = quads
+            .iter()
+            .flat_map(|(rect, color)| {
+                let (x, y) = (rect.position().x(), rect.position().y());
+                let (w, h) = (rect.size().x(), rect.size().y());
+                [
+                    ContainerInput::solid(x, y, *color),
+                    ContainerInput::solid(x + w, y, *color),
+                    ContainerInput::solid(x, y + h, *color),
+                    ContainerInput::solid(x + w, y + h, *color),
+                ]
+            })
+            .collect::<Vec<_>>();
+        self.indices_len = quads.len() as u32 * 6;
+        if quads.is_empty() {
+            return;
+        }
+        if quads.len() > self.capacity {
+            let indices = (0..quads.len() as u16)
+                .flat_map(|quad| {
+                    let base = quad * 4;
+                    [base, base + 1, base + 2, base + 2, base + 1, base + 3]
+                })
+                .collect::<Vec<_>>();
+            self.vertices = Some(core.create_vertex_buffer(&vertices, None));
+            self.index = Some(core.create_index_buffer(&indices, None));
+            self.capacity = quads.len();
+        } else if let Some(ref buffer) = self.vertices {
+            core.write_buffer(&vertices, buffer);
+        }
+    }
+
+    ///Applies the given `transform` to the quads
+    pub fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        if self.transform.inner() != transform {
+            self.transform.write_with(core, *transform);
+        }
+    }
+
+    ///Sets the `opacity` the quads are drawn with
+    pub fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        if *self.opacity.inner() != opacity {
+            self.opacity.write_with(core, opacity);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices_len == 0
+    }
+
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) {
+        let (Some(vertices), Some(index)) = (&self.vertices, &self.index) else {
+            return;
+        };
+        if self.is_empty() {
+            return;
+        }
+        pass.set_pipeline(self.shader.pipeline());
+        for (idx, bind_group) in self.shader.bind_groups().iter().enumerate() {
+            pass.set_bind_group(idx as u32, bind_group, &[]);
+        }
+        pass.set_index_buffer(index.slice(..), wgpu::IndexFormat::Uint16);
+        pass.set_vertex_buffer(0, vertices.slice(..));
+        pass.draw_indexed(0..self.indices_len, 0, 0..1);
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use hyst_math::Affine;
+
+use crate::core::RenderingCore;
+use crate::meshes::container::ContainerInput;
+use crate::shaders::{HystConstructor, HystShader, ShaderCreationOptions, ShaderInput};
+use crate::{AbstractBuffer, BindGroupConfig};
+
+#[derive(Debug)]
+pub struct QuadsShader {
+    module: Arc<wgpu::ShaderModule>,
+    bindgroups: Vec<wgpu::BindGroup>,
+    layouts: Vec<wgpu::BindGroupLayout>,
+    pipeline: std::sync::Arc<wgpu::RenderPipeline>,
+}
+
+impl QuadsShader {
+    ///Creates the shader which draws solid colored triangles, with the given uniforms
+    pub(crate) fn create(
+        core: &mut RenderingCore,
+        transform: &AbstractBuffer<Affine>,
+        opacity: &AbstractBuffer<f32>,
+    ) -> Self {
+        core.create_shader(
+            ShaderCreationOptions::new(
+                "quads",
+                &std::fs::read_to_string("./shaders/quads.wgsl").unwrap(),
+            )
+            .group(
+                BindGroupConfig::new()
+                    .uniform(0, wgpu::ShaderStages::VERTEX, transform.inner_buffer())
+                    .uniform(1, wgpu::ShaderStages::FRAGMENT, opacity.inner_buffer()),
+            ),
+        )
+    }
+}
+
+impl HystConstructor for QuadsShader {
+    fn new(
+        module: Arc<wgpu::ShaderModule>,
+        bindgroups: Vec<wgpu::BindGroup>,
+        layouts: Vec<wgpu::BindGroupLayout>,
+        pipeline: std::sync::Arc<wgpu::RenderPipeline>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            module,
+            bindgroups,
+            layouts,
+            pipeline,
+        }
+    }
+
+    fn name() -> &'static str {
+        "quads"
+    }
+    fn shader_inputs() -> Vec<wgpu::VertexBufferLayout<'static>> {
+        vec![ContainerInput::LAYOUT]
+    }
+}
+
+impl HystShader for QuadsShader {
+    fn module(&self) -> &Arc<wgpu::ShaderModule> {
+        &self.module
+    }
+    fn pipeline(&self) -> &wgpu::RenderPipeline {
+        &self.pipeline
+    }
+    fn bind_group_layouts(&self) -> Option<&[wgpu::BindGroupLayout]> {
+        Some(&self.layouts)
+    }
+    fn bind_groups(&self) -> &[wgpu::BindGroup] {
+        &self.bindgroups
+    }
+}
@@ -0,0 +1,111 @@
+use hyst_math::{Affine, vectors::Rgba};
+
+use crate::{
+    AbstractBuffer, BufferType,
+    core::RenderingCore,
+    meshes::{container::ContainerInput, quads::QuadsShader},
+    path::Triangles,
+    shaders::HystShader,
+};
+
+///A batch of solid colored triangles drawn in a single draw call, such as tessellated paths. Vertices are given in pixels and
+///moved by the transform, so they can be relative to the element which draws them
+#[derive(Debug)]
+pub struct Shapes {
+    shader: QuadsShader,
+    vertices: Option<wgpu::Buffer>,
+    indices: Option<wgpu::Buffer>,
+    ///Amount of vertices and of indices the buffers have room for
+    capacity: (usize, usize),
+    indices_len: u32,
+    transform: AbstractBuffer<Affine>,
+    opacity: AbstractBuffer<f32>,
+}
+
+impl Shapes {
+    pub fn new(core: &mut RenderingCore) -> Self {
+        let transform = AbstractBuffer::new(core, Affine::IDENTITY, BufferType::Uniform);
+        let opacity = AbstractBuffer::new(core, 1.0, BufferType::Uniform);
+        Self {
+            shader: QuadsShader::create(core, &transform, &opacity),
+            vertices: None,
+            indices: None,
+            capacity: (0, 0),
+            indices_len: 0,
+            transform,
+            opacity,
+        }
+    }
+
+    ///Replaces the triangles drawn by this batch with the given ones, each of them with it's color
+    pub fn set_shapes(&mut self, core: &RenderingCore, shapes: &[(Triangles, Rgba)]) {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for (triangles, color) in shapes {
+            let base = vertices.len() as u32;
+            vertices.extend(
+                triangles
+                    .vertices
+                    .iter()
+                    .map(|vertex| ContainerInput::solid(vertex.x(), vertex.y(), *color)),
+            );
+            indices.extend(triangles.indices.iter().map(|idx| base + idx));
+        }
+        self.indices_len = indices.len() as u32;
+        if indices.is_empty() {
+            return;
+        }
+        match self.vertices {
+            Some(ref buffer) if vertices.len() <= self.capacity.0 => {
+                core.write_buffer(&vertices, buffer)
+            }
+            _ => {
+                self.vertices = Some(core.create_vertex_buffer(&vertices, Some("shapes")));
+                self.capacity.0 = vertices.len();
+            }
+        }
+        match self.indices {
+            Some(ref buffer) if indices.len() <= self.capacity.1 => {
+                core.write_buffer(&indices, buffer)
+            }
+            _ => {
+                self.indices = Some(core.create_index_buffer_u32(&indices, Some("shapes")));
+                self.capacity.1 = indices.len();
+            }
+        }
+    }
+
+    ///Applies the given `transform` to the triangles
+    pub fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        if self.transform.inner() != transform {
+            self.transform.write_with(core, *transform);
+        }
+    }
+
+    ///Sets the `opacity` the triangles are drawn with
+    pub fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        if *self.opacity.inner() != opacity {
+            self.opacity.write_with(core, opacity);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices_len == 0
+    }
+
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) {
+        let (Some(vertices), Some(indices)) = (&self.vertices, &self.indices) else {
+            return;
+        };
+        if self.is_empty() {
+            return;
+        }
+        pass.set_pipeline(self.shader.pipeline());
+        for (idx, bind_group) in self.shader.bind_groups().iter().enumerate() {
+            pass.set_bind_group(idx as u32, bind_group, &[]);
+        }
+        pass.set_index_buffer(indices.slice(..), wgpu::IndexFormat::Uint32);
+        pass.set_vertex_buffer(0, vertices.slice(..));
+        pass.draw_indexed(0..self.indices_len, 0, 0..1);
+    }
+}
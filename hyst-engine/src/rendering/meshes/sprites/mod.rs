@@ -0,0 +1,126 @@
+mod shader;
+pub use shader::*;
+
+use hyst_math::{Affine, Rect, vectors::Rgba};
+
+use crate::{AbstractBuffer, BufferType, GpuImage, core::RenderingCore, shaders::HystShader};
+
+///Area of a texture drawn on some rect
+#[derive(Debug, Clone, Copy)]
+pub struct Sprite {
+    ///Where the sprite is drawn, in pixels
+    pub rect: Rect,
+    ///Area of the texture which is shown, in uv coordinates, from 0 to 1
+    pub source: Rect,
+    ///Color the texels are multiplied with
+    pub tint: Rgba,
+}
+
+///A batch of quads showing parts of the same texture, drawn in a single draw call. Vertices are given in pixels and moved by the
+///transform, so they can be relative to the element which draws them
+#[derive(Debug)]
+pub struct Sprites {
+    shader: SpritesShader,
+    image: GpuImage,
+    vertices: Option<wgpu::Buffer>,
+    indices: Option<wgpu::Buffer>,
+    ///Amount of sprites the buffers have room for
+    capacity: usize,
+    indices_len: u32,
+    transform: AbstractBuffer<Affine>,
+    opacity: AbstractBuffer<f32>,
+}
+
+impl Sprites {
+    ///Creates an empty batch of sprites of the given `image`
+    pub fn new(core: &mut RenderingCore, image: GpuImage) -> Self {
+        let transform = AbstractBuffer::new(core, Affine::IDENTITY, BufferType::Uniform);
+        let opacity = AbstractBuffer::new(core, 1.0, BufferType::Uniform);
+        Self {
+            shader: SpritesShader::create(core, &transform, &opacity, &image),
+            image,
+            vertices: None,
+            indices: None,
+            capacity: 0,
+            indices_len: 0,
+            transform,
+            opacity,
+        }
+    }
+
+    #[inline]
+    ///Gets the image the sprites are parts of
+    pub fn image(&self) -> &GpuImage {
+        &self.image
+    }
+
+    ///Replaces the sprites drawn by this batch with the given ones
+    pub fn set_sprites(&mut self, core: &RenderingCore, sprites: &[Sprite]) {
+        let mut vertices = Vec::with_capacity(sprites.len() * 4);
+        let mut indices = Vec::with_capacity(sprites.len() * 6);
+        for sprite in sprites {
+            let base = vertices.len() as u32;
+            let (position, size) = (sprite.rect.position(), sprite.rect.size());
+            let (uv, uv_size) = (sprite.source.position(), sprite.source.size());
+            for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)] {
+                vertices.push(SpriteInput::new(
+                    position.x() + size.x() * x,
+                    position.y() + size.y() * y,
+                    uv.x() + uv_size.x() * x,
+                    uv.y() + uv_size.y() * y,
+                    sprite.tint,
+                ));
+            }
+            indices.extend([base, base + 1, base + 2, base + 2, base + 1, base + 3]);
+        }
+        self.indices_len = indices.len() as u32;
+        if sprites.is_empty() {
+            return;
+        }
+        match (&self.vertices, &self.indices) {
+            (Some(vertex_buffer), Some(index_buffer)) if sprites.len() <= self.capacity => {
+                core.write_buffer(&vertices, vertex_buffer);
+                core.write_buffer(&indices, index_buffer);
+            }
+            _ => {
+                self.vertices = Some(core.create_vertex_buffer(&vertices, Some("sprites")));
+                self.indices = Some(core.create_index_buffer_u32(&indices, Some("sprites")));
+                self.capacity = sprites.len();
+            }
+        }
+    }
+
+    ///Applies the given `transform` to the sprites
+    pub fn set_transform(&mut self, core: &RenderingCore, transform: &Affine) {
+        if self.transform.inner() != transform {
+            self.transform.write_with(core, *transform);
+        }
+    }
+
+    ///Sets the `opacity` the sprites are drawn with
+    pub fn set_opacity(&mut self, core: &RenderingCore, opacity: f32) {
+        if *self.opacity.inner() != opacity {
+            self.opacity.write_with(core, opacity);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices_len == 0
+    }
+
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) {
+        let (Some(vertices), Some(indices)) = (&self.vertices, &self.indices) else {
+            return;
+        };
+        if self.is_empty() {
+            return;
+        }
+        pass.set_pipeline(self.shader.pipeline());
+        for (idx, bind_group) in self.shader.bind_groups().iter().enumerate() {
+            pass.set_bind_group(idx as u32, bind_group, &[]);
+        }
+        pass.set_index_buffer(indices.slice(..), wgpu::IndexFormat::Uint32);
+        pass.set_vertex_buffer(0, vertices.slice(..));
+        pass.draw_indexed(0..self.indices_len, 0, 0..1);
+    }
+}
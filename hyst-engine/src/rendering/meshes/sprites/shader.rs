@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use bytemuck::{Pod, Zeroable};
+use hyst_math::{
+    Affine,
+    vectors::{Rgba, Vec2f32},
+};
+
+use crate::core::RenderingCore;
+use crate::shaders::{HystConstructor, HystShader, ShaderCreationOptions, ShaderInput};
+use crate::{AbstractBuffer, BindGroupConfig, GpuImage};
+
+///Vertex of a sprite, in pixels, with it's coordinates on the texture and the color it's multiplied with
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SpriteInput {
+    position: Vec2f32,
+    uv: Vec2f32,
+    tint: Rgba,
+}
+
+impl SpriteInput {
+    pub fn new(x: f32, y: f32, u: f32, v: f32, tint: Rgba) -> Self {
+        Self {
+            position: Vec2f32::new(x, y),
+            uv: Vec2f32::new(u, v),
+            tint,
+        }
+    }
+}
+
+impl ShaderInput for SpriteInput {
+    const LAYOUT: wgpu::VertexBufferLayout<'static> = wgpu::VertexBufferLayout {
+        array_stride: std::mem::size_of::<Self>() as u64,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &wgpu::vertex_attr_array![
+            0 => Float32x2,
+            1 => Float32x2,
+            2 => Float32x4
+        ],
+    };
+}
+
+#[derive(Debug)]
+pub struct SpritesShader {
+    module: Arc<wgpu::ShaderModule>,
+    bindgroups: Vec<wgpu::BindGroup>,
+    layouts: Vec<wgpu::BindGroupLayout>,
+    pipeline: Arc<wgpu::RenderPipeline>,
+}
+
+impl SpritesShader {
+    ///Creates the shader which draws quads of the given `image`, with the given uniforms
+    pub(crate) fn create(
+        core: &mut RenderingCore,
+        transform: &AbstractBuffer<Affine>,
+        opacity: &AbstractBuffer<f32>,
+        image: &GpuImage,
+    ) -> Self {
+        core.create_shader(
+            ShaderCreationOptions::new(
+                "sprites",
+                &std::fs::read_to_string("./shaders/sprites.wgsl").unwrap(),
+            )
+            .group(
+                BindGroupConfig::new()
+                    .uniform(0, wgpu::ShaderStages::VERTEX, transform.inner_buffer())
+                    .uniform(1, wgpu::ShaderStages::FRAGMENT, opacity.inner_buffer()),
+            )
+            .group(
+                BindGroupConfig::new()
+                    .texture(
+                        0,
+                        wgpu::ShaderStages::FRAGMENT,
+                        image.view(),
+                        wgpu::TextureViewDimension::D2,
+                        wgpu::TextureSampleType::Float { filterable: true },
+                    )
+                    .sampler(
+                        1,
+                        wgpu::ShaderStages::FRAGMENT,
+                        image.sampler(),
+                        wgpu::SamplerBindingType::Filtering,
+                    ),
+            ),
+        )
+    }
+}
+
+impl HystConstructor for SpritesShader {
+    fn new(
+        module: Arc<wgpu::ShaderModule>,
+        bindgroups: Vec<wgpu::BindGroup>,
+        layouts: Vec<wgpu::BindGroupLayout>,
+        pipeline: Arc<wgpu::RenderPipeline>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            module,
+            bindgroups,
+            layouts,
+            pipeline,
+        }
+    }
+    fn shader_inputs() -> Vec<wgpu::VertexBufferLayout<'static>> {
+        vec![SpriteInput::LAYOUT]
+    }
+    fn blend() -> wgpu::BlendState {
+        //The fragment shader multiplies the colors by their alpha
+        wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING
+    }
+    fn name() -> &'static str {
+        "sprites"
+    }
+}
+
+impl HystShader for SpritesShader {
+    fn module(&self) -> &Arc<wgpu::ShaderModule> {
+        &self.module
+    }
+    fn pipeline(&self) -> &wgpu::RenderPipeline {
+        &self.pipeline
+    }
+    fn bind_group_layouts(&self) -> Option<&[wgpu::BindGroupLayout]> {
+        Some(&self.layouts)
+    }
+    fn bind_groups(&self) -> &[wgpu::BindGroup] {
+        &self.bindgroups
+    }
+}
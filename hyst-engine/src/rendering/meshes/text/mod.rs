@@ -1,24 +1,71 @@
+mod selection;
+
 use glyphon::Buffer;
-use hyst_math::vectors::Vec2f32;
+use hyst_math::{Rect, vectors::Rgba};
+use taffy::{AvailableSpace, Layout, Size};
 
-use crate::core::RenderingCore;
+use crate::{
+    core::RenderingCore,
+    text::{RichText, TextStyle, TextWrap, visible_runs},
+};
 
 pub struct Text {
-    position: Vec2f32,
+    rect: Rect,
     buffer: Buffer,
+    ///Truncated copy of the buffer, drawn instead of it when the content doesn't fit and the style requires an ellipsis
+    display: Option<Buffer>,
+    ///Offset of the content from the top of the rect, used for vertical alignment
+    offset: f32,
+    ///Height of the visible lines
+    content_height: f32,
+    style: TextStyle,
 }
 
 impl Text {
     pub fn new(
         manager: &mut RenderingCore,
-        position: Vec2f32,
         text: &str,
         font_size: f32,
         line_height: f32,
+        style: TextStyle,
     ) -> Self {
-        let buffer = manager.create_text_buffer(font_size, line_height, text);
-        Self { position, buffer }
+        let buffer = manager.create_text_buffer(font_size, line_height, text, &style);
+        Self {
+            rect: Rect::from_xywh(0.0, 0.0, 0.0, 0.0),
+            buffer,
+            display: None,
+            offset: 0.0,
+            content_height: 0.0,
+            style,
+        }
     }
+    ///Creates a text whose content are the spans of the given `text`
+    pub fn rich(
+        manager: &mut RenderingCore,
+        text: &RichText,
+        font_size: f32,
+        line_height: f32,
+        style: TextStyle,
+    ) -> Self {
+        let mut out = Self::new(manager, "", font_size, line_height, style);
+        out.set_rich_text(manager, text);
+        out
+    }
+
+    ///Sets the content of this text to be the given `text`
+    pub fn set_text(&mut self, core: &mut RenderingCore, text: &str) {
+        core.set_text(&mut self.buffer, text, &self.style);
+    }
+
+    ///Sets the content of this text to be the spans of the given `text`
+    pub fn set_rich_text(&mut self, core: &mut RenderingCore, text: &RichText) {
+        core.set_rich_text(&mut self.buffer, text, &self.style);
+    }
+
+    pub fn style(&self) -> &TextStyle {
+        &self.style
+    }
+
     #[inline]
     pub fn buffer(&self) -> &Buffer {
         &self.buffer
@@ -29,15 +76,172 @@ impl Text {
         &mut self.buffer
     }
 
+    #[inline]
+    ///The buffer which is drawn. It's a truncated copy of the content when it doesn't fit and an ellipsis is required.
+    pub fn display_buffer(&self) -> &Buffer {
+        self.display.as_ref().unwrap_or(&self.buffer)
+    }
+
     #[inline]
     ///The left corner where this text is located at.
     pub fn x(&self) -> f32 {
-        self.position.x()
+        self.rect.position().x()
     }
 
     #[inline]
-    ///The top corner where this text is located at.
+    ///The top corner where this text is located at, considering it's vertical alignment.
     pub fn y(&self) -> f32 {
-        self.position.y()
+        self.rect.position().y() + self.offset
+    }
+
+    #[inline]
+    ///Height of the lines of this text, which may overflow it's rect
+    pub fn content_height(&self) -> f32 {
+        self.content_height
+    }
+
+    #[inline]
+    ///The area this text is drawn at. Everything outside of it is clipped.
+    pub fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    #[inline]
+    ///The area where the visible lines of this text are drawn.
+    pub fn clip_rect(&self) -> Rect {
+        match self.style.max_lines {
+            Some(_) => Rect::from_xywh(
+                self.x(),
+                self.y(),
+                self.rect.size().x(),
+                self.content_height.min(self.rect.size().y()),
+            ),
+            None => self.rect,
+        }
+    }
+
+    ///Measures the size required by this text given the `known` dimensions of it's layout and the `available` space of it's parent.
+    pub fn measure(
+        &mut self,
+        core: &mut RenderingCore,
+        known: Size<Option<f32>>,
+        available: Size<AvailableSpace>,
+    ) -> Size<f32> {
+        let (width, min_content) = match (known.width, available.width) {
+            (_, _) if self.style.wrap == TextWrap::None => (None, false),
+            (Some(width), _) | (None, AvailableSpace::Definite(width)) => (Some(width), false),
+            (None, AvailableSpace::MinContent) => (Some(0.0), true),
+            (None, AvailableSpace::MaxContent) => (None, false),
+        };
+        let size = core.measure(&mut self.buffer, width, min_content, self.style.max_lines);
+        Size {
+            width: known.width.unwrap_or(size.x()),
+            height: known.height.unwrap_or(size.y()),
+        }
+    }
+
+    ///Moves this text to the content box of the given `layout`, wraps it to it's width and truncates it if required.
+    pub fn resize(&mut self, core: &mut RenderingCore, layout: &Layout) {
+        let Size { width, height } = layout.content_box_size();
+        self.rect = Rect::from_xywh(
+            layout.content_box_x(),
+            layout.content_box_y(),
+            width,
+            height,
+        );
+        core.set_size(&mut self.buffer, Some(width), Some(height));
+        self.display = match self.style.ellipsis {
+            true => core.ellipsize(&self.buffer, width, height, self.style.max_lines),
+            false => None,
+        };
+        let buffer = self.display_buffer();
+        self.content_height = buffer
+            .layout_runs()
+            .take(visible_runs(buffer, height, self.style.max_lines))
+            .map(|run| run.line_height)
+            .sum();
+        self.offset = self
+            .style
+            .vertical_align
+            .offset(height - self.content_height);
+    }
+
+    ///Gets the rects of the underlines and strikethroughs of the spans of the given `text`, which must be the content of this Text.
+    ///Rects of glyphs of the same span which are on the same line are merged.
+    pub fn decorations(&self, text: &RichText, default_color: Rgba) -> Vec<(Rect, Rgba)> {
+        let mut out = Vec::new();
+        if !text.is_decorated() {
+            return out;
+        }
+        let (x, y) = (self.x(), self.y());
+        for run in self.display_buffer().layout_runs() {
+            if run.line_top + self.offset > self.rect.size().y() {
+                break;
+            }
+            //(span index, left, right, font size)
+            let mut current: Option<(usize, f32, f32, f32)> = None;
+            let mut flush = |current: Option<(usize, f32, f32, f32)>| {
+                let Some((idx, left, right, font_size)) = current else {
+                    return;
+                };
+                let Some(span) = text.span(idx) else {
+                    return;
+                };
+                let color = span.color.unwrap_or(default_color);
+                let thickness = (font_size / 14.0).max(1.0);
+                if span.underline {
+                    out.push((
+                        Rect::from_xywh(
+                            x + left,
+                            y + run.line_y + thickness,
+                            right - left,
+                            thickness,
+                        ),
+                        color,
+                    ));
+                }
+                if span.strikethrough {
+                    out.push((
+                        Rect::from_xywh(
+                            x + left,
+                            y + run.line_y - font_size * 0.3,
+                            right - left,
+                            thickness,
+                        ),
+                        color,
+                    ));
+                }
+            };
+            for glyph in run.glyphs {
+                match current {
+                    Some((idx, left, right, size)) if idx == glyph.metadata => {
+                        current = Some((
+                            idx,
+                            left.min(glyph.x),
+                            right.max(glyph.x + glyph.w),
+                            size.max(glyph.font_size),
+                        ))
+                    }
+                    _ => {
+                        flush(current);
+                        current =
+                            Some((glyph.metadata, glyph.x, glyph.x + glyph.w, glyph.font_size));
+                    }
+                }
+            }
+            flush(current);
+        }
+        out
+    }
+
+    ///Gets the link id of the span of the given `text` at the given position, in the coordinates of the layout, if any. `text` must be the content of this Text.
+    pub fn link_at(&self, text: &RichText, x: f32, y: f32) -> Option<u32> {
+        let (x, y) = (x - self.x(), y - self.y());
+        self.display_buffer()
+            .layout_runs()
+            .filter(|run| y >= run.line_top && y < run.line_top + run.line_height)
+            .flat_map(|run| run.glyphs.iter())
+            .find(|glyph| x >= glyph.x && x < glyph.x + glyph.w)
+            .and_then(|glyph| text.span(glyph.metadata)?.link)
     }
 }
@@ -0,0 +1,256 @@
+use std::ops::Range;
+
+use glyphon::{Affinity, Buffer, Cursor};
+use hyst_math::Rect;
+
+use super::Text;
+use crate::text::ELLIPSIS;
+
+//Hit testing and selection of texts. Offsets are byte offsets on the whole content of the text, line endings included, even when
+//a truncated copy of it is drawn. The ellipsis of a truncated line stands for the rest of that line.
+//Positions and rects are in the coordinates of the layout, before the transforms of the text are applied.
+
+///Converts the given `cursor` into a byte offset on the content of the given `buffer`
+fn cursor_to_offset(buffer: &Buffer, cursor: Cursor) -> usize {
+    buffer
+        .lines
+        .iter()
+        .take(cursor.line)
+        .map(|line| line.text().len() + line.ending().as_str().len())
+        .sum::<usize>()
+        + cursor.index
+}
+
+///Converts the given byte `offset` on the content of the given `buffer` into a cursor. Offsets after the content are clamped to it's end.
+fn offset_to_cursor(buffer: &Buffer, mut offset: usize) -> Cursor {
+    for (idx, line) in buffer.lines.iter().enumerate() {
+        let len = line.text().len();
+        if offset <= len {
+            return Cursor::new(idx, offset);
+        }
+        offset -= (len + line.ending().as_str().len()).min(offset);
+    }
+    let line = buffer.lines.len().saturating_sub(1);
+    Cursor::new(
+        line,
+        buffer.lines.get(line).map_or(0, |line| line.text().len()),
+    )
+}
+
+impl Text {
+    ///Gets the byte of the given `line` where it was cut, if it's truncated on the drawn buffer
+    fn cut_of(&self, line: usize) -> Option<usize> {
+        let display = self.display.as_ref()?.lines.get(line)?;
+        let source = self.buffer.lines.get(line)?;
+        (display.text() != source.text()).then(|| display.text().len() - ELLIPSIS.len())
+    }
+
+    ///Maps the given `cursor` on the drawn buffer to the content. Cursors after the cut of a truncated line are moved to it's end
+    fn display_to_source(&self, cursor: Cursor) -> Cursor {
+        match self.cut_of(cursor.line) {
+            Some(cut) if cursor.index > cut => {
+                Cursor::new(cursor.line, self.buffer.lines[cursor.line].text().len())
+            }
+            _ => cursor,
+        }
+    }
+
+    ///Maps the given `cursor` on the content to the drawn buffer. Cursors on hidden characters are moved after the ellipsis
+    fn source_to_display(&self, cursor: Cursor) -> Cursor {
+        let Some(display) = self.display.as_ref() else {
+            return cursor;
+        };
+        let Some(line) = display.lines.get(cursor.line) else {
+            let last = display.lines.len().saturating_sub(1);
+            return Cursor::new(
+                last,
+                display.lines.get(last).map_or(0, |line| line.text().len()),
+            );
+        };
+        match self.cut_of(cursor.line) {
+            Some(cut) if cursor.index > cut => Cursor::new(cursor.line, line.text().len()),
+            _ => cursor,
+        }
+    }
+
+    ///Gets the byte offset of the character under the given position.
+    ///Positions outside of the text are mapped to the nearest character.
+    pub fn hit(&self, x: f32, y: f32) -> Option<usize> {
+        let cursor = self.display_buffer().hit(x - self.x(), y - self.y())?;
+        Some(cursor_to_offset(
+            &self.buffer,
+            self.display_to_source(cursor),
+        ))
+    }
+
+    ///Gets the rect of the caret placed before the character at the given byte `offset`.
+    ///The caret is `width` pixels wide and as tall as the line it's on.
+    pub fn caret_rect(&self, offset: usize, width: f32) -> Option<Rect> {
+        let buffer = self.display_buffer();
+        let cursor = self.source_to_display(offset_to_cursor(&self.buffer, offset));
+        buffer
+            .layout_runs()
+            .filter(|run| run.line_i == cursor.line)
+            .find_map(|run| {
+                //Both sides of the offset, so the glyph starting at it and the one ending at it are found
+                let before = Cursor::new_with_affinity(cursor.line, cursor.index, Affinity::Before);
+                let after = Cursor::new_with_affinity(cursor.line, cursor.index, Affinity::After);
+                let (x, _) = run.highlight(before, after).or_else(|| {
+                    //Empty lines have no glyphs to be highlighted
+                    run.glyphs.is_empty().then_some((0.0, 0.0))
+                })?;
+                Some(Rect::from_xywh(
+                    self.x() + x,
+                    self.y() + run.line_top,
+                    width,
+                    run.line_height,
+                ))
+            })
+    }
+
+    ///Gets the rects that highlight the characters inside the given `range` of byte offsets, one for each line.
+    pub fn selection_rects(&self, range: Range<usize>) -> Vec<Rect> {
+        let buffer = self.display_buffer();
+        let start =
+            self.source_to_display(offset_to_cursor(&self.buffer, range.start.min(range.end)));
+        let end =
+            self.source_to_display(offset_to_cursor(&self.buffer, range.start.max(range.end)));
+        buffer
+            .layout_runs()
+            .filter_map(|run| {
+                let (x, w) = run.highlight(start, end)?;
+                Some(Rect::from_xywh(
+                    self.x() + x,
+                    self.y() + run.line_top,
+                    w,
+                    run.line_height,
+                ))
+            })
+            .filter(|rect| rect.size().x() > 0.0)
+            .collect()
+    }
+
+    ///Gets the content of this text inside the given `range` of byte offsets, including the characters hidden by an ellipsis.
+    ///The range is clamped to the content and to char boundaries.
+    pub fn text_in(&self, range: Range<usize>) -> String {
+        let mut content = String::new();
+        for line in self.buffer.lines.iter() {
+            content.push_str(line.text());
+            content.push_str(line.ending().as_str());
+        }
+        let floor = |mut idx: usize| {
+            idx = idx.min(content.len());
+            while !content.is_char_boundary(idx) {
+                idx -= 1;
+            }
+            idx
+        };
+        let (start, end) = (floor(range.start), floor(range.end));
+        content[start.min(end)..end.max(start)].to_string()
+    }
+
+    ///Gets the range of byte offsets between the characters under the given positions, such as the start and the end of a mouse drag.
+    pub fn range_between(&self, start: (f32, f32), end: (f32, f32)) -> Option<Range<usize>> {
+        let start = self.hit(start.0, start.1)?;
+        let end = self.hit(end.0, end.1)?;
+        Some(start.min(end)..start.max(end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glyphon::{Attrs, Family, FontSystem, Metrics, Shaping, Wrap, fontdb};
+    use hyst_math::Rect;
+
+    use super::*;
+    use crate::text::{TextStyle, ellipsize};
+
+    fn font_system() -> FontSystem {
+        let mut db = fontdb::Database::new();
+        db.load_font_data(include_bytes!("../../../../fonts/Inter-Regular.ttf").to_vec());
+        FontSystem::new_with_locale_and_db("en-US".into(), db)
+    }
+
+    ///Creates a text at (10, 20) with the given `content`. When there's a `width`, lines aren't wrapped and are truncated to it
+    fn text(font_sys: &mut FontSystem, content: &str, width: Option<f32>) -> Text {
+        let mut buffer = Buffer::new(font_sys, Metrics::new(10.0, 12.0));
+        buffer.set_wrap(font_sys, Wrap::None);
+        buffer.set_text(
+            font_sys,
+            content,
+            &Attrs::new().family(Family::Name("Inter")),
+            Shaping::Advanced,
+        );
+        buffer.set_size(font_sys, None, None);
+        buffer.shape_until_scroll(font_sys, true);
+        let display = width.and_then(|width| ellipsize(font_sys, &buffer, width, 100.0, None));
+        Text {
+            rect: Rect::from_xywh(10.0, 20.0, width.unwrap_or(500.0), 100.0),
+            buffer,
+            display,
+            offset: 0.0,
+            content_height: 0.0,
+            style: TextStyle::default(),
+        }
+    }
+
+    #[test]
+    fn hit_returns_the_offset_of_the_caret() {
+        let text = text(&mut font_system(), "hello world", None);
+        assert_eq!(text.caret_rect(0, 1.0).unwrap().position().x(), 10.0);
+        for offset in [0, 5, 11] {
+            let caret = text.caret_rect(offset, 1.0).unwrap();
+            let (x, y) = (caret.position().x(), caret.position().y());
+            assert_eq!(text.hit(x + 0.1, y + 1.0), Some(offset));
+        }
+    }
+
+    #[test]
+    fn offsets_include_line_endings() {
+        let text = text(&mut font_system(), "ab\ncd", None);
+        let first = text.caret_rect(0, 1.0).unwrap();
+        let second = text.caret_rect(3, 1.0).unwrap();
+        assert_eq!(first.position().x(), second.position().x());
+        assert_eq!(second.position().y() - first.position().y(), 12.0);
+        assert_eq!(text.hit(10.1, second.position().y() + 1.0), Some(3));
+        assert_eq!(text.text_in(0..5), "ab\ncd");
+        assert_eq!(text.selection_rects(1..4).len(), 2);
+    }
+
+    #[test]
+    fn ranges_are_ordered_and_clamped_to_char_boundaries() {
+        let text = text(&mut font_system(), "añb", None);
+        assert_eq!(text.text_in(0..2), "a");
+        assert_eq!(text.text_in(Range { start: 5, end: 1 }), "ñb");
+        let start = text.caret_rect(3, 1.0).unwrap().position().x();
+        let end = text.caret_rect(1, 1.0).unwrap().position().x();
+        assert_eq!(
+            text.range_between((start + 0.1, 21.0), (end + 0.1, 21.0)),
+            Some(1..3)
+        );
+    }
+
+    #[test]
+    fn truncated_lines_map_back_to_the_content() {
+        let content = "a long first line which doesn't fit\nsecond";
+        let text = text(&mut font_system(), content, Some(60.0));
+        let display = text.display_buffer();
+        assert!(display.lines[0].text().ends_with(ELLIPSIS));
+        let second = content.find("second").unwrap();
+        //The second line starts at the same offset of the content, even if the first one is shorter when drawn
+        let caret = text.caret_rect(second, 1.0).unwrap();
+        assert_eq!(caret.position().x(), 10.0);
+        assert_eq!(text.hit(10.1, caret.position().y() + 1.0), Some(second));
+        //The ellipsis stands for the rest of the line
+        assert_eq!(text.hit(69.0, 21.0), Some(second - 1));
+        let hidden = text.caret_rect(second - 5, 1.0).unwrap();
+        let end = text.caret_rect(second - 1, 1.0).unwrap();
+        assert_eq!(hidden, end);
+        assert!(end.position().x() <= 70.0);
+        let selected = text.range_between((10.1, 21.0), (69.0, 21.0)).unwrap();
+        assert_eq!(
+            text.text_in(selected),
+            "a long first line which doesn't fit"
+        );
+    }
+}
@@ -1,11 +1,15 @@
 pub mod background;
 mod basics;
 pub mod core;
+pub mod effects;
 pub mod elements;
 mod helpers;
 pub mod meshes;
+pub mod path;
 pub mod shaders;
 pub mod text;
+pub mod transform;
 pub mod ui;
+pub mod widgets;
 pub(crate) use basics::*;
 pub(crate) use helpers::*;
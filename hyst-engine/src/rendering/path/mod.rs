@@ -0,0 +1,216 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use hyst_math::{Rect, vectors::Vec2f32};
+
+mod stroke;
+pub use stroke::*;
+mod tessellate;
+pub use tessellate::*;
+
+///Segment of a path. Points are in pixels, relative to the top left corner of what the path is drawn on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    ///Starts a new subpath at the point
+    MoveTo(Vec2f32),
+    LineTo(Vec2f32),
+    ///Quadratic bezier curve with a control point, ending at the second point
+    QuadTo(Vec2f32, Vec2f32),
+    ///Cubic bezier curve with two control points, ending at the third point
+    CubicTo(Vec2f32, Vec2f32, Vec2f32),
+    ///Joins the current point to the start of the subpath with a line
+    Close,
+}
+
+///Outline made of lines and bezier curves, which can be filled and stroked
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+}
+
+impl Path {
+    pub fn builder() -> PathBuilder {
+        PathBuilder::default()
+    }
+
+    #[inline]
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    ///Creates a path with lines joining the given `points` in order
+    pub fn polyline(points: &[Vec2f32]) -> Self {
+        let mut points = points.iter();
+        let Some(first) = points.next() else {
+            return Self::default();
+        };
+        points
+            .fold(
+                Self::builder().move_to(first.x(), first.y()),
+                |builder, point| builder.line_to(point.x(), point.y()),
+            )
+            .build()
+    }
+
+    ///Creates a closed path with lines joining the given `points` in order
+    pub fn polygon(points: &[Vec2f32]) -> Self {
+        let mut path = Self::polyline(points);
+        if !path.is_empty() {
+            path.commands.push(PathCommand::Close);
+        }
+        path
+    }
+
+    pub fn rect(rect: &Rect) -> Self {
+        let (x, y) = (rect.position().x(), rect.position().y());
+        let (width, height) = (rect.size().x(), rect.size().y());
+        Self::polygon(&[
+            Vec2f32::new(x, y),
+            Vec2f32::new(x + width, y),
+            Vec2f32::new(x + width, y + height),
+            Vec2f32::new(x, y + height),
+        ])
+    }
+
+    ///Creates a rect with corners of the given `radius`, limited to half of it's smallest side
+    pub fn rounded_rect(rect: &Rect, radius: f32) -> Self {
+        let (x, y) = (rect.position().x(), rect.position().y());
+        let (width, height) = (rect.size().x(), rect.size().y());
+        let radius = radius.min(width / 2.0).min(height / 2.0);
+        if radius <= 0.0 {
+            return Self::rect(rect);
+        }
+        let (right, bottom) = (x + width, y + height);
+        Self::builder()
+            .arc(right - radius, y + radius, radius, -FRAC_PI_2, FRAC_PI_2)
+            .arc(right - radius, bottom - radius, radius, 0.0, FRAC_PI_2)
+            .arc(x + radius, bottom - radius, radius, FRAC_PI_2, FRAC_PI_2)
+            .arc(x + radius, y + radius, radius, PI, FRAC_PI_2)
+            .close()
+            .build()
+    }
+
+    pub fn circle(x: f32, y: f32, radius: f32) -> Self {
+        Self::builder()
+            .arc(x, y, radius, 0.0, std::f32::consts::TAU)
+            .close()
+            .build()
+    }
+}
+
+///Builds a path segment by segment
+#[derive(Debug, Clone, Default)]
+pub struct PathBuilder {
+    commands: Vec<PathCommand>,
+    ///Point the next segment starts at, if there's a subpath
+    current: Option<Vec2f32>,
+    ///Point the current subpath started at
+    start: Vec2f32,
+}
+
+impl PathBuilder {
+    ///Starts a new subpath at the given point
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        let point = Vec2f32::new(x, y);
+        self.commands.push(PathCommand::MoveTo(point));
+        self.current = Some(point);
+        self.start = point;
+        self
+    }
+
+    ///Adds a line from the current point to the given one. Without a subpath, it starts one there instead
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        if self.current.is_none() {
+            return self.move_to(x, y);
+        }
+        let point = Vec2f32::new(x, y);
+        self.commands.push(PathCommand::LineTo(point));
+        self.current = Some(point);
+        self
+    }
+
+    ///Adds a quadratic bezier curve from the current point to (`x`, `y`), bent towards the control point (`cx`, `cy`)
+    pub fn quad_to(self, cx: f32, cy: f32, x: f32, y: f32) -> Self {
+        let mut this = self.ensure_subpath(cx, cy);
+        let point = Vec2f32::new(x, y);
+        this.commands
+            .push(PathCommand::QuadTo(Vec2f32::new(cx, cy), point));
+        this.current = Some(point);
+        this
+    }
+
+    ///Adds a cubic bezier curve from the current point to (`x`, `y`), with the control points (`c1x`, `c1y`) and (`c2x`, `c2y`)
+    pub fn cubic_to(self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) -> Self {
+        let mut this = self.ensure_subpath(c1x, c1y);
+        let point = Vec2f32::new(x, y);
+        this.commands.push(PathCommand::CubicTo(
+            Vec2f32::new(c1x, c1y),
+            Vec2f32::new(c2x, c2y),
+            point,
+        ));
+        this.current = Some(point);
+        this
+    }
+
+    ///Adds an arc of the circle centered at (`x`, `y`) with the given `radius`. It's joined to the current point with a line,
+    ///if there's a subpath.
+    /// # Arguments
+    /// * `start` - Angle the arc starts at, in radians. 0 is the right of the circle, and angles grow clockwise on the screen
+    /// * `sweep` - Angle the arc covers, in radians. It goes clockwise when it's positive and counter clockwise otherwise
+    pub fn arc(self, x: f32, y: f32, radius: f32, start: f32, sweep: f32) -> Self {
+        let point_at = |angle: f32| {
+            let (sin, cos) = angle.sin_cos();
+            Vec2f32::new(x + cos * radius, y + sin * radius)
+        };
+        let first = point_at(start);
+        let mut this = self.line_to(first.x(), first.y());
+        //Each curve covers a quarter of the circle at most, so it stays close to it
+        let segments = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as u32;
+        let step = sweep / segments as f32;
+        let handle = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+        for segment in 0..segments {
+            let (from, to) = (
+                start + step * segment as f32,
+                start + step * (segment + 1) as f32,
+            );
+            let (sin_from, cos_from) = from.sin_cos();
+            let (sin_to, cos_to) = to.sin_cos();
+            let (begin, end) = (point_at(from), point_at(to));
+            this = this.cubic_to(
+                begin.x() - sin_from * handle,
+                begin.y() + cos_from * handle,
+                end.x() + sin_to * handle,
+                end.y() - cos_to * handle,
+                end.x(),
+                end.y(),
+            );
+        }
+        this
+    }
+
+    ///Closes the current subpath with a line to it's start. The next segment starts a new subpath from there
+    pub fn close(mut self) -> Self {
+        if self.current.is_some() {
+            self.commands.push(PathCommand::Close);
+            self.current = Some(self.start);
+        }
+        self
+    }
+
+    pub fn build(self) -> Path {
+        Path {
+            commands: self.commands,
+        }
+    }
+
+    ///Starts a subpath at the given point if there's none, so curves always have a point to start from
+    fn ensure_subpath(self, x: f32, y: f32) -> Self {
+        match self.current {
+            Some(_) => self,
+            None => self.move_to(x, y),
+        }
+    }
+}
@@ -0,0 +1,438 @@
+use hyst_math::vectors::Vec2f32;
+
+use super::{Contour, FillRule, Path, TOLERANCE, Triangles, cross, fill_contours, length};
+
+///Shape of the corners where the segments of a stroke meet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineJoin {
+    ///The outer edges are extended until they meet, unless that's further than the miter limit, in which case it's beveled
+    #[default]
+    Miter,
+    Round,
+    ///The outer corners are joined with a straight line
+    Bevel,
+}
+
+///Shape of the ends of open strokes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineCap {
+    ///The stroke ends right at the end of the path
+    #[default]
+    Butt,
+    ///Half a circle is added at the end
+    Round,
+    ///The stroke is extended by half it's width
+    Square,
+}
+
+///How the outline of a path is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    ///Width of the stroke, in pixels, centered on the path
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    ///Biggest ratio between the length of a miter and the width of the stroke. Sharper corners are beveled
+    pub miter_limit: f32,
+    ///Lengths, in pixels, of the dashes and of the gaps between them, alternated. Lists with an odd amount of lengths are
+    ///repeated. Empty strokes are solid
+    pub dashes: Vec<f32>,
+    ///Distance, in pixels, into the dash pattern where the stroke starts
+    pub dash_offset: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            ..Default::default()
+        }
+    }
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+    pub fn miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+    pub fn dashes(mut self, dashes: Vec<f32>, offset: f32) -> Self {
+        self.dashes = dashes;
+        self.dash_offset = offset;
+        self
+    }
+
+    ///Gets the dash pattern with an even amount of lengths, or None if the stroke is solid
+    fn pattern(&self) -> Option<Vec<f32>> {
+        let total: f32 = self.dashes.iter().sum();
+        if total <= 0.0 || self.dashes.iter().any(|dash| *dash < 0.0) {
+            return None;
+        }
+        Some(match self.dashes.len() % 2 {
+            0 => self.dashes.clone(),
+            _ => self.dashes.repeat(2),
+        })
+    }
+}
+
+impl Path {
+    ///Tessellates the outline of the path with the given `style`. The pieces of the stroke are filled as a whole, so
+    ///where they overlap, such as on joins, isn't drawn twice
+    pub fn stroke(&self, style: &StrokeStyle) -> Triangles {
+        if style.width <= 0.0 {
+            return Triangles::default();
+        }
+        let mut contours = self.flatten();
+        if let Some(pattern) = style.pattern() {
+            contours = contours
+                .iter()
+                .flat_map(|contour| dash(contour, &pattern, style.dash_offset))
+                .collect();
+        }
+        let mut outline = Outline {
+            style,
+            half: style.width / 2.0,
+            pieces: Vec::new(),
+        };
+        for contour in contours.iter() {
+            outline.add(contour);
+        }
+        fill_contours(&outline.pieces, FillRule::NonZero)
+    }
+}
+
+///Splits the given `contour` into it's dashes, starting `offset` pixels into the `pattern`
+fn dash(contour: &Contour, pattern: &[f32], offset: f32) -> Vec<Contour> {
+    let total: f32 = pattern.iter().sum();
+    let mut offset = offset.rem_euclid(total);
+    let mut idx = 0;
+    while offset >= pattern[idx] {
+        offset -= pattern[idx];
+        idx = (idx + 1) % pattern.len();
+    }
+    //Length left of the current dash or gap
+    let mut left = pattern[idx] - offset;
+    let mut dashes = Vec::new();
+    let mut current = Contour::default();
+    let mut points = contour.points.clone();
+    if contour.closed
+        && let Some(first) = points.first()
+    {
+        points.push(*first);
+    }
+    if idx % 2 == 0
+        && let Some(first) = points.first()
+    {
+        current.points.push(*first);
+    }
+    for segment in points.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let size = length(to - from);
+        let mut done = 0.0;
+        while size - done > left {
+            done += left;
+            let point = from + (to - from) * (done / size);
+            match idx % 2 == 0 {
+                true => {
+                    current.points.push(point);
+                    dashes.push(std::mem::take(&mut current));
+                }
+                false => current.points.push(point),
+            }
+            idx = (idx + 1) % pattern.len();
+            left = pattern[idx];
+        }
+        left -= size - done;
+        if idx % 2 == 0 {
+            current.points.push(to);
+        }
+    }
+    if idx % 2 == 0 && !current.points.is_empty() {
+        dashes.push(current);
+    }
+    dashes
+}
+
+///Pieces of a stroke, each of them a closed polygon going clockwise on the screen, so filling them by the non zero rule covers
+///their union
+struct Outline<'a> {
+    style: &'a StrokeStyle,
+    half: f32,
+    pieces: Vec<Contour>,
+}
+
+impl Outline<'_> {
+    fn add(&mut self, contour: &Contour) {
+        let mut points = contour.points.clone();
+        points.dedup_by(|point, previous| length(*point - *previous) < f32::EPSILON);
+        let Some(&first) = points.first() else {
+            return;
+        };
+        //Zero length subpaths only draw their caps, facing right
+        if points.len() == 1 {
+            if !contour.closed {
+                let direction = Vec2f32::new(1.0, 0.0);
+                self.cap(first, direction * -1.0);
+                self.cap(first, direction);
+            }
+            return;
+        }
+        let count = points.len();
+        let segments = match contour.closed && count > 2 {
+            true => count,
+            false => count - 1,
+        };
+        for idx in 0..segments {
+            let (from, to) = (points[idx], points[(idx + 1) % count]);
+            let normal = normal(to - from) * self.half;
+            self.push(vec![from + normal, to + normal, to - normal, from - normal]);
+        }
+        let joins = match contour.closed && count > 2 {
+            true => 0..count,
+            false => 1..count - 1,
+        };
+        for idx in joins {
+            let previous = points[(idx + count - 1) % count];
+            let (point, next) = (points[idx], points[(idx + 1) % count]);
+            self.join(point, point - previous, next - point);
+        }
+        if !(contour.closed && count > 2) {
+            self.cap(first, first - points[1]);
+            self.cap(points[count - 1], points[count - 1] - points[count - 2]);
+        }
+    }
+
+    ///Adds the given polygon, reversing it if it goes counter clockwise
+    fn push(&mut self, mut points: Vec<Vec2f32>) {
+        let next = points.iter().cycle().skip(1);
+        let area: f32 = points.iter().zip(next).map(|(a, b)| cross(*a, *b)).sum();
+        if area < 0.0 {
+            points.reverse();
+        }
+        self.pieces.push(Contour {
+            points,
+            closed: true,
+        });
+    }
+
+    ///Adds the cap at the given end of a subpath, with `direction` pointing away from the subpath
+    fn cap(&mut self, point: Vec2f32, direction: Vec2f32) {
+        let size = length(direction);
+        if size == 0.0 {
+            return;
+        }
+        let direction = direction * (self.half / size);
+        let normal = normal(direction) * self.half;
+        match self.style.cap {
+            LineCap::Butt => {}
+            LineCap::Square => self.push(vec![
+                point + normal,
+                point + normal + direction,
+                point - normal + direction,
+                point - normal,
+            ]),
+            LineCap::Round => {
+                let mut points = vec![point];
+                points.extend(self.arc(point, normal, normal * -1.0, direction));
+                self.push(points);
+            }
+        }
+    }
+
+    ///Adds the join at the given point, between the segments coming along `incoming` and leaving along `outgoing`
+    fn join(&mut self, point: Vec2f32, incoming: Vec2f32, outgoing: Vec2f32) {
+        let turn = cross(incoming, outgoing);
+        if turn.abs() < f32::EPSILON && dot(incoming, outgoing) > 0.0 {
+            return;
+        }
+        //The outer side of the corner is the opposite one to where the stroke turns
+        let side = if turn > 0.0 { -1.0 } else { 1.0 };
+        let before = normal(incoming) * (self.half * side);
+        let after = normal(outgoing) * (self.half * side);
+        match self.style.join {
+            LineJoin::Bevel => self.push(vec![point, point + before, point + after]),
+            LineJoin::Miter => {
+                let alignment = 1.0 + dot(before, after) / (self.half * self.half);
+                let miter = (before + after) * (1.0 / alignment.max(f32::EPSILON));
+                match alignment > f32::EPSILON
+                    && length(miter) <= self.style.miter_limit * self.half
+                {
+                    true => self.push(vec![point, point + before, point + miter, point + after]),
+                    false => self.push(vec![point, point + before, point + after]),
+                }
+            }
+            LineJoin::Round => {
+                //When the stroke turns back, the join bulges forward
+                let middle = match length(before + after) > f32::EPSILON {
+                    true => before + after,
+                    false => incoming,
+                };
+                let middle = middle * (self.half / length(middle));
+                let mut points = vec![point];
+                points.extend(self.arc(point, before, after, middle));
+                self.push(points);
+            }
+        }
+    }
+
+    ///Gets the points of the arc around `center` going from the offset `from` to the offset `to`, through `middle`
+    fn arc(&self, center: Vec2f32, from: Vec2f32, to: Vec2f32, middle: Vec2f32) -> Vec<Vec2f32> {
+        let angle_of = |offset: Vec2f32| offset.y().atan2(offset.x());
+        let start = angle_of(from);
+        let half_sweep = wrap(angle_of(middle) - start);
+        let sweep = half_sweep + wrap(angle_of(to) - angle_of(middle));
+        //Steps short enough for the chords to stay within the tolerance of the circle
+        let step = 2.0
+            * (1.0 - TOLERANCE / self.half.max(TOLERANCE))
+                .acos()
+                .max(0.01);
+        let segments = (sweep.abs() / step).ceil().clamp(1.0, 256.0) as usize;
+        (0..=segments)
+            .map(|idx| {
+                let (sin, cos) = (start + sweep * idx as f32 / segments as f32).sin_cos();
+                center + Vec2f32::new(cos, sin) * self.half
+            })
+            .collect()
+    }
+}
+
+///Wraps the given angle between -PI and PI
+fn wrap(angle: f32) -> f32 {
+    (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
+
+///Gets the unit vector perpendicular to the given `direction`, pointing to it's right on the screen
+fn normal(direction: Vec2f32) -> Vec2f32 {
+    let size = length(direction).max(f32::EPSILON);
+    Vec2f32::new(-direction.y() / size, direction.x() / size)
+}
+
+fn dot(a: Vec2f32, b: Vec2f32) -> f32 {
+    a.x() * b.x() + a.y() * b.y()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    fn line(length: f32) -> Path {
+        Path::polyline(&[Vec2f32::new(0.0, 0.0), Vec2f32::new(length, 0.0)])
+    }
+
+    ///Corner turning right, whose outer side is on the top right
+    fn corner() -> Path {
+        Path::polyline(&[
+            Vec2f32::new(0.0, 0.0),
+            Vec2f32::new(10.0, 0.0),
+            Vec2f32::new(10.0, 10.0),
+        ])
+    }
+
+    fn max_x(triangles: &Triangles) -> f32 {
+        triangles
+            .vertices
+            .iter()
+            .map(|vertex| vertex.x())
+            .fold(f32::MIN, f32::max)
+    }
+
+    #[test]
+    fn caps_extend_the_ends_of_open_strokes() {
+        let butt = line(10.0).stroke(&StrokeStyle::new(2.0));
+        assert!((butt.area() - 20.0).abs() < 1e-2);
+        assert!(!butt.covers(-0.5, 0.0));
+        let square = line(10.0).stroke(&StrokeStyle::new(2.0).cap(LineCap::Square));
+        assert!((square.area() - 24.0).abs() < 1e-2);
+        assert!(square.covers(-0.5, 0.0) && square.covers(10.5, 0.5));
+        //Half circles on both ends make up a whole one, approximated from inside within the tolerance
+        let round = line(10.0).stroke(&StrokeStyle::new(20.0).cap(LineCap::Round));
+        let (area, exact) = (round.area(), 200.0 + PI * 100.0);
+        assert!(area <= exact && exact - area < 2.0 * PI * 10.0 * TOLERANCE);
+        assert!(round.covers(-9.0, 0.0) && !round.covers(-9.0, 9.0));
+    }
+
+    #[test]
+    fn joins_fill_the_outer_side_of_corners() {
+        //Both segments cover 20 pixels and overlap on a single one
+        let bevel = corner().stroke(&StrokeStyle::new(2.0).join(LineJoin::Bevel));
+        assert!((bevel.area() - 39.5).abs() < 1e-2);
+        let miter = corner().stroke(&StrokeStyle::new(2.0).join(LineJoin::Miter));
+        assert!((miter.area() - 40.0).abs() < 1e-2);
+        assert!(miter.covers(10.9, -0.9) && !bevel.covers(10.9, -0.9));
+        //A quarter of a circle, as wide as the stroke, rounds the corner
+        let round = corner().stroke(&StrokeStyle::new(20.0).join(LineJoin::Round));
+        let (area, exact) = (round.area(), 300.0 + PI * 25.0);
+        assert!(area <= exact && exact - area < PI * 5.0 * TOLERANCE);
+        assert!(round.covers(16.0, -6.0) && !round.covers(18.0, -8.0));
+        //Joins don't cover the inner side of the corner twice
+        assert!(!miter.covers(8.5, 1.5));
+    }
+
+    #[test]
+    fn sharp_miters_are_beveled_beyond_the_limit() {
+        //The segments meet at about 6 degrees, so the miter is about 20 times as long as half the width
+        let sharp = Path::polyline(&[
+            Vec2f32::new(0.0, 0.0),
+            Vec2f32::new(10.0, 0.0),
+            Vec2f32::new(0.0, 1.0),
+        ]);
+        let limited = sharp.stroke(&StrokeStyle::new(2.0).miter_limit(4.0));
+        assert!(max_x(&limited) < 11.5);
+        let unlimited = sharp.stroke(&StrokeStyle::new(2.0).miter_limit(50.0));
+        assert!(max_x(&unlimited) > 25.0);
+    }
+
+    #[test]
+    fn dashes_split_the_stroke() {
+        let dashed = line(100.0).stroke(&StrokeStyle::new(2.0).dashes(vec![10.0, 10.0], 0.0));
+        assert!((dashed.area() - 100.0).abs() < 1e-2);
+        assert!(dashed.covers(5.0, 0.0) && !dashed.covers(15.0, 0.0) && dashed.covers(25.0, 0.0));
+        //The offset moves the pattern back along the stroke
+        let offset = line(100.0).stroke(&StrokeStyle::new(2.0).dashes(vec![10.0, 10.0], 10.0));
+        assert!(!offset.covers(5.0, 0.0) && offset.covers(15.0, 0.0));
+        //Odd patterns are repeated, so the lengths alternate between dashes and gaps
+        let odd = line(60.0).stroke(&StrokeStyle::new(2.0).dashes(vec![10.0, 5.0, 5.0], 0.0));
+        assert!(odd.covers(5.0, 0.0) && !odd.covers(12.0, 0.0) && odd.covers(17.0, 0.0));
+        assert!(!odd.covers(22.0, 0.0) && !odd.covers(27.0, 0.0) && odd.covers(32.0, 0.0));
+    }
+
+    #[test]
+    fn dashes_follow_closed_contours() {
+        //The pattern goes around the square, across it's corners
+        let square = Path::polygon(&[
+            Vec2f32::new(0.0, 0.0),
+            Vec2f32::new(10.0, 0.0),
+            Vec2f32::new(10.0, 10.0),
+            Vec2f32::new(0.0, 10.0),
+        ]);
+        let dashed = square.stroke(&StrokeStyle::new(1.0).dashes(vec![15.0, 5.0], 0.0));
+        assert!(dashed.covers(10.0, 4.0) && !dashed.covers(10.0, 7.5));
+        assert!(dashed.covers(5.0, 10.0) && !dashed.covers(0.0, 2.5));
+    }
+
+    #[test]
+    fn invalid_strokes_are_empty_or_solid() {
+        assert!(line(10.0).stroke(&StrokeStyle::new(0.0)).is_empty());
+        let negative = line(10.0).stroke(&StrokeStyle::new(2.0).dashes(vec![5.0, -1.0], 0.0));
+        assert!((negative.area() - 20.0).abs() < 1e-2);
+    }
+}
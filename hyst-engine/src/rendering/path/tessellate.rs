@@ -0,0 +1,445 @@
+use hyst_math::vectors::Vec2f32;
+
+use super::{Path, PathCommand};
+
+///Maximum distance, in pixels, between curves and the lines they're approximated with
+pub const TOLERANCE: f32 = 0.25;
+
+///Maximum amount of lines a single curve is approximated with
+const MAX_CURVE_SEGMENTS: usize = 1024;
+
+///Points closer than this, in pixels, are considered the same while tessellating
+const EPSILON: f32 = 1e-4;
+
+///Which parts of a path are inside of it, when it overlaps itself or has several subpaths
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    ///Points around which the path winds a different amount of times clockwise than counter clockwise are inside
+    #[default]
+    NonZero,
+    ///Points around which the path winds an odd amount of times are inside, so overlaps make holes
+    EvenOdd,
+}
+
+impl FillRule {
+    fn is_inside(&self, winding: i32) -> bool {
+        match self {
+            Self::NonZero => winding != 0,
+            Self::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+///Triangles covering a shape, in pixels
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Triangles {
+    pub vertices: Vec<Vec2f32>,
+    ///Indices of the vertices of each triangle, three by three
+    pub indices: Vec<u32>,
+}
+
+impl Triangles {
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    ///Adds the quad with the given top and bottom edges, each as it's left and right x
+    fn push_trapezoid(&mut self, top: (f32, f32, f32), bottom: (f32, f32, f32)) {
+        let base = self.vertices.len() as u32;
+        self.vertices.extend([
+            Vec2f32::new(top.1, top.0),
+            Vec2f32::new(top.2, top.0),
+            Vec2f32::new(bottom.1, bottom.0),
+            Vec2f32::new(bottom.2, bottom.0),
+        ]);
+        self.indices
+            .extend([base, base + 1, base + 2, base + 2, base + 1, base + 3]);
+    }
+}
+
+///Subpath approximated with lines
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Vec2f32>,
+    ///Whether the last point is joined to the first one
+    pub closed: bool,
+}
+
+impl Path {
+    ///Approximates the curves of the path with lines, getting each of it's subpaths as a contour
+    pub fn flatten(&self) -> Vec<Contour> {
+        let mut contours = Vec::new();
+        let mut contour = Contour::default();
+        let mut start = Vec2f32::default();
+        for command in self.commands() {
+            let current = contour.points.last().copied().unwrap_or(start);
+            match *command {
+                PathCommand::MoveTo(point) => {
+                    finish(&mut contours, &mut contour);
+                    start = point;
+                    contour.points.push(point);
+                }
+                PathCommand::LineTo(point) => {
+                    begin(&mut contour, current);
+                    contour.points.push(point);
+                }
+                PathCommand::QuadTo(control, point) => {
+                    begin(&mut contour, current);
+                    let deviation = length(current - control * 2.0 + point);
+                    let segments = curve_segments(deviation / 8.0);
+                    contour.points.extend((1..=segments).map(|idx| {
+                        let t = idx as f32 / segments as f32;
+                        let u = 1.0 - t;
+                        current * (u * u) + control * (2.0 * u * t) + point * (t * t)
+                    }));
+                }
+                PathCommand::CubicTo(first, second, point) => {
+                    begin(&mut contour, current);
+                    let deviation = length(current - first * 2.0 + second)
+                        .max(length(first - second * 2.0 + point));
+                    let segments = curve_segments(deviation * 0.75);
+                    contour.points.extend((1..=segments).map(|idx| {
+                        let t = idx as f32 / segments as f32;
+                        let u = 1.0 - t;
+                        current * (u * u * u)
+                            + first * (3.0 * u * u * t)
+                            + second * (3.0 * u * t * t)
+                            + point * (t * t * t)
+                    }));
+                }
+                PathCommand::Close => {
+                    contour.closed = true;
+                    finish(&mut contours, &mut contour);
+                }
+            }
+        }
+        finish(&mut contours, &mut contour);
+        contours
+    }
+
+    ///Tessellates the area inside of the path, by the given fill `rule`. Open subpaths are closed with a line
+    pub fn fill(&self, rule: FillRule) -> Triangles {
+        fill_contours(&self.flatten(), rule)
+    }
+}
+
+///Starts the contour at the given point when it's empty, such as after closing a subpath
+fn begin(contour: &mut Contour, current: Vec2f32) {
+    if contour.points.is_empty() {
+        contour.points.push(current);
+    }
+}
+
+///Adds the contour to the list, without the points which repeat the previous one, and starts a new one
+fn finish(contours: &mut Vec<Contour>, contour: &mut Contour) {
+    let mut finished = std::mem::take(contour);
+    finished
+        .points
+        .dedup_by(|point, previous| length(*point - *previous) < EPSILON);
+    if finished.closed
+        && finished.points.len() > 1
+        && length(finished.points[0] - finished.points[finished.points.len() - 1]) < EPSILON
+    {
+        finished.points.pop();
+    }
+    if !finished.points.is_empty() {
+        contours.push(finished);
+    }
+}
+
+///Gets the amount of lines a curve is approximated with, from how far it's control points are from being aligned
+fn curve_segments(deviation: f32) -> usize {
+    ((deviation / TOLERANCE).sqrt().ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS)
+}
+
+pub(crate) fn length(vector: Vec2f32) -> f32 {
+    vector.x().hypot(vector.y())
+}
+
+///Edge of a filled contour, going down the screen
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    top: Vec2f32,
+    bottom: Vec2f32,
+    ///1 if the contour goes down along the edge, -1 if it goes up
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        let t = (y - self.top.y()) / (self.bottom.y() - self.top.y());
+        self.top.x() + (self.bottom.x() - self.top.x()) * t
+    }
+}
+
+///Tessellates the area inside of the given `contours`, all of them closed, by the given fill `rule`.
+///The area is split in horizontal bands, between the heights where a contour has a point or crosses itself, so no edges cross
+///inside of a band. The spans of each band which are inside are covered with trapezoids
+pub fn fill_contours(contours: &[Contour], rule: FillRule) -> Triangles {
+    let mut edges = Vec::new();
+    for contour in contours.iter().filter(|contour| contour.points.len() > 2) {
+        let next = contour.points.iter().cycle().skip(1);
+        for (&from, &to) in contour.points.iter().zip(next) {
+            if (from.y() - to.y()).abs() < EPSILON {
+                continue;
+            }
+            edges.push(match from.y() < to.y() {
+                true => Edge {
+                    top: from,
+                    bottom: to,
+                    winding: 1,
+                },
+                false => Edge {
+                    top: to,
+                    bottom: from,
+                    winding: -1,
+                },
+            });
+        }
+    }
+    edges.sort_by(|a, b| a.top.y().total_cmp(&b.top.y()));
+    let mut heights: Vec<f32> = edges
+        .iter()
+        .flat_map(|edge| [edge.top.y(), edge.bottom.y()])
+        .collect();
+    for (idx, edge) in edges.iter().enumerate() {
+        //Edges are sorted by their top, so the ones after an edge's bottom can't cross it
+        for other in edges[idx + 1..]
+            .iter()
+            .take_while(|other| other.top.y() < edge.bottom.y())
+        {
+            if let Some(y) = crossing(edge, other) {
+                heights.push(y);
+            }
+        }
+    }
+    heights.sort_by(f32::total_cmp);
+    heights.dedup_by(|height, previous| *height - *previous < EPSILON);
+
+    let mut triangles = Triangles::default();
+    let (mut active, mut next) = (Vec::<Edge>::new(), 0);
+    let mut spans = Vec::new();
+    for band in heights.windows(2) {
+        let (top, bottom) = (band[0], band[1]);
+        let middle = (top + bottom) / 2.0;
+        while next < edges.len() && edges[next].top.y() < middle {
+            active.push(edges[next]);
+            next += 1;
+        }
+        active.retain(|edge| edge.bottom.y() > middle);
+        spans.clear();
+        spans.extend(active.iter().map(|edge| {
+            (
+                edge.x_at(middle),
+                edge.x_at(top),
+                edge.x_at(bottom),
+                edge.winding,
+            )
+        }));
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut winding = 0;
+        let mut left = None;
+        for &(_, x_top, x_bottom, edge_winding) in spans.iter() {
+            let inside = rule.is_inside(winding);
+            winding += edge_winding;
+            match (inside, rule.is_inside(winding)) {
+                (false, true) => left = Some((x_top, x_bottom)),
+                (true, false) => {
+                    if let Some((left_top, left_bottom)) = left.take() {
+                        triangles.push_trapezoid(
+                            (top, left_top, x_top),
+                            (bottom, left_bottom, x_bottom),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    triangles
+}
+
+///Gets the height where the given edges cross, if they do between their ends
+fn crossing(a: &Edge, b: &Edge) -> Option<f32> {
+    let (r, s) = (a.bottom - a.top, b.bottom - b.top);
+    let denominator = cross(r, s);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let offset = b.top - a.top;
+    let (t, u) = (
+        cross(offset, s) / denominator,
+        cross(offset, r) / denominator,
+    );
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a.top.y() + r.y() * t)
+}
+
+pub(crate) fn cross(a: Vec2f32, b: Vec2f32) -> f32 {
+    a.x() * b.y() - a.y() * b.x()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use hyst_math::Rect;
+
+    use super::*;
+
+    impl Triangles {
+        ///Sum of the areas of the triangles
+        pub(crate) fn area(&self) -> f32 {
+            self.triangles()
+                .map(|[a, b, c]| cross(b - a, c - a).abs() / 2.0)
+                .sum()
+        }
+
+        ///Whether some triangle contains the given point
+        pub(crate) fn covers(&self, x: f32, y: f32) -> bool {
+            let point = Vec2f32::new(x, y);
+            self.triangles().any(|[a, b, c]| {
+                let sides = [
+                    cross(b - a, point - a),
+                    cross(c - b, point - b),
+                    cross(a - c, point - c),
+                ];
+                sides.iter().all(|side| *side >= 0.0) || sides.iter().all(|side| *side <= 0.0)
+            })
+        }
+
+        fn triangles(&self) -> impl Iterator<Item = [Vec2f32; 3]> + '_ {
+            self.indices
+                .chunks(3)
+                .map(|idx| [idx[0], idx[1], idx[2]].map(|idx| self.vertices[idx as usize]))
+        }
+    }
+
+    fn square(x: f32, y: f32, size: f32, clockwise: bool) -> Vec<Vec2f32> {
+        let mut points = vec![
+            Vec2f32::new(x, y),
+            Vec2f32::new(x + size, y),
+            Vec2f32::new(x + size, y + size),
+            Vec2f32::new(x, y + size),
+        ];
+        if !clockwise {
+            points.reverse();
+        }
+        points
+    }
+
+    fn contour(points: Vec<Vec2f32>) -> Contour {
+        Contour {
+            points,
+            closed: true,
+        }
+    }
+
+    #[test]
+    fn fills_known_shapes_with_their_area() {
+        let rect = Path::rect(&Rect::from_xywh(10.0, 20.0, 30.0, 40.0));
+        assert!((rect.fill(FillRule::NonZero).area() - 1200.0).abs() < 1e-2);
+        let triangle = Path::polygon(&[
+            Vec2f32::new(0.0, 0.0),
+            Vec2f32::new(20.0, 0.0),
+            Vec2f32::new(0.0, 10.0),
+        ]);
+        assert!((triangle.fill(FillRule::NonZero).area() - 100.0).abs() < 1e-2);
+        //Curves are approximated from inside, within the tolerance
+        let circle = Path::circle(50.0, 50.0, 40.0)
+            .fill(FillRule::NonZero)
+            .area();
+        let exact = PI * 40.0 * 40.0;
+        assert!(circle <= exact && exact - circle < 2.0 * PI * 40.0 * TOLERANCE);
+    }
+
+    #[test]
+    fn open_subpaths_are_filled_as_if_closed() {
+        let open = Path::polyline(&[
+            Vec2f32::new(0.0, 0.0),
+            Vec2f32::new(10.0, 0.0),
+            Vec2f32::new(10.0, 10.0),
+        ]);
+        assert!((open.fill(FillRule::NonZero).area() - 50.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn nested_contours_make_holes_by_the_rule() {
+        //The inner square goes the same way as the outer one, so only the even odd rule leaves it empty
+        let same = [
+            contour(square(0.0, 0.0, 10.0, true)),
+            contour(square(3.0, 3.0, 4.0, true)),
+        ];
+        let non_zero = fill_contours(&same, FillRule::NonZero);
+        let even_odd = fill_contours(&same, FillRule::EvenOdd);
+        assert!((non_zero.area() - 100.0).abs() < 1e-2);
+        assert!(non_zero.covers(5.0, 5.0));
+        assert!((even_odd.area() - 84.0).abs() < 1e-2);
+        assert!(!even_odd.covers(5.0, 5.0));
+        //Going the other way, it's a hole by both rules
+        let opposite = [
+            contour(square(0.0, 0.0, 10.0, true)),
+            contour(square(3.0, 3.0, 4.0, false)),
+        ];
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            let triangles = fill_contours(&opposite, rule);
+            assert!((triangles.area() - 84.0).abs() < 1e-2);
+            assert!(!triangles.covers(5.0, 5.0));
+        }
+    }
+
+    #[test]
+    fn self_intersecting_paths_are_split_where_they_cross() {
+        //Bow tie whose edges cross on it's center
+        let bow_tie = Path::polygon(&[
+            Vec2f32::new(0.0, 0.0),
+            Vec2f32::new(10.0, 10.0),
+            Vec2f32::new(10.0, 0.0),
+            Vec2f32::new(0.0, 10.0),
+        ]);
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            let triangles = bow_tie.fill(rule);
+            assert!((triangles.area() - 50.0).abs() < 1e-2);
+            assert!(triangles.covers(2.0, 5.0) && triangles.covers(8.0, 5.0));
+            assert!(!triangles.covers(5.0, 2.0) && !triangles.covers(5.0, 8.0));
+        }
+        //The center of a star is wound twice, so it's only inside by the non zero rule
+        let star = Path::polygon(
+            &(0..5)
+                .map(|idx| {
+                    let angle = -PI / 2.0 + idx as f32 * 4.0 * PI / 5.0;
+                    Vec2f32::new(50.0 + 40.0 * angle.cos(), 50.0 + 40.0 * angle.sin())
+                })
+                .collect::<Vec<_>>(),
+        );
+        let non_zero = star.fill(FillRule::NonZero);
+        let even_odd = star.fill(FillRule::EvenOdd);
+        assert!(non_zero.covers(50.0, 50.0));
+        assert!(!even_odd.covers(50.0, 50.0));
+        assert!(non_zero.area() > even_odd.area());
+        //Both still cover the tips
+        assert!(non_zero.covers(50.0, 15.0) && even_odd.covers(50.0, 15.0));
+    }
+
+    #[test]
+    fn flattening_drops_repeated_points() {
+        let path = Path::builder()
+            .move_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(10.0, 10.0)
+            .line_to(0.0, 0.0)
+            .close()
+            .build();
+        let contours = path.flatten();
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].closed);
+        assert_eq!(
+            contours[0].points,
+            vec![
+                Vec2f32::new(0.0, 0.0),
+                Vec2f32::new(10.0, 0.0),
+                Vec2f32::new(10.0, 10.0)
+            ]
+        );
+    }
+}
@@ -1,10 +1,10 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
 use bitmask::bitmask;
 pub mod events;
 use wgpu::{BindGroup, BindGroupLayout, RenderPipeline, ShaderModule};
 
-use crate::rendering::basics::BindGroupAndLayoutConfig;
+use crate::rendering::basics::BindGroupConfig;
 
 bitmask! {
     pub mask ShaderRenderMethod:u8 where flags ShaderRenderingStyle {
@@ -24,9 +24,13 @@ bitmask! {
 
         //Fill = 0 << 5,
         Line = 1 << 5,
-        Point = 2 << 5
+        Point = 2 << 5,
+
+        //Culled = 0 << 7,
+        Unculled = 1 << 7
         /*
-        PPFDTT
+        UPPFDTT
+        U = Culling disabled
         T = Type
         D = Direction
         F = Face
@@ -35,13 +39,19 @@ bitmask! {
     }
 }
 impl ShaderRenderMethod {
+    #[allow(non_upper_case_globals)]
     pub const TriangleCcwBack: Self = Self {
         mask: (ShaderRenderingStyle::Ccw as u8
             | ShaderRenderingStyle::TriangleList as u8
             | ShaderRenderingStyle::Back as u8),
     };
+    ///Lists of triangles drawn whichever way they go, so elements mirrored by a negative scale are still drawn
+    #[allow(non_upper_case_globals)]
+    pub const TriangleUnculled: Self = Self {
+        mask: (ShaderRenderingStyle::TriangleList as u8 | ShaderRenderingStyle::Unculled as u8),
+    };
     pub fn get_primitive_state(&self) -> wgpu::PrimitiveState {
-        let this = self.mask as u8;
+        let this = self.mask;
         wgpu::PrimitiveState {
             topology: match this & 0b11 {
                 0b00 => wgpu::PrimitiveTopology::PointList,
@@ -55,10 +65,13 @@ impl ShaderRenderMethod {
                 0 => wgpu::FrontFace::Cw,
                 _ => wgpu::FrontFace::Ccw,
             },
-            cull_mode: Some(match this & 0b1000 {
-                0 => wgpu::Face::Front,
-                _ => wgpu::Face::Back,
-            }),
+            cull_mode: match this & ShaderRenderingStyle::Unculled as u8 {
+                0 => Some(match this & 0b1000 {
+                    0 => wgpu::Face::Front,
+                    _ => wgpu::Face::Back,
+                }),
+                _ => None,
+            },
             polygon_mode: match (this & 0b110000) >> 5 {
                 0 => wgpu::PolygonMode::Fill,
                 1 => wgpu::PolygonMode::Line,
@@ -70,13 +83,118 @@ impl ShaderRenderMethod {
     }
 }
 
+///Format of the stencil buffer of the render passes, used for clipping elements to rounded rects
+pub const STENCIL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Stencil8;
+
+///How a pipeline uses the stencil buffer. The stencil value of a pixel is the amount of rounded clips it is inside of, and the
+///stencil reference is the amount of rounded clips the element being drawn is inside of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StencilMode {
+    ///Only draws where the stencil value equals the reference, so elements are clipped to their rounded ancestors
+    #[default]
+    Clipped,
+    ///Increments the stencil value where the shape is drawn. Nothing is written on the color target
+    PushClip,
+    ///Decrements the stencil value where the shape is drawn. Nothing is written on the color target
+    PopClip,
+    ///Ignores the stencil buffer
+    Ignored,
+}
+
+impl StencilMode {
+    pub fn depth_stencil_state(&self) -> wgpu::DepthStencilState {
+        let (compare, pass_op) = match self {
+            Self::Clipped => (wgpu::CompareFunction::Equal, wgpu::StencilOperation::Keep),
+            Self::PushClip => (
+                wgpu::CompareFunction::Equal,
+                wgpu::StencilOperation::IncrementClamp,
+            ),
+            Self::PopClip => (
+                wgpu::CompareFunction::Equal,
+                wgpu::StencilOperation::DecrementClamp,
+            ),
+            Self::Ignored => (wgpu::CompareFunction::Always, wgpu::StencilOperation::Keep),
+        };
+        let face = wgpu::StencilFaceState {
+            compare,
+            fail_op: wgpu::StencilOperation::Keep,
+            depth_fail_op: wgpu::StencilOperation::Keep,
+            pass_op,
+        };
+        wgpu::DepthStencilState {
+            format: STENCIL_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState {
+                front: face,
+                back: face,
+                read_mask: 0xff,
+                write_mask: match self {
+                    Self::PushClip | Self::PopClip => 0xff,
+                    _ => 0,
+                },
+            },
+            bias: wgpu::DepthBiasState::default(),
+        }
+    }
+
+    ///Whether pipelines with this mode write on the color target
+    pub fn writes_color(&self) -> bool {
+        !matches!(self, Self::PushClip | Self::PopClip)
+    }
+}
+
+///Blends colors which aren't premultiplied by their alpha over the target, keeping the target premultiplied. It looks the same as
+///the usual alpha blending on opaque targets, but the offscreen targets, which start transparent, can be drawn on others afterwards.
+pub const ALPHA_OVER: wgpu::BlendState = wgpu::BlendState {
+    color: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::SrcAlpha,
+        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+        operation: wgpu::BlendOperation::Add,
+    },
+    alpha: wgpu::BlendComponent::OVER,
+};
+
 pub struct ShaderCreationOptions<'a> {
     pub source: &'a str,
-    pub bind_group_configs: Vec<Vec<BindGroupAndLayoutConfig<'a>>>,
+    ///Bind groups of the shader, each of them bound on it's index plus one, since group 0 holds the global uniforms
+    pub bind_group_configs: Vec<BindGroupConfig<'a>>,
+    ///Ranges of bytes of the push constants, and the stages they're visible on. They require a device which supports them, and
+    ///can't be bigger than RenderingCore::max_push_constant_size
+    pub push_constants: Vec<wgpu::PushConstantRange>,
     pub rendering_style: ShaderRenderMethod,
     pub name: String,
 }
 
+impl<'a> ShaderCreationOptions<'a> {
+    ///Creates the options of a shader with the given wgsl `source`, without bind groups nor push constants, which draws lists
+    ///of triangles without culling them, so mirrored elements are drawn too
+    pub fn new(name: impl Into<String>, source: &'a str) -> Self {
+        Self {
+            source,
+            bind_group_configs: Vec::new(),
+            push_constants: Vec::new(),
+            rendering_style: ShaderRenderMethod::TriangleUnculled,
+            name: name.into(),
+        }
+    }
+    ///Adds the given bind `group`, bound after the ones already added. The first one is bound on group 1
+    pub fn group(mut self, group: BindGroupConfig<'a>) -> Self {
+        self.bind_group_configs.push(group);
+        self
+    }
+    ///Adds the given `range` of bytes of the push constants, visible on the given `stages`
+    pub fn push_constants(mut self, stages: wgpu::ShaderStages, range: Range<u32>) -> Self {
+        self.push_constants
+            .push(wgpu::PushConstantRange { stages, range });
+        self
+    }
+    pub fn rendering_style(mut self, style: ShaderRenderMethod) -> Self {
+        self.rendering_style = style;
+        self
+    }
+}
+
 pub trait ShaderInput {
     const LAYOUT: wgpu::VertexBufferLayout<'static>;
 }
@@ -103,5 +221,31 @@ pub trait HystConstructor: HystShader {
 
     fn shader_inputs() -> Vec<wgpu::VertexBufferLayout<'static>>;
 
+    ///How the pipeline of this shader uses the stencil buffer
+    fn stencil() -> StencilMode {
+        StencilMode::Clipped
+    }
+
+    ///How the pipeline of this shader blends it's colors with the target
+    fn blend() -> wgpu::BlendState {
+        ALPHA_OVER
+    }
+
     fn name() -> &'static str;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_shaders_draw_mirrored_triangles() {
+        let options = ShaderCreationOptions::new("mirrored", "");
+        let state = options.rendering_style.get_primitive_state();
+        assert_eq!(state.cull_mode, None);
+        assert_eq!(state.topology, wgpu::PrimitiveTopology::TriangleList);
+        assert_eq!(state.polygon_mode, wgpu::PolygonMode::Fill);
+        let culled = ShaderRenderMethod::TriangleCcwBack.get_primitive_state();
+        assert!(culled.cull_mode.is_some());
+    }
+}
@@ -0,0 +1,531 @@
+mod rich;
+pub use rich::*;
+mod style;
+pub use style::*;
+
+use std::{path::Path, sync::Arc};
+
+use glyphon::{
+    Attrs, Buffer, Cache, FontSystem, Metrics, Resolution, SwashCache, TextAtlas, TextBounds,
+    TextRenderer, Viewport, Wrap, cosmic_text::AttrsOwned, fontdb,
+};
+use hashbrown::HashSet;
+use hyst_math::{
+    Rect,
+    vectors::{Vec2f32, Vec4f32},
+};
+use smol_str::SmolStr;
+use wgpu::{Device, Queue, RenderPass, TextureFormat};
+
+use crate::shaders::StencilMode;
+
+///Font bundled with the crate, used as the default family so texts look the same on every machine.
+#[cfg(feature = "default-font")]
+const DEFAULT_FONT: &[u8] = include_bytes!("../../../fonts/Inter-Regular.ttf");
+#[cfg(feature = "default-font")]
+const DEFAULT_FONT_FAMILY: &str = "Inter";
+
+///Appended to the last visible line of texts whose content doesn't fit
+pub(crate) const ELLIPSIS: &str = "…";
+
+///Text prepared for being drawn on some layer
+pub(crate) struct PreparedText {
+    pub buffer: Buffer,
+    ///Position of the top left corner of the text on the screen
+    pub position: Vec2f32,
+    ///Scale the glyphs are drawn with
+    pub scale: f32,
+    ///Area the glyphs are clipped to
+    pub bounds: Rect,
+    pub color: Vec4f32,
+}
+
+///This struct is used for managning and rendering texts on the screen.
+pub struct TextManager {
+    font_sys: FontSystem,
+    default_family: FontFamily,
+    ///Named families that were requested by some text but are not loaded, so a fallback was used instead.
+    missing_families: HashSet<SmolStr>,
+    swash_cache: SwashCache,
+    cache: Cache,
+    ///Format of the targets the texts are drawn on
+    format: TextureFormat,
+    viewport: Viewport,
+    atlas: TextAtlas,
+    ///Renderer of each layer of the ui. Texts of a layer are drawn above the elements of that layer, but below the ones of the next
+    renderers: Vec<TextRenderer>,
+}
+
+impl TextManager {
+    pub fn new(device: &Device, queue: &Queue, texture: TextureFormat) -> Self {
+        let cache = Cache::new(device);
+        let mut atlas = TextAtlas::new(device, queue, &cache, texture);
+        let mut this = Self {
+            font_sys: FontSystem::new(),
+            default_family: FontFamily::Serif,
+            missing_families: HashSet::new(),
+            swash_cache: SwashCache::new(),
+            viewport: Viewport::new(device, &cache),
+            renderers: vec![Self::create_renderer(&mut atlas, device)],
+            atlas,
+            cache,
+            format: texture,
+        };
+        this.load_default_font();
+        this
+    }
+
+    ///Creates the renderer of a layer. Texts of a layer are drawn all at once, clipped through the stencil to the rounded clips
+    ///they're inside of, so they must be drawn with the amount of them as the stencil reference
+    fn create_renderer(atlas: &mut TextAtlas, device: &Device) -> TextRenderer {
+        TextRenderer::new(
+            atlas,
+            device,
+            wgpu::MultisampleState::default(),
+            Some(StencilMode::Clipped.depth_stencil_state()),
+        )
+    }
+
+    #[cfg(feature = "default-font")]
+    fn load_default_font(&mut self) {
+        self.load_font_data(DEFAULT_FONT.to_vec());
+        self.default_family = FontFamily::Name(DEFAULT_FONT_FAMILY.into());
+    }
+    #[cfg(not(feature = "default-font"))]
+    fn load_default_font(&mut self) {}
+
+    ///Loads the font on the given `path`, so it can be used by texts. Returns the families of the loaded faces.
+    pub fn load_font_file(&mut self, path: impl AsRef<Path>) -> std::io::Result<Vec<SmolStr>> {
+        Ok(self.load_font_data(std::fs::read(path)?))
+    }
+
+    ///Loads the font contained on the given `data`, which can be embedded with include_bytes!. Returns the families of the loaded faces.
+    pub fn load_font_data(&mut self, data: Vec<u8>) -> Vec<SmolStr> {
+        let db = self.font_sys.db_mut();
+        let ids = db.load_font_source(fontdb::Source::Binary(Arc::new(data)));
+        let mut families = Vec::new();
+        for id in ids {
+            let Some(face) = db.face(id) else {
+                continue;
+            };
+            for (family, _) in face.families.iter() {
+                if !families.iter().any(|f: &SmolStr| f == family) {
+                    families.push(family.into());
+                }
+            }
+        }
+        self.missing_families
+            .retain(|missing| !families.contains(missing));
+        families
+    }
+
+    ///Removes every loaded font, including the system ones. If the `default-font` feature is enabled, the bundled font is loaded again.
+    ///The glyphs cached for the old fonts are dropped along with them, so texts must be set again before they're drawn.
+    pub(crate) fn clear_fonts(&mut self, device: &Device, queue: &Queue) {
+        let locale = self.font_sys.locale().to_string();
+        self.font_sys = FontSystem::new_with_locale_and_db(locale, fontdb::Database::new());
+        self.missing_families.clear();
+        //Font ids start again from the new database, so glyphs cached under the old ones would be drawn for the new faces
+        self.swash_cache = SwashCache::new();
+        self.atlas = TextAtlas::new(device, queue, &self.cache, self.format);
+        let layers = self.renderers.len();
+        self.renderers = (0..layers)
+            .map(|_| Self::create_renderer(&mut self.atlas, device))
+            .collect();
+        self.load_default_font();
+    }
+
+    ///Sets the family used by texts which don't define their own. Texts which are already shaped keep the previous family, so
+    ///callers must refresh them themselves, as HystUi::set_default_family does.
+    pub fn set_default_family(&mut self, family: FontFamily) {
+        self.check_family(&family);
+        self.default_family = family;
+    }
+
+    pub fn default_family(&self) -> &FontFamily {
+        &self.default_family
+    }
+
+    ///Checks if the given `family` has some loaded face. Generic families are always considered to exist, since they always have a fallback.
+    pub fn has_family(&self, family: &FontFamily) -> bool {
+        let FontFamily::Name(name) = family else {
+            return true;
+        };
+        self.font_sys.db().faces().any(|face| {
+            face.families
+                .iter()
+                .any(|(family, _)| family.eq_ignore_ascii_case(name))
+        })
+    }
+
+    ///Gets the named families that were requested by some text but are not loaded. Texts that requested them use a fallback font instead.
+    pub fn missing_families(&self) -> impl Iterator<Item = &SmolStr> {
+        self.missing_families.iter()
+    }
+
+    ///Records the given `family` as missing if it has no loaded face
+    fn check_family(&mut self, family: &FontFamily) {
+        if let FontFamily::Name(ref name) = *family
+            && !self.has_family(family)
+        {
+            self.missing_families.insert(name.clone());
+        }
+    }
+
+    ///Gets the family the text with the given `style` is going to use, recording it if it's missing
+    fn family_of(&mut self, style: &TextStyle) -> FontFamily {
+        let family = style
+            .family
+            .clone()
+            .unwrap_or_else(|| self.default_family.clone());
+        self.check_family(&family);
+        family
+    }
+
+    ///Creates a buffer with the given `font_size`, `line_height` and `text`. The buffer is created without size, so it's not wrapped
+    ///until it receives the size of it's layout.
+    pub fn create_text_buffer(
+        &mut self,
+        font_size: f32,
+        line_height: f32,
+        text: &str,
+        style: &TextStyle,
+    ) -> Buffer {
+        let mut buffer = Buffer::new(&mut self.font_sys, Metrics::new(font_size, line_height));
+        self.set_text(&mut buffer, text, style);
+        buffer.set_size(&mut self.font_sys, None, None);
+        buffer.shape_until_scroll(&mut self.font_sys, true);
+        buffer
+    }
+
+    ///Sets the content of the given `buffer` to be the given `text`
+    pub fn set_text(&mut self, buffer: &mut Buffer, text: &str, style: &TextStyle) {
+        let family = self.family_of(style);
+        buffer.set_text(
+            &mut self.font_sys,
+            text,
+            &Attrs::new().family(family.as_family()),
+            style.shaping.into(),
+        );
+        self.apply_style(buffer, style);
+    }
+
+    ///Sets the content of the given `buffer` to be the spans of the given `text`
+    pub fn set_rich_text(&mut self, buffer: &mut Buffer, text: &RichText, style: &TextStyle) {
+        let family = self.family_of(style);
+        for span in text.spans() {
+            if let Some(ref family) = span.family {
+                self.check_family(family);
+            }
+        }
+        let default = Attrs::new().family(family.as_family());
+        let metrics = buffer.metrics();
+        buffer.set_rich_text(
+            &mut self.font_sys,
+            text.spans()
+                .iter()
+                .enumerate()
+                .map(|(idx, span)| (span.content.as_str(), span.attrs(&default, metrics, idx))),
+            &default,
+            style.shaping.into(),
+            None,
+        );
+        self.apply_style(buffer, style);
+    }
+
+    ///Applies the wrapping and alignment of the given `style` on the lines of the given `buffer`
+    fn apply_style(&mut self, buffer: &mut Buffer, style: &TextStyle) {
+        buffer.set_wrap(&mut self.font_sys, style.wrap.into());
+        for line in buffer.lines.iter_mut() {
+            line.set_align(Some(style.align.into()));
+        }
+        buffer.shape_until_scroll(&mut self.font_sys, true);
+    }
+
+    #[inline]
+    ///Creates a truncated copy of the given `buffer`, shaped with the fonts of this manager. See [ellipsize]
+    pub fn ellipsize(
+        &mut self,
+        buffer: &Buffer,
+        width: f32,
+        height: f32,
+        max_lines: Option<usize>,
+    ) -> Option<Buffer> {
+        ellipsize(&mut self.font_sys, buffer, width, height, max_lines)
+    }
+
+    #[inline]
+    ///Sets the size of the given `buffer`, wrapping it's content to the given `width`.
+    pub fn set_size(&mut self, buffer: &mut Buffer, width: Option<f32>, height: Option<f32>) {
+        buffer.set_size(&mut self.font_sys, width, height);
+    }
+
+    ///Measures the size the content of the given `buffer` requires when wrapped to the given `width`. If `width` is None, lines are only broken
+    ///where the text breaks them.
+    ///When `min_content` is true, the text is wrapped at every word, so the returned width is the width of the largest word.
+    ///If `max_lines` is defined, only the first lines are considered.
+    pub fn measure(
+        &mut self,
+        buffer: &mut Buffer,
+        width: Option<f32>,
+        min_content: bool,
+        max_lines: Option<usize>,
+    ) -> Vec2f32 {
+        let wrap = buffer.wrap();
+        if min_content {
+            buffer.set_wrap(&mut self.font_sys, Wrap::Word);
+        }
+        buffer.set_size(&mut self.font_sys, width, None);
+        buffer.shape_until_scroll(&mut self.font_sys, false);
+        let (width, height) = buffer
+            .layout_runs()
+            .take(max_lines.unwrap_or(usize::MAX))
+            .fold((0.0f32, 0.0f32), |(width, height), run| {
+                (width.max(run.line_w), height + run.line_height)
+            });
+        if min_content {
+            buffer.set_wrap(&mut self.font_sys, wrap);
+        }
+        Vec2f32::new(width, height)
+    }
+
+    #[inline]
+    ///Sets the given `metrics` for the given `buffer`
+    pub fn set_metrics(&mut self, buffer: &mut Buffer, metrics: Metrics) {
+        buffer.set_metrics(&mut self.font_sys, metrics);
+    }
+    ///Prepare the given texts for rendering, one list per layer
+    pub(crate) fn prepare(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        layers: Vec<Vec<PreparedText>>,
+    ) {
+        while self.renderers.len() < layers.len() {
+            let renderer = Self::create_renderer(&mut self.atlas, device);
+            self.renderers.push(renderer);
+        }
+        for (renderer, texts) in self.renderers.iter_mut().zip(layers.iter()) {
+            renderer
+                .prepare(
+                    device,
+                    queue,
+                    &mut self.font_sys,
+                    &mut self.atlas,
+                    &self.viewport,
+                    texts.iter().map(|text| glyphon::TextArea {
+                        buffer: &text.buffer,
+                        left: text.position.x(),
+                        top: text.position.y(),
+                        scale: text.scale,
+                        bounds: {
+                            let rect = text.bounds;
+                            let x = rect.position().x();
+                            let y = rect.position().y();
+                            TextBounds {
+                                left: x.floor() as i32,
+                                top: y.floor() as i32,
+                                right: (x + rect.size().x()).ceil() as i32,
+                                bottom: (y + rect.size().y()).ceil() as i32,
+                            }
+                        },
+                        default_color: rgba_to_color(text.color),
+                        custom_glyphs: &[],
+                    }),
+                    &mut self.swash_cache,
+                )
+                .unwrap();
+        }
+        //Layers which no longer exist would draw their old texts
+        self.renderers.truncate(layers.len().max(1));
+    }
+    ///Resizes this text renderer viewport
+    pub fn resize(&mut self, queue: &Queue, width: u32, height: u32) {
+        self.viewport.update(queue, Resolution { width, height });
+    }
+
+    #[inline]
+    ///Draws the prepared texts of the given `layer`
+    pub fn draw_texts(&self, rpass: &mut RenderPass, layer: usize) {
+        if let Some(renderer) = self.renderers.get(layer) {
+            renderer.render(&self.atlas, &self.viewport, rpass).unwrap();
+        }
+    }
+}
+
+///Creates a copy of the given `buffer` whose content is truncated to fit on the given `width`, `height` and `max_lines`,
+///with the last visible line ending with '…'. Lines wider than `width`, which only exist when the text is not wrapped, are truncated as well.
+///Returns None if the whole content fits, so nothing needs to be truncated.
+pub(crate) fn ellipsize(
+    font_sys: &mut FontSystem,
+    buffer: &Buffer,
+    width: f32,
+    height: f32,
+    max_lines: Option<usize>,
+) -> Option<Buffer> {
+    let total = buffer.layout_runs().count();
+    let visible = visible_runs(buffer, height, max_lines);
+    let hidden = visible < total;
+    let ellipsis_width = {
+        let mut ellipsis = Buffer::new(font_sys, buffer.metrics());
+        let attrs = buffer
+            .lines
+            .first()
+            .map(|line| line.attrs_list().defaults())
+            .unwrap_or_else(Attrs::new);
+        ellipsis.set_text(font_sys, ELLIPSIS, &attrs, glyphon::Shaping::Basic);
+        ellipsis.layout_runs().map(|run| run.line_w).next()?
+    };
+
+    //(line index, byte where the line is cut)
+    let mut cuts: Vec<(usize, usize)> = Vec::new();
+    let mut last_line = 0;
+    for (idx, run) in buffer.layout_runs().take(visible).enumerate() {
+        last_line = run.line_i;
+        let is_last = idx + 1 == visible;
+        if run.line_w <= width && !(is_last && hidden) {
+            continue;
+        }
+        let cut = run
+            .glyphs
+            .iter()
+            .take_while(|glyph| glyph.x + glyph.w + ellipsis_width <= width)
+            .last()
+            .map(|glyph| glyph.end)
+            .or(run.glyphs.first().map(|glyph| glyph.start))
+            .unwrap_or(0);
+        if !cuts.iter().any(|(line, _)| *line == run.line_i) {
+            cuts.push((run.line_i, cut));
+        }
+    }
+    if cuts.is_empty() {
+        return None;
+    }
+    let mut out = buffer.clone();
+    out.lines.truncate(last_line + 1);
+    for (line_i, cut) in cuts {
+        let line = &mut out.lines[line_i];
+        let attrs = AttrsOwned::new(&line.attrs_list().get_span(cut.saturating_sub(1)));
+        let mut attrs_list = line.attrs_list().clone();
+        attrs_list.split_off(cut);
+        let mut text = line.text()[..cut].to_string();
+        text.push_str(ELLIPSIS);
+        attrs_list.add_span(cut..text.len(), &attrs.as_attrs());
+        let ending = line.ending();
+        line.set_text(text, ending, attrs_list);
+    }
+    out.shape_until_scroll(font_sys, false);
+    Some(out)
+}
+
+///Gets how many layout runs of the given `buffer` are visible when it's content is limited by the given `height` and `max_lines`.
+///The first line is always visible.
+pub fn visible_runs(buffer: &Buffer, height: f32, max_lines: Option<usize>) -> usize {
+    buffer
+        .layout_runs()
+        .take(max_lines.unwrap_or(usize::MAX))
+        .enumerate()
+        .take_while(|(idx, run)| *idx == 0 || run.line_top + run.line_height <= height + 0.5)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use glyphon::{Family, Shaping};
+
+    use super::*;
+
+    fn font_system() -> FontSystem {
+        let mut db = fontdb::Database::new();
+        db.load_font_data(include_bytes!("../../../fonts/Inter-Regular.ttf").to_vec());
+        FontSystem::new_with_locale_and_db("en-US".into(), db)
+    }
+
+    ///Creates a buffer with lines 12 pixels tall, wrapped at the given `width` if there's one
+    fn buffer(font_sys: &mut FontSystem, content: &str, width: Option<f32>) -> Buffer {
+        let mut buffer = Buffer::new(font_sys, Metrics::new(10.0, 12.0));
+        if width.is_none() {
+            buffer.set_wrap(font_sys, Wrap::None);
+        }
+        buffer.set_text(
+            font_sys,
+            content,
+            &Attrs::new().family(Family::Name("Inter")),
+            Shaping::Advanced,
+        );
+        buffer.set_size(font_sys, width, None);
+        buffer.shape_until_scroll(font_sys, true);
+        buffer
+    }
+
+    fn widest_run(buffer: &Buffer) -> f32 {
+        buffer
+            .layout_runs()
+            .map(|run| run.line_w)
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn nothing_is_truncated_when_the_content_fits() {
+        let mut font_sys = font_system();
+        let buffer = buffer(&mut font_sys, "short\ntext", None);
+        assert!(ellipsize(&mut font_sys, &buffer, 200.0, 24.0, None).is_none());
+        assert!(ellipsize(&mut font_sys, &buffer, 200.0, 100.0, Some(2)).is_none());
+    }
+
+    #[test]
+    fn the_last_visible_line_ends_with_an_ellipsis() {
+        let mut font_sys = font_system();
+        let buffer = buffer(&mut font_sys, "first\nsecond\nthird", None);
+        let display = ellipsize(&mut font_sys, &buffer, 200.0, 24.0, None).unwrap();
+        assert_eq!(display.lines.len(), 2);
+        assert_eq!(display.lines[0].text(), "first");
+        assert_eq!(display.lines[1].text(), format!("second{ELLIPSIS}"));
+        let display = ellipsize(&mut font_sys, &buffer, 200.0, 100.0, Some(1)).unwrap();
+        assert_eq!(display.lines.len(), 1);
+        assert_eq!(display.lines[0].text(), format!("first{ELLIPSIS}"));
+    }
+
+    #[test]
+    fn wrapped_lines_are_cut_on_the_last_visible_run() {
+        let mut font_sys = font_system();
+        let buffer = buffer(&mut font_sys, "one two three four five six", Some(50.0));
+        assert!(buffer.layout_runs().count() > 2);
+        let display = ellipsize(&mut font_sys, &buffer, 50.0, 24.0, None).unwrap();
+        assert_eq!(display.layout_runs().count(), 2);
+        let text = display.lines[0].text();
+        assert!(text.ends_with(ELLIPSIS));
+        assert!(
+            buffer.lines[0]
+                .text()
+                .starts_with(&text[..text.len() - ELLIPSIS.len()])
+        );
+        assert!(widest_run(&display) <= 50.0);
+    }
+
+    #[test]
+    fn lines_wider_than_the_width_are_cut_when_not_wrapped() {
+        let mut font_sys = font_system();
+        let buffer = buffer(
+            &mut font_sys,
+            "a line which is too wide\nfits\nanother wide line",
+            None,
+        );
+        let display = ellipsize(&mut font_sys, &buffer, 60.0, 100.0, None).unwrap();
+        assert_eq!(display.lines.len(), 3);
+        assert!(display.lines[0].text().ends_with(ELLIPSIS));
+        assert_eq!(display.lines[1].text(), "fits");
+        assert!(display.lines[2].text().ends_with(ELLIPSIS));
+        assert!(widest_run(&display) <= 60.0);
+    }
+
+    #[test]
+    fn the_first_line_is_always_visible() {
+        let mut font_sys = font_system();
+        let buffer = buffer(&mut font_sys, "first\nsecond\nthird", None);
+        assert_eq!(visible_runs(&buffer, 0.0, None), 1);
+        assert_eq!(visible_runs(&buffer, 30.0, None), 2);
+        assert_eq!(visible_runs(&buffer, 100.0, Some(2)), 2);
+        assert_eq!(visible_runs(&buffer, 100.0, None), 3);
+    }
+}
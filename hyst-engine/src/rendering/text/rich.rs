@@ -0,0 +1,201 @@
+use glyphon::{Attrs, Color, Family, Metrics, Style, Weight};
+use hyst_math::vectors::Rgba;
+use smol_str::SmolStr;
+
+///The family of the font used by some text. Owned version of glyphon's Family.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FontFamily {
+    Serif,
+    SansSerif,
+    Monospace,
+    Cursive,
+    Fantasy,
+    ///A family with the given name, such as "Fira Sans"
+    Name(SmolStr),
+}
+
+impl FontFamily {
+    pub fn as_family(&self) -> Family<'_> {
+        match self {
+            Self::Serif => Family::Serif,
+            Self::SansSerif => Family::SansSerif,
+            Self::Monospace => Family::Monospace,
+            Self::Cursive => Family::Cursive,
+            Self::Fantasy => Family::Fantasy,
+            Self::Name(name) => Family::Name(name),
+        }
+    }
+}
+
+///A piece of text which has it's own font, weight, style and color.
+///The family, the size and the color are inherited from the text the span is part of when they're None. The weight and the
+///style aren't, since texts don't have them, so spans are normal and upright unless they say otherwise.
+#[derive(Debug, Clone)]
+pub struct TextSpan {
+    pub content: String,
+    pub family: Option<FontFamily>,
+    pub weight: u16,
+    pub italic: bool,
+    pub size: Option<f32>,
+    pub color: Option<Rgba>,
+    pub underline: bool,
+    pub strikethrough: bool,
+    ///Id of the link this span refers to. It's up to the user to define what it means.
+    pub link: Option<u32>,
+}
+
+impl TextSpan {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            family: None,
+            weight: Weight::NORMAL.0,
+            italic: false,
+            size: None,
+            color: None,
+            underline: false,
+            strikethrough: false,
+            link: None,
+        }
+    }
+    pub fn family(mut self, family: FontFamily) -> Self {
+        self.family = Some(family);
+        self
+    }
+    pub fn weight(mut self, weight: u16) -> Self {
+        self.weight = weight;
+        self
+    }
+    pub fn bold(self) -> Self {
+        self.weight(Weight::BOLD.0)
+    }
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+    pub fn color(mut self, color: Rgba) -> Self {
+        self.color = Some(color);
+        self
+    }
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+    pub fn link(mut self, id: u32) -> Self {
+        self.link = Some(id);
+        self
+    }
+
+    ///Creates the glyphon attributes of this span based on the `default` ones. The `index` of the span is stored as the metadata of the attributes,
+    ///so glyphs can be mapped back to the span they came from.
+    pub(crate) fn attrs<'a>(
+        &'a self,
+        default: &Attrs<'a>,
+        metrics: Metrics,
+        index: usize,
+    ) -> Attrs<'a> {
+        let mut attrs = default
+            .clone()
+            .weight(Weight(self.weight))
+            .style(if self.italic {
+                Style::Italic
+            } else {
+                Style::Normal
+            })
+            .metadata(index);
+        if let Some(ref family) = self.family {
+            attrs = attrs.family(family.as_family());
+        }
+        if let Some(size) = self.size {
+            attrs = attrs.metrics(Metrics::new(
+                size,
+                size * metrics.line_height / metrics.font_size,
+            ));
+        }
+        if let Some(color) = self.color {
+            attrs = attrs.color(rgba_to_color(color));
+        }
+        attrs
+    }
+}
+
+///Text made of spans, each one with it's own attributes.
+#[derive(Debug, Clone, Default)]
+pub struct RichText {
+    spans: Vec<TextSpan>,
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Self { spans: Vec::new() }
+    }
+    ///Adds the given `span` at the end of this text
+    pub fn push(&mut self, span: TextSpan) {
+        self.spans.push(span);
+    }
+    ///Adds the given `span` at the end of this text and returns it
+    pub fn with(mut self, span: TextSpan) -> Self {
+        self.push(span);
+        self
+    }
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+    pub fn spans_mut(&mut self) -> &mut Vec<TextSpan> {
+        &mut self.spans
+    }
+    ///Gets the span at the given `index`. Glyphs created from a RichText have the index of their span as metadata.
+    pub fn span(&self, index: usize) -> Option<&TextSpan> {
+        self.spans.get(index)
+    }
+    ///Checks if some span of this text requires decorations, such as underlines
+    pub fn is_decorated(&self) -> bool {
+        self.spans
+            .iter()
+            .any(|span| span.underline || span.strikethrough)
+    }
+}
+
+impl From<&str> for RichText {
+    fn from(value: &str) -> Self {
+        Self::new().with(TextSpan::new(value))
+    }
+}
+impl From<String> for RichText {
+    fn from(value: String) -> Self {
+        Self::new().with(TextSpan::new(value))
+    }
+}
+impl From<TextSpan> for RichText {
+    fn from(value: TextSpan) -> Self {
+        Self::new().with(value)
+    }
+}
+impl FromIterator<TextSpan> for RichText {
+    fn from_iter<T: IntoIterator<Item = TextSpan>>(iter: T) -> Self {
+        Self {
+            spans: iter.into_iter().collect(),
+        }
+    }
+}
+
+///Converts a normalized rgba color into a glyphon color
+pub fn rgba_to_color(color: Rgba) -> Color {
+    fn f32_to_unorm(n: f32) -> u8 {
+        (n.clamp(0.0, 1.0) * 255.0).round() as u8
+    }
+    Color::rgba(
+        f32_to_unorm(color.x()),
+        f32_to_unorm(color.y()),
+        f32_to_unorm(color.z()),
+        f32_to_unorm(color.w()),
+    )
+}
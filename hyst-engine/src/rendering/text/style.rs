@@ -0,0 +1,159 @@
+use glyphon::{Shaping, Wrap, cosmic_text::Align};
+use hyst_math::vectors::Rgba;
+
+use super::FontFamily;
+
+///How the glyphs of a text are shaped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextShaping {
+    ///Fast shaping, which doesn't handle ligatures nor complex scripts
+    #[default]
+    Basic,
+    ///Shaping that handles complex scripts, such as Arabic and Devanagari, and ligatures
+    Advanced,
+}
+
+impl From<TextShaping> for Shaping {
+    fn from(value: TextShaping) -> Self {
+        match value {
+            TextShaping::Basic => Shaping::Basic,
+            TextShaping::Advanced => Shaping::Advanced,
+        }
+    }
+}
+
+///Horizontal alignment of the lines of a text inside it's layout box
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+impl From<TextAlign> for Align {
+    fn from(value: TextAlign) -> Self {
+        match value {
+            TextAlign::Left => Align::Left,
+            TextAlign::Center => Align::Center,
+            TextAlign::Right => Align::Right,
+            TextAlign::Justify => Align::Justified,
+        }
+    }
+}
+
+///Vertical alignment of the content of a text inside it's layout box
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+impl VerticalAlign {
+    ///Gets the offset of the content based on the `free` space of the layout box
+    pub fn offset(&self, free: f32) -> f32 {
+        match self {
+            Self::Top => 0.0,
+            Self::Center => free.max(0.0) * 0.5,
+            Self::Bottom => free.max(0.0),
+        }
+    }
+}
+
+///Where lines of a text are broken when they don't fit on the width of their layout box
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextWrap {
+    ///Lines are only broken where the text breaks them
+    None,
+    ///Lines are broken between words
+    Word,
+    ///Lines are broken between any glyph
+    Glyph,
+    ///Lines are broken between words, or between glyphs when a word doesn't fit on a line by itself
+    #[default]
+    WordOrGlyph,
+}
+
+impl From<TextWrap> for Wrap {
+    fn from(value: TextWrap) -> Self {
+        match value {
+            TextWrap::None => Wrap::None,
+            TextWrap::Word => Wrap::Word,
+            TextWrap::Glyph => Wrap::Glyph,
+            TextWrap::WordOrGlyph => Wrap::WordOrGlyph,
+        }
+    }
+}
+
+///Attributes shared by the whole content of a text. Spans of rich texts may override some of them.
+#[derive(Debug, Clone)]
+pub struct TextStyle {
+    ///Family of the font used by the text. If None, the default family of the ui is used.
+    pub family: Option<FontFamily>,
+    pub shaping: TextShaping,
+    pub align: TextAlign,
+    pub vertical_align: VerticalAlign,
+    pub wrap: TextWrap,
+    ///Maximum amount of lines shown. Lines after it are hidden and not considered when measuring the text.
+    pub max_lines: Option<usize>,
+    ///Whether the last visible line ends with '…' when the text doesn't fit on it's layout box.
+    pub ellipsis: bool,
+    ///Color of the highlight behind selected characters
+    pub selection_color: Rgba,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            family: None,
+            shaping: TextShaping::default(),
+            align: TextAlign::default(),
+            vertical_align: VerticalAlign::default(),
+            wrap: TextWrap::default(),
+            max_lines: None,
+            ellipsis: false,
+            selection_color: Rgba::new(0.2, 0.4, 0.9, 0.5),
+        }
+    }
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn family(mut self, family: FontFamily) -> Self {
+        self.family = Some(family);
+        self
+    }
+    pub fn shaping(mut self, shaping: TextShaping) -> Self {
+        self.shaping = shaping;
+        self
+    }
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+    pub fn vertical_align(mut self, align: VerticalAlign) -> Self {
+        self.vertical_align = align;
+        self
+    }
+    pub fn wrap(mut self, wrap: TextWrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+    pub fn max_lines(mut self, lines: usize) -> Self {
+        self.max_lines = Some(lines);
+        self
+    }
+    pub fn ellipsis(mut self) -> Self {
+        self.ellipsis = true;
+        self
+    }
+    pub fn selection_color(mut self, color: Rgba) -> Self {
+        self.selection_color = color;
+        self
+    }
+}
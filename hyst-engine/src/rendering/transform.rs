@@ -0,0 +1,75 @@
+use hyst_math::{Affine, Rect, vectors::Vec2f32};
+
+///Transform of an element and it's children, relative to where their layout places them. The layout isn't affected by it, so
+///siblings aren't moved. It's applied as translate, rotate, skew and then scale, around the `origin`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    ///Offset, in pixels
+    pub translate: Vec2f32,
+    ///Scale along each axis. Negative scales mirror the element, though the glyphs of texts stay upright
+    pub scale: Vec2f32,
+    ///Clockwise rotation, in degrees
+    pub rotate: f32,
+    ///Skew along each axis, in degrees
+    pub skew: Vec2f32,
+    ///Point the element is rotated, skewed and scaled around, as a fraction of it's size. The center by default
+    pub origin: Vec2f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translate: Vec2f32::default(),
+            scale: Vec2f32::new(1.0, 1.0),
+            rotate: 0.0,
+            skew: Vec2f32::default(),
+            origin: Vec2f32::new(0.5, 0.5),
+        }
+    }
+}
+
+impl Transform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn translate(mut self, x: f32, y: f32) -> Self {
+        self.translate = Vec2f32::new(x, y);
+        self
+    }
+    pub fn scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = Vec2f32::new(x, y);
+        self
+    }
+    pub fn rotate(mut self, degrees: f32) -> Self {
+        self.rotate = degrees;
+        self
+    }
+    pub fn skew(mut self, x: f32, y: f32) -> Self {
+        self.skew = Vec2f32::new(x, y);
+        self
+    }
+    pub fn origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = Vec2f32::new(x, y);
+        self
+    }
+
+    ///Gets the affine transform, in pixels, which applies this transform to the given `rect`
+    pub fn affine_of(&self, rect: &Rect) -> Affine {
+        let origin = *rect.position()
+            + Vec2f32::new(
+                rect.size().x() * self.origin.x(),
+                rect.size().y() * self.origin.y(),
+            );
+        Affine::translation(
+            origin.x() + self.translate.x(),
+            origin.y() + self.translate.y(),
+        )
+        .then(&Affine::rotation(self.rotate.to_radians()))
+        .then(&Affine::skew(
+            self.skew.x().to_radians(),
+            self.skew.y().to_radians(),
+        ))
+        .then(&Affine::scale(self.scale.x(), self.scale.y()))
+        .then(&Affine::translation(-origin.x(), -origin.y()))
+    }
+}
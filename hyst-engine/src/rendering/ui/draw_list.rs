@@ -0,0 +1,239 @@
+use hyst_math::Rect;
+use slotmap::SecondaryMap;
+
+use super::HystElementKey;
+
+///Something drawn by an element, along with the amount of rounded clips it's inside of
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum DrawOp {
+    Element(HystElementKey, u32),
+    ///Clips the next operations to the rounded corners of the element, until it's popped
+    PushMask(HystElementKey, u32),
+    PopMask(HystElementKey, u32),
+    ///Draws the things the element draws above it's children
+    Overlay(HystElementKey, u32),
+}
+
+///Something done before drawing the operations of a layer, which changes where they're drawn
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum LayerAction {
+    ///Draws the next layers on an offscreen target, until it's popped, so the effects of the element are applied to them
+    PushTarget,
+    ///Draws the offscreen target of the element through it's effects
+    PopTarget(HystElementKey),
+    ///Blurs what's drawn behind the element
+    Backdrop(HystElementKey),
+    ///Draws the next layers on the cached layer of the element, until it's popped. They're skipped if it's up to date
+    PushLayer(HystElementKey),
+    ///Draws the cached layer of the element, which is inside of the given amount of rounded clips
+    PopLayer(HystElementKey, u32),
+}
+
+///Sequence in which the elements are drawn, split in layers. The texts of a layer are drawn above the operations of it, all at once,
+///so a new layer is started whenever something is drawn above a text of the current one. They're clipped to the rounded clips
+///they're inside of through the stencil, so every text of a layer is inside of the same amount of them, and the layer ends
+///before the clips are popped.
+pub(crate) struct DrawList {
+    ops: Vec<DrawOp>,
+    ///Index of the first operation of each layer
+    starts: Vec<usize>,
+    ///Actions done before drawing each layer
+    actions: Vec<Vec<LayerAction>>,
+    ///Layer of each text
+    texts: SecondaryMap<HystElementKey, usize>,
+    ///Visible areas of the texts of the last layer
+    areas: Vec<Rect>,
+    ///Amount of rounded clips the texts of each layer are inside of
+    text_depths: Vec<u32>,
+}
+
+impl DrawList {
+    pub fn new() -> Self {
+        Self {
+            ops: Vec::new(),
+            starts: vec![0],
+            actions: vec![Vec::new()],
+            texts: SecondaryMap::new(),
+            areas: Vec::new(),
+            text_depths: vec![0],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.ops.clear();
+        self.starts = vec![0];
+        self.actions = vec![Vec::new()];
+        self.texts.clear();
+        self.areas.clear();
+        self.text_depths = vec![0];
+    }
+
+    ///Adds the given `op`, which draws inside of the given `area`. A new layer is started if it's above a text of the current one.
+    ///Operations which draw nothing visible, such as masks, have no area.
+    pub fn push(&mut self, op: DrawOp, area: Option<Rect>) {
+        let covers_text = area.is_some_and(|area| {
+            self.areas
+                .iter()
+                .any(|text| text.intersection(&area).is_some())
+        });
+        //Texts inside of the popped clip must be drawn while it's still on the stencil
+        let unclips_text = match op {
+            DrawOp::PopMask(_, depth) => self.has_texts() && depth < self.text_depth(),
+            _ => false,
+        };
+        if covers_text || unclips_text {
+            self.start_layer();
+        }
+        self.ops.push(op);
+    }
+
+    ///Adds the given `action`, which is done after drawing everything pushed until now. A new layer is started if the current one
+    ///already draws something.
+    pub fn push_action(&mut self, action: LayerAction) {
+        if self
+            .starts
+            .last()
+            .is_some_and(|start| *start < self.ops.len())
+        {
+            self.start_layer();
+        }
+        self.actions.last_mut().unwrap().push(action);
+    }
+
+    fn start_layer(&mut self) {
+        self.starts.push(self.ops.len());
+        self.actions.push(Vec::new());
+        self.areas.clear();
+        self.text_depths.push(0);
+    }
+
+    #[inline]
+    ///Whether the current layer has texts
+    fn has_texts(&self) -> bool {
+        !self.areas.is_empty()
+    }
+
+    #[inline]
+    ///Gets the amount of rounded clips the texts of the current layer are inside of
+    fn text_depth(&self) -> u32 {
+        *self.text_depths.last().unwrap()
+    }
+
+    ///Adds the text with the given `key`, whose glyphs are visible inside of the given `area`, to the current layer.
+    ///The given `op` draws the decorations of the text, which are below it's glyphs.
+    pub fn push_text(&mut self, key: HystElementKey, op: DrawOp, area: Rect) {
+        let (DrawOp::Element(_, depth)
+        | DrawOp::PushMask(_, depth)
+        | DrawOp::PopMask(_, depth)
+        | DrawOp::Overlay(_, depth)) = op;
+        if self.has_texts() && depth != self.text_depth() {
+            self.start_layer();
+        }
+        *self.text_depths.last_mut().unwrap() = depth;
+        self.ops.push(op);
+        self.texts.insert(key, self.starts.len() - 1);
+        self.areas.push(area);
+    }
+
+    #[inline]
+    pub fn layer_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    #[inline]
+    ///Gets the layer the glyphs of the text with the given `key` are drawn on
+    pub fn layer_of(&self, key: HystElementKey) -> Option<usize> {
+        self.texts.get(key).copied()
+    }
+
+    #[inline]
+    ///Gets the amount of rounded clips the texts of the given `layer` are inside of, which is their stencil reference
+    pub fn text_depth_of(&self, layer: usize) -> u32 {
+        self.text_depths.get(layer).copied().unwrap_or(0)
+    }
+
+    ///Gets the actions done before drawing the given `layer`
+    pub fn actions_of(&self, layer: usize) -> &[LayerAction] {
+        self.actions.get(layer).map_or(&[], Vec::as_slice)
+    }
+
+    ///Gets the operations of the given `layer`
+    pub fn ops_of(&self, layer: usize) -> &[DrawOp] {
+        let Some(start) = self.starts.get(layer) else {
+            return &[];
+        };
+        let end = self
+            .starts
+            .get(layer + 1)
+            .copied()
+            .unwrap_or(self.ops.len());
+        &self.ops[*start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use super::*;
+
+    fn keys(amount: usize) -> Vec<HystElementKey> {
+        let mut map = SlotMap::<HystElementKey, ()>::with_key();
+        (0..amount).map(|_| map.insert(())).collect()
+    }
+
+    fn area(x: f32) -> Rect {
+        Rect::from_xywh(x, 0.0, 10.0, 10.0)
+    }
+
+    #[test]
+    fn drawing_above_a_text_starts_a_layer() {
+        let [text, beside, above] = keys(3)[..] else {
+            unreachable!()
+        };
+        let mut list = DrawList::new();
+        list.push_text(text, DrawOp::Element(text, 0), area(0.0));
+        list.push(DrawOp::Element(beside, 0), Some(area(20.0)));
+        assert_eq!(list.layer_count(), 1);
+        list.push(DrawOp::Element(above, 0), Some(area(5.0)));
+        assert_eq!(list.layer_count(), 2);
+        assert_eq!(list.layer_of(text), Some(0));
+        assert_eq!(list.ops_of(1).len(), 1);
+    }
+
+    #[test]
+    fn texts_of_a_layer_are_inside_of_the_same_clips() {
+        let [outside, clip, inside, nested] = keys(4)[..] else {
+            unreachable!()
+        };
+        let mut list = DrawList::new();
+        list.push_text(outside, DrawOp::Element(outside, 0), area(0.0));
+        list.push(DrawOp::Element(clip, 0), Some(area(20.0)));
+        list.push(DrawOp::PushMask(clip, 0), None);
+        list.push_text(inside, DrawOp::Element(inside, 1), area(20.0));
+        list.push_text(nested, DrawOp::Element(nested, 1), area(25.0));
+        assert_eq!(list.layer_of(outside), Some(0));
+        assert_eq!(list.layer_of(inside), Some(1));
+        assert_eq!(list.layer_of(nested), Some(1));
+        assert_eq!(list.text_depth_of(0), 0);
+        assert_eq!(list.text_depth_of(1), 1);
+        //The texts inside of the clip are drawn before it's popped
+        list.push(DrawOp::PopMask(clip, 0), None);
+        assert_eq!(list.layer_count(), 3);
+        assert!(matches!(list.ops_of(2), [DrawOp::PopMask(..)]));
+        assert_eq!(list.text_depth_of(2), 0);
+    }
+
+    #[test]
+    fn popping_a_clip_without_texts_inside_keeps_the_layer() {
+        let [text, clip, child] = keys(3)[..] else {
+            unreachable!()
+        };
+        let mut list = DrawList::new();
+        list.push_text(text, DrawOp::Element(text, 0), area(0.0));
+        list.push(DrawOp::PushMask(clip, 0), None);
+        list.push(DrawOp::Element(child, 1), Some(area(20.0)));
+        list.push(DrawOp::PopMask(clip, 0), None);
+        assert_eq!(list.layer_count(), 1);
+    }
+}
@@ -1,22 +1,77 @@
-use std::any::Any;
-
-use hyst_math::Rect;
-use slotmap::SlotMap;
-use smol_str::SmolStr;
-use taffy::NodeId;
+use std::{any::Any, time::Duration};
 
 use crate::{
     HystLayout,
     background::Background,
     core::RenderingCore,
+    effects::{CachedLayer, Effects, Frame},
     elements::{
-        HystBox, HystBoxCreationOption, HystElement, HystImage, HystImageCreationOption, HystText,
+        HystAnimation, HystAnimationCreationOption, HystBox, HystBoxCreationOption, HystCanvas,
+        HystCanvasCreationOption, HystCustomCreationOption, HystElement, HystImage,
+        HystImageCreationOption, HystList, HystListCreationOption, HystPainter,
+        HystPainterCreationOption, HystScroll, HystScrollCreationOption, HystText, ScrollAxis,
         TextCreationOption,
     },
     error::LayoutError,
+    meshes::clip::ClipMask,
+    transform::Transform,
+};
+use hyst_math::{Affine, Rect, vectors::Vec2f32};
+use slotmap::{SecondaryMap, SlotMap};
+use smol_str::SmolStr;
+use taffy::{NodeId, Overflow};
+
+use super::{
+    HystAnimationOptions, HystCanvasOptions, HystElementKey, HystImageOptions, HystListOptions,
+    HystPainterOptions, HystScrollOptions, HystTextOptions,
+    draw_list::{DrawList, DrawOp, LayerAction},
+    pulse::Pulse,
 };
 
-use super::{HystElementKey, HystTextOptions};
+///Transform, opacity and clip an element is drawn with on the target it's drawn on, which is the screen, or the texture of the
+///cached layer it's inside of
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Space {
+    world: Affine,
+    alpha: f32,
+    clip: Rect,
+}
+
+impl Space {
+    ///Gets the space of an element inside of this one, transformed by the given `local` transform and faded by the given `opacity`,
+    ///clipped to the given `clip`
+    fn inner(&self, local: Option<&Affine>, opacity: f32, clip: Rect) -> Self {
+        Self {
+            world: local.map_or(self.world, |local| self.world.then(local)),
+            alpha: self.alpha * opacity,
+            clip,
+        }
+    }
+}
+
+///Gets the space an element inside of the given `parent` space is drawn in. If it's subtree is cached on a layer covering the given
+///`area`, it's drawn on it untransformed and opaque, and the second space is the one the layer is drawn in, which has the
+///transform and the opacity of the element instead. Moving or fading the element then only moves or fades the layer.
+fn spaces_of(
+    parent: &Space,
+    local: Option<&Affine>,
+    opacity: f32,
+    clip: Rect,
+    layer: Option<Rect>,
+) -> (Space, Option<Space>) {
+    let space = parent.inner(local, opacity, clip);
+    match layer {
+        Some(area) => (
+            Space {
+                world: Affine::default(),
+                alpha: 1.0,
+                clip: area,
+            },
+            Some(space),
+        ),
+        None => (space, None),
+    }
+}
 
 ///Entry point for the managing how the ui is shown on the screen.
 ///Things related to pulses, and events, even if they do modify the ui, they're handled on the HystUi which is used to request some management
@@ -25,6 +80,52 @@ pub struct ElementManager {
     elements: SlotMap<HystElementKey, Box<dyn HystElement>>,
     texts: Vec<HystElementKey>, // used for getting track of texts and using them for drawing.
     roots: Vec<HystElementKey>,
+    ///Roots drawn above every other root, in the order they were shown
+    overlays: Vec<HystElementKey>,
+    ///Sequence in which the elements are drawn, computed when resizing
+    draw_list: DrawList,
+    ///Z-index of the elements which define one. Children are drawn sorted by it, the ones with the same z-index in the order they were added
+    z_indices: SecondaryMap<HystElementKey, i32>,
+    ///Effects of the elements which define them, applied to them and their children
+    effects: SecondaryMap<HystElementKey, Pulse<Effects>>,
+    ///Values of the effects on the last resize, used to know when they changed
+    applied_effects: SecondaryMap<HystElementKey, Effects>,
+    ///Effects the offscreen targets of the elements are composited with, computed when resizing
+    composites: SecondaryMap<HystElementKey, Effects>,
+    ///Opacities of the elements which define them, and whether they're applied to the subtree as a whole
+    opacities: SecondaryMap<HystElementKey, (Pulse<f32>, bool)>,
+    ///Values of the opacities on the last resize, used to know when they changed
+    applied_opacities: SecondaryMap<HystElementKey, f32>,
+    ///Opacity each element is drawn with, multiplied with the ones of it's ancestors, computed when resizing
+    alphas: SecondaryMap<HystElementKey, f32>,
+    ///Transforms of the elements which define them, applied to them and their children
+    transforms: SecondaryMap<HystElementKey, Pulse<Transform>>,
+    ///Values of the transforms on the last resize, used to know when they changed
+    applied_transforms: SecondaryMap<HystElementKey, Transform>,
+    ///Transform of each element composed with the ones of it's ancestors, computed when resizing
+    worlds: SecondaryMap<HystElementKey, Affine>,
+    ///Space each element is drawn in, computed when resizing. Outside of cached layers, it has the values on the screen
+    spaces: SecondaryMap<HystElementKey, Space>,
+    ///Layers of the elements whose subtrees are cached
+    layers: SecondaryMap<HystElementKey, CachedLayer>,
+    ///Cached layers the element being resized is inside of, the innermost last
+    open_layers: Vec<HystElementKey>,
+    ///Parent of each element, computed when resizing
+    parents: SecondaryMap<HystElementKey, HystElementKey>,
+    scrolls: Vec<HystElementKey>, // used for finding which scroll is under the cursor.
+    ///Images whose sources are still being decoded
+    loading: Vec<HystElementKey>,
+    animations: Vec<HystElementKey>,
+    ///Painters, whose texts are drawn along with the ones of the text elements
+    painters: Vec<HystElementKey>,
+    ///Rects of the elements in screen coordinates, computed when resizing
+    rects: SecondaryMap<HystElementKey, Rect>,
+    ///Area each element is clipped to, based on it's ancestors
+    clips: SecondaryMap<HystElementKey, Rect>,
+    ///Masks of the elements which clip their children to rounded corners
+    masks: SecondaryMap<HystElementKey, ClipMask>,
+    ///Order in which the elements are drawn, computed when resizing. Elements drawn later are above the ones drawn before
+    order: SecondaryMap<HystElementKey, usize>,
 }
 
 impl ElementManager {
@@ -34,6 +135,30 @@ impl ElementManager {
             texts: Vec::new(),
             elements: SlotMap::with_key(),
             roots: Vec::new(),
+            overlays: Vec::new(),
+            draw_list: DrawList::new(),
+            z_indices: SecondaryMap::new(),
+            effects: SecondaryMap::new(),
+            applied_effects: SecondaryMap::new(),
+            composites: SecondaryMap::new(),
+            opacities: SecondaryMap::new(),
+            applied_opacities: SecondaryMap::new(),
+            alphas: SecondaryMap::new(),
+            transforms: SecondaryMap::new(),
+            applied_transforms: SecondaryMap::new(),
+            worlds: SecondaryMap::new(),
+            spaces: SecondaryMap::new(),
+            layers: SecondaryMap::new(),
+            open_layers: Vec::new(),
+            parents: SecondaryMap::new(),
+            scrolls: Vec::new(),
+            loading: Vec::new(),
+            animations: Vec::new(),
+            painters: Vec::new(),
+            rects: SecondaryMap::new(),
+            clips: SecondaryMap::new(),
+            masks: SecondaryMap::new(),
+            order: SecondaryMap::new(),
         }
     }
 
@@ -47,6 +172,35 @@ impl ElementManager {
         self.layout.create_element_style(parent, name)
     }
 
+    ///Generates a new layout for an element which is child of the element with the given `parent` key. If it's None, then the element is understood as a Root element
+    pub fn generate_layout_in(
+        &mut self,
+        parent: Option<HystElementKey>,
+        name: SmolStr,
+    ) -> Result<NodeId, LayoutError> {
+        let parent = self.parent_layout(parent)?;
+        self.layout.create_element_style(parent, name)
+    }
+
+    ///Gets the layout the children of the element with the given `parent` key are created in
+    fn parent_layout(&self, parent: Option<HystElementKey>) -> Result<Option<NodeId>, LayoutError> {
+        match parent {
+            Some(key) => match self.elements.get(key) {
+                Some(element) => Ok(Some(element.children_layout())),
+                None => Err(LayoutError::InvalidParent),
+            },
+            None => Ok(None),
+        }
+    }
+
+    ///Adds the element with the given `key` as the last child of `parent`, or as a root if it's None
+    fn attach(&mut self, parent: Option<HystElementKey>, key: HystElementKey) {
+        match parent.and_then(|parent| self.elements.get_mut(parent)) {
+            Some(parent) => parent.children_mut().push(key),
+            None => self.roots.push(key),
+        }
+    }
+
     #[inline]
     ///Creates a new layout and maps the given name to it.
     pub fn create_layout(&mut self, name: &str, style: taffy::Style) {
@@ -67,33 +221,38 @@ impl ElementManager {
     ///Inserts a new HystBox on the ui.
     pub fn insert_box(
         &mut self,
+        parent: Option<HystElementKey>,
         layout_id: NodeId,
         background: Background,
         rect: hyst_math::Rect,
+        radius: f32,
         core: &mut RenderingCore,
     ) -> HystElementKey {
-        self.elements.insert_with_key(|key| {
-            self.roots.push(key);
+        let key = self.elements.insert_with_key(|key| {
             Box::new(HystBox::new(
                 core,
                 HystBoxCreationOption {
                     background,
                     rect,
-                    parent: None,
+                    radius,
+                    parent,
                     style: layout_id,
                     key,
                 },
             ))
-        })
+        });
+        self.attach(parent, key);
+        key
     }
     ///Inserts a new HystText on the ui
     pub fn insert_text(
         &mut self,
         core: &mut RenderingCore,
+        parent: Option<HystElementKey>,
         opts: HystTextOptions,
     ) -> Result<HystElementKey, LayoutError> {
-        let style = self.generate_layout(None, opts.style)?;
-        Ok(self.elements.insert_with_key(|key| {
+        let style = self.generate_layout_in(parent, opts.style)?;
+        let key = self.elements.insert_with_key(|key| {
             self.texts.push(key);
             Box::new(HystText::new(
                 core,
@@ -101,35 +260,329 @@ impl ElementManager {
                     key,
                     layout: style,
                     font_size: opts.font_size,
-                    line_height: opts.font_size * 0.5,
-                    position: opts.position,
+                    line_height: opts.font_size * 1.2,
                     content: opts.content,
                     color: opts.color,
+                    style: opts.text_style,
                 },
             ))
-        }))
+        });
+        self.layout.set_measured(style, key)?;
+        self.attach(parent, key);
+        Ok(key)
     }
 
-    ///Inserts a new HystImage on the ui .
+    ///Inserts a new HystImage on the ui, laid out on the node with the given `layout_id`
     pub fn insert_image(
         &mut self,
         core: &mut RenderingCore,
+        parent: Option<HystElementKey>,
         rect: Rect,
-        source: String,
         layout_id: NodeId,
+        options: HystImageOptions,
     ) -> HystElementKey {
-        self.elements.insert_with_key(|key| {
-            self.roots.push(key);
+        let key = self.elements.insert_with_key(|key| {
             Box::new(HystImage::new(
                 core,
                 HystImageCreationOption {
-                    source,
+                    source: options.source,
+                    display: options.display,
+                    max_size: options.max_size,
+                    placeholder: options.placeholder,
                     rect,
+                    parent,
                     style: layout_id,
                     key,
                 },
             ))
-        })
+        });
+        if let Some(Some(image)) = self.get_element_with_type::<HystImage>(key)
+            && image.is_loading()
+        {
+            self.loading.push(key);
+        }
+        self.attach(parent, key);
+        key
+    }
+
+    ///Inserts a new HystAnimation on the ui, laid out on the node with the given `layout_id`
+    pub fn insert_animation(
+        &mut self,
+        core: &mut RenderingCore,
+        parent: Option<HystElementKey>,
+        rect: Rect,
+        layout_id: NodeId,
+        options: HystAnimationOptions,
+    ) -> HystElementKey {
+        let key = self.elements.insert_with_key(|key| {
+            Box::new(HystAnimation::new(
+                core,
+                HystAnimationCreationOption {
+                    source: options.source,
+                    display: options.display,
+                    max_size: options.max_size,
+                    placeholder: options.placeholder,
+                    playing: options.playing,
+                    looping: options.looping,
+                    rect,
+                    parent,
+                    style: layout_id,
+                    key,
+                },
+            ))
+        });
+        self.animations.push(key);
+        self.attach(parent, key);
+        key
+    }
+
+    ///Inserts a new HystCanvas on the ui, laid out on the node with the given `layout_id`
+    pub fn insert_canvas(
+        &mut self,
+        core: &mut RenderingCore,
+        parent: Option<HystElementKey>,
+        layout_id: NodeId,
+        options: HystCanvasOptions,
+    ) -> HystElementKey {
+        let key = self.elements.insert_with_key(|key| {
+            Box::new(HystCanvas::new(
+                core,
+                HystCanvasCreationOption {
+                    shapes: options.shapes,
+                    parent,
+                    style: layout_id,
+                    key,
+                },
+            ))
+        });
+        self.attach(parent, key);
+        key
+    }
+
+    ///Inserts a new HystPainter on the ui, laid out on the node with the given `layout_id`
+    pub fn insert_painter(
+        &mut self,
+        parent: Option<HystElementKey>,
+        layout_id: NodeId,
+        options: HystPainterOptions,
+    ) -> HystElementKey {
+        let key = self.elements.insert_with_key(|key| {
+            Box::new(HystPainter::new(HystPainterCreationOption {
+                paint: options.paint,
+                parent,
+                style: layout_id,
+                key,
+            }))
+        });
+        self.painters.push(key);
+        self.attach(parent, key);
+        key
+    }
+
+    ///Inserts the element built by the given `build` function on the ui, laid out on the node with the given `layout_id`.
+    ///The node is measured by the element, as texts are
+    pub fn insert_custom<E, F>(
+        &mut self,
+        core: &mut RenderingCore,
+        parent: Option<HystElementKey>,
+        layout_id: NodeId,
+        build: F,
+    ) -> Result<HystElementKey, LayoutError>
+    where
+        E: HystElement,
+        F: FnOnce(HystCustomCreationOption, &mut RenderingCore) -> E,
+    {
+        let key = self.elements.insert_with_key(|key| {
+            Box::new(build(
+                HystCustomCreationOption {
+                    parent,
+                    style: layout_id,
+                    key,
+                },
+                core,
+            ))
+        });
+        self.layout.set_measured(layout_id, key)?;
+        self.attach(parent, key);
+        Ok(key)
+    }
+
+    ///Uploads the frames of the animations which finished decoding and advances the ones which are playing by `dt`.
+    /// # Returns
+    /// Whether some animation is loading or playing, so the ui must be drawn again
+    pub fn tick_animations(&mut self, core: &mut RenderingCore, dt: Duration) -> bool {
+        let mut active = false;
+        self.animations
+            .retain(|key| self.elements.contains_key(*key));
+        for key in self.animations.clone() {
+            let Some(animation) = self.elements.get_mut(key).and_then(|element| {
+                (&mut **element as &mut dyn Any).downcast_mut::<HystAnimation>()
+            }) else {
+                continue;
+            };
+            let loaded = animation.poll(core);
+            let (changed, playing) = animation.tick(core, dt);
+            active |= animation.is_loading() || playing;
+            if loaded || changed {
+                self.invalidate(key);
+            }
+        }
+        active
+    }
+
+    ///Uploads the sources of the images which finished decoding.
+    /// # Returns
+    /// Whether some image is still loading or finished loading, so the ui must be drawn again
+    pub fn poll_images(&mut self, core: &mut RenderingCore) -> bool {
+        let polling = !self.loading.is_empty();
+        for key in std::mem::take(&mut self.loading) {
+            let Some(element) = self.elements.get_mut(key) else {
+                continue;
+            };
+            let Some(image) = (&mut **element as &mut dyn Any).downcast_mut::<HystImage>() else {
+                continue;
+            };
+            if image.poll(core) {
+                self.invalidate(key);
+            } else {
+                self.loading.push(key);
+            }
+        }
+        polling
+    }
+
+    ///Inserts a new HystScroll on the ui. The viewport uses the style with the name given on the options, and the children
+    ///are laid out on a node inside of it which grows on the scrolled axis.
+    pub fn insert_scroll(
+        &mut self,
+        core: &mut RenderingCore,
+        parent: Option<HystElementKey>,
+        opts: HystScrollOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        let style = self
+            .layout
+            .named_style(&opts.style)
+            .ok_or_else(|| LayoutError::InvalidStyleName(opts.style.to_string()))?;
+        let style = opts.axis.viewport_style(style);
+        let parent_layout = self.parent_layout(parent)?;
+        let viewport = self.layout.create_node(parent_layout, style)?;
+        let content = self
+            .layout
+            .create_node(Some(viewport), opts.axis.content_style())?;
+        let key = self.elements.insert_with_key(|key| {
+            Box::new(HystScroll::new(
+                core,
+                HystScrollCreationOption {
+                    key,
+                    viewport,
+                    content,
+                    axis: opts.axis,
+                    offset: opts.offset,
+                    inertia: opts.inertia,
+                    scrollbar: opts.scrollbar,
+                },
+            ))
+        });
+        self.scrolls.push(key);
+        self.attach(parent, key);
+        Ok(key)
+    }
+
+    ///Inserts a new HystList on the ui. It's rows are created by the ui when they become visible.
+    pub fn insert_list(
+        &mut self,
+        core: &mut RenderingCore,
+        parent: Option<HystElementKey>,
+        opts: HystListOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        let axis = ScrollAxis::Vertical;
+        let style = self
+            .layout
+            .named_style(&opts.style)
+            .ok_or_else(|| LayoutError::InvalidStyleName(opts.style.to_string()))?;
+        let style = axis.viewport_style(style);
+        let parent_layout = self.parent_layout(parent)?;
+        let viewport = self.layout.create_node(parent_layout, style)?;
+        let content = self
+            .layout
+            .create_node(Some(viewport), axis.content_style())?;
+        let key = self.elements.insert_with_key(|key| {
+            Box::new(HystList::new(
+                core,
+                HystListCreationOption {
+                    scroll: HystScrollCreationOption {
+                        key,
+                        viewport,
+                        content,
+                        axis,
+                        offset: opts.offset,
+                        inertia: opts.inertia,
+                        scrollbar: opts.scrollbar,
+                    },
+                    count: opts.count,
+                    estimated_height: opts.estimated_height,
+                    overscan: opts.overscan,
+                },
+            ))
+        });
+        self.scrolls.push(key);
+        self.attach(parent, key);
+        Ok(key)
+    }
+
+    ///Replaces the children of the element with the given `key`, laying them out in the given order. Elements which are no longer
+    ///children aren't removed, they're just not laid out nor drawn until they're children of some element again.
+    pub fn set_children(
+        &mut self,
+        key: HystElementKey,
+        children: Vec<HystElementKey>,
+    ) -> Result<(), LayoutError> {
+        let nodes = children
+            .iter()
+            .filter_map(|child| self.elements.get(*child).map(|child| child.layout()))
+            .collect::<Vec<_>>();
+        let Some(element) = self.elements.get_mut(key) else {
+            return Err(LayoutError::InvalidParent);
+        };
+        let removed = element
+            .children()
+            .iter()
+            .filter(|child| !children.contains(child))
+            .copied()
+            .collect::<Vec<_>>();
+        let node = element.children_layout();
+        *element.children_mut() = children;
+        self.layout.set_children(node, &nodes)?;
+        for child in removed {
+            self.forget_rects(child);
+        }
+        Ok(())
+    }
+
+    ///Removes the computed rects of the element with the given `key` and of it's children, since they aren't laid out anymore
+    fn forget_rects(&mut self, key: HystElementKey) {
+        self.rects.remove(key);
+        self.clips.remove(key);
+        self.spaces.remove(key);
+        self.order.remove(key);
+        self.masks.remove(key);
+        let children = match self.elements.get(key) {
+            Some(element) => element.children().clone(),
+            None => return,
+        };
+        for child in children {
+            self.forget_rects(child);
+        }
+    }
+
+    #[inline]
+    pub fn layout(&self) -> &HystLayout {
+        &self.layout
+    }
+
+    #[inline]
+    pub fn layout_mut(&mut self) -> &mut HystLayout {
+        &mut self.layout
     }
 
     #[inline]
@@ -147,6 +600,269 @@ impl ElementManager {
             .collect()
     }
 
+    #[inline]
+    ///Gets the list of all Painters in the ui
+    pub fn painter_elements(&self) -> Vec<&HystPainter> {
+        self.painters
+            .iter()
+            .filter_map(|key| self.get_element_with_type(*key).unwrap())
+            .collect()
+    }
+
+    #[inline]
+    ///Gets the list of all Scrolls id's on the Ui
+    pub fn scrolls(&self) -> &Vec<HystElementKey> {
+        &self.scrolls
+    }
+
+    #[inline]
+    ///Gets the rect of the element with the given `key` in screen coordinates, as computed on the last resize
+    pub fn rect_of(&self, key: HystElementKey) -> Option<&Rect> {
+        self.rects.get(key)
+    }
+
+    #[inline]
+    ///Gets the area the element with the given `key` is clipped to
+    pub fn clip_of(&self, key: HystElementKey) -> Option<&Rect> {
+        self.clips.get(key)
+    }
+
+    ///Gets the keys of the scrolls whose visible area contains the given point, the innermost first.
+    pub fn scrolls_at(&self, x: f32, y: f32) -> Vec<HystElementKey> {
+        self.scrolls
+            .iter()
+            .rev()
+            .filter(|key| self.contains(**key, x, y))
+            .copied()
+            .collect()
+    }
+
+    ///Gets the topmost of the given elements whose visible area contains the given point
+    pub fn topmost_at<I>(&self, keys: I, x: f32, y: f32) -> Option<HystElementKey>
+    where
+        I: IntoIterator<Item = HystElementKey>,
+    {
+        keys.into_iter()
+            .filter(|key| self.contains(*key, x, y))
+            .max_by_key(|key| self.order.get(*key).copied().unwrap_or(0))
+    }
+
+    ///Draws the root with the given `key` above every other element. If it was already shown, it's moved
+    ///to the top.
+    pub fn show_overlay(&mut self, key: HystElementKey) {
+        self.roots.retain(|root| *root != key);
+        self.overlays.retain(|overlay| *overlay != key);
+        self.overlays.push(key);
+    }
+
+    ///Stops drawing the root with the given `key`, wheter it's an overlay or not. It's not removed, so it can be shown again later.
+    pub fn hide_overlay(&mut self, key: HystElementKey) {
+        self.roots.retain(|root| *root != key);
+        self.overlays.retain(|overlay| *overlay != key);
+        self.forget_rects(key);
+    }
+
+    #[inline]
+    ///Gets the keys of the overlays being shown, the topmost last
+    pub fn overlays(&self) -> &Vec<HystElementKey> {
+        &self.overlays
+    }
+
+    #[inline]
+    ///Gets the amount of layers drawn, as computed on the last resize. Texts of each layer are drawn above it's elements
+    pub fn layer_count(&self) -> usize {
+        self.draw_list.layer_count()
+    }
+
+    #[inline]
+    ///Gets the layer the text with the given `key` is drawn on, as computed on the last resize. None if it wasn't laid out
+    pub fn layer_of(&self, key: HystElementKey) -> Option<usize> {
+        self.draw_list.layer_of(key)
+    }
+
+    #[inline]
+    ///Gets the position of the element with the given `key` on the draw order, as computed on the last resize. Elements drawn later are above
+    pub fn order_of(&self, key: HystElementKey) -> Option<usize> {
+        self.order.get(key).copied()
+    }
+
+    ///Sets the z-index of the element with the given `key`, which is used on the next resize. Siblings are drawn sorted by their
+    ///z-index, so the ones with greater values are above. Each element is a stacking context: it's children are drawn with it,
+    ///above it and below the siblings above it, whatever their z-index is.
+    pub fn set_z_index(&mut self, key: HystElementKey, z_index: i32) {
+        self.z_indices.insert(key, z_index);
+        self.invalidate(key);
+    }
+
+    #[inline]
+    ///Gets the z-index of the element with the given `key`. Elements which didn't define one have 0
+    pub fn z_index_of(&self, key: HystElementKey) -> i32 {
+        self.z_indices.get(key).copied().unwrap_or(0)
+    }
+
+    ///Sets the `effects` of the element with the given `key`, which are applied to it and it's children from the next resize on.
+    ///The pulse doesn't update the element, so changing them doesn't draw it's cached layer again
+    pub fn set_effects(&mut self, key: HystElementKey, mut effects: Pulse<Effects>) {
+        effects.add_visual_dependency(key);
+        self.effects.insert(key, effects);
+        self.invalidate(key);
+    }
+
+    ///Whether some effects changed since the last resize
+    pub fn effects_changed(&self) -> bool {
+        self.effects
+            .iter()
+            .any(|(key, effects)| self.applied_effects.get(key) != Some(&*effects.read()))
+    }
+
+    #[inline]
+    ///Gets the effects of the element with the given `key`
+    pub fn effects_of(&self, key: HystElementKey) -> Option<&Pulse<Effects>> {
+        self.effects.get(key)
+    }
+
+    ///Sets the `opacity` of the element with the given `key`, from 0 to 1, from the next resize on. It's multiplied with the
+    ///opacity of it's children, unless it's a `group`, which draws the subtree on an offscreen target and fades it as a whole,
+    ///so overlapping children don't show through each other.
+    pub fn set_opacity(&mut self, key: HystElementKey, mut opacity: Pulse<f32>, group: bool) {
+        opacity.add_visual_dependency(key);
+        self.opacities.insert(key, (opacity, group));
+        self.invalidate(key);
+    }
+
+    ///Whether some opacities changed since the last resize
+    pub fn opacities_changed(&self) -> bool {
+        self.opacities.iter().any(|(key, (opacity, _))| {
+            self.applied_opacities.get(key) != Some(&opacity.read().clamp(0.0, 1.0))
+        })
+    }
+
+    #[inline]
+    ///Gets the opacity the element with the given `key` is drawn with, multiplied with the ones of it's ancestors,
+    ///as computed on the last resize
+    pub fn opacity_of(&self, key: HystElementKey) -> f32 {
+        self.alphas.get(key).copied().unwrap_or(1.0)
+    }
+
+    ///Sets the `transform` of the element with the given `key`, which is applied to it and it's children from the next resize on
+    pub fn set_transform(&mut self, key: HystElementKey, mut transform: Pulse<Transform>) {
+        transform.add_visual_dependency(key);
+        self.transforms.insert(key, transform);
+        self.invalidate(key);
+    }
+
+    #[inline]
+    ///Gets the transform of the element with the given `key`
+    pub fn transform_of(&self, key: HystElementKey) -> Option<&Pulse<Transform>> {
+        self.transforms.get(key)
+    }
+
+    ///Whether some transforms changed since the last resize
+    pub fn transforms_changed(&self) -> bool {
+        self.transforms
+            .iter()
+            .any(|(key, transform)| self.applied_transforms.get(key) != Some(&*transform.read()))
+    }
+
+    #[inline]
+    ///Gets the transform, in pixels, applied to the element with the given `key` along with the ones of it's ancestors,
+    ///as computed on the last resize
+    pub fn world_transform_of(&self, key: HystElementKey) -> Affine {
+        self.worlds.get(key).copied().unwrap_or_default()
+    }
+
+    ///Converts the given `point` on the screen into the coordinates of the layout of the element with the given `key`, undoing
+    ///the transforms applied to it. None if the transforms collapse the element, so no point is on it
+    pub fn to_local(&self, key: HystElementKey, point: Vec2f32) -> Option<Vec2f32> {
+        Some(self.world_transform_of(key).inverse()?.apply(point))
+    }
+
+    ///Gets the smallest rect aligned to the axes which contains the element with the given `key` once transformed
+    pub fn bounds_of(&self, key: HystElementKey) -> Option<Rect> {
+        let rect = self.rects.get(key)?;
+        Some(self.world_transform_of(key).bounds_of(rect))
+    }
+
+    #[inline]
+    ///Gets the transform the element with the given `key` is drawn with on the target it's drawn on. Inside of cached layers, it
+    ///doesn't have the transforms of the root of the layer and of it's ancestors, which are applied to the layer instead
+    pub(crate) fn drawn_world_of(&self, key: HystElementKey) -> Affine {
+        self.spaces
+            .get(key)
+            .map(|space| space.world)
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    ///Gets the opacity the element with the given `key` is drawn with on the target it's drawn on
+    pub(crate) fn drawn_opacity_of(&self, key: HystElementKey) -> f32 {
+        self.spaces.get(key).map_or(1.0, |space| space.alpha)
+    }
+
+    #[inline]
+    ///Gets the area the element with the given `key` is clipped to on the target it's drawn on
+    pub(crate) fn drawn_clip_of(&self, key: HystElementKey) -> Option<&Rect> {
+        self.spaces.get(key).map(|space| &space.clip)
+    }
+
+    ///Same as `bounds_of`, but on the target the element with the given `key` is drawn on
+    pub(crate) fn drawn_bounds_of(&self, key: HystElementKey) -> Option<Rect> {
+        let rect = self.rects.get(key)?;
+        Some(self.drawn_world_of(key).bounds_of(rect))
+    }
+
+    ///Whether the visible area of the element with the given `key` contains the given point, taking it's transform into account
+    pub fn contains(&self, key: HystElementKey, x: f32, y: f32) -> bool {
+        let (Some(rect), Some(clip)) = (self.rects.get(key), self.clips.get(key)) else {
+            return false;
+        };
+        clip.contains(x, y)
+            && self
+                .to_local(key, Vec2f32::new(x, y))
+                .is_some_and(|local| rect.contains(local.x(), local.y()))
+    }
+
+    ///Sets whether the subtree of the element with the given `key` is cached, from the next resize on. Cached subtrees are drawn
+    ///on a texture of their own without the transform and the opacity of their root, which are applied when drawing the texture
+    ///on the frame instead. It's only drawn again when some element inside of them changes relative to the root, so moving or
+    ///fading the root doesn't. Each cached layer takes a texture the size of the screen.
+    pub fn set_cached(&mut self, core: &mut RenderingCore, key: HystElementKey, cached: bool) {
+        match cached {
+            true if !self.layers.contains_key(key) => {
+                self.layers.insert(key, CachedLayer::new(core));
+            }
+            false => {
+                self.layers.remove(key);
+            }
+            _ => {}
+        }
+        self.invalidate(key);
+    }
+
+    #[inline]
+    ///Whether the subtree of the element with the given `key` is cached
+    pub fn is_cached(&self, key: HystElementKey) -> bool {
+        self.layers.contains_key(key)
+    }
+
+    ///Requests the cached layers the element with the given `key` is inside of, and it's own, to be drawn again
+    pub fn invalidate(&self, key: HystElementKey) {
+        let mut current = Some(key);
+        while let Some(key) = current {
+            if let Some(layer) = self.layers.get(key) {
+                layer.invalidate();
+            }
+            current = self.parents.get(key).copied();
+        }
+    }
+
+    ///Sorts the given `keys` by the z-index of their elements, keeping the order of the ones with the same z-index
+    fn sorted_by_z(&self, keys: &[HystElementKey]) -> Vec<HystElementKey> {
+        let mut keys = keys.to_vec();
+        keys.sort_by_key(|key| self.z_index_of(*key));
+        keys
+    }
+
     #[inline]
     ///Gets the list of all elements in the Ui
     pub fn elements(&self) -> &SlotMap<HystElementKey, Box<dyn HystElement>> {
@@ -166,18 +882,29 @@ impl ElementManager {
 
     #[inline]
     ///Gets the element which has the given `key`
+    #[allow(clippy::borrowed_box)]
     pub fn get_element(&self, key: HystElementKey) -> Option<&Box<dyn HystElement>> {
         self.elements.get(key)
     }
 
     #[inline]
-    ///Gets the element which has the given `key`
+    ///Gets the element which has the given `key`. Since it may be modified, the cached layers it's inside of are drawn again
     pub fn get_element_mut(&mut self, key: HystElementKey) -> Option<&mut Box<dyn HystElement>> {
+        self.invalidate(key);
         self.elements.get_mut(key)
     }
 
+    ///Gets the scroll with the given `key` for animating it. The cached layers it's inside of aren't drawn again, unless it moves
+    ///it's children when resized
+    pub(crate) fn scroll_mut(&mut self, key: HystElementKey) -> Option<&mut HystScroll> {
+        self.elements
+            .get_mut(key)
+            .and_then(|element| element.as_scroll_mut())
+    }
+
     #[inline]
     ///Gets every root element
+    #[allow(clippy::borrowed_box)]
     pub fn roots(&self) -> Vec<&Box<dyn HystElement>> {
         self.roots
             .iter()
@@ -193,7 +920,17 @@ impl ElementManager {
         Some(el.downcast_ref::<T>())
     }
 
+    ///Tries to get the element which has the given `key` mutably, casting it to <T>. Works like get_element_with_type.
+    pub fn get_element_mut_with_type<T: HystElement>(
+        &mut self,
+        key: HystElementKey,
+    ) -> Option<Option<&mut T>> {
+        let el = &mut **self.get_element_mut(key)? as &mut dyn Any;
+        Some(el.downcast_mut::<T>())
+    }
+
     ///Gets a vector containing all the children of the element which has the given `key`
+    #[allow(clippy::borrowed_box)]
     pub fn get_children_of(&self, key: HystElementKey) -> Vec<&Box<dyn HystElement>> {
         let mut out = Vec::new();
         if let Some(element) = self.elements.get(key) {
@@ -208,29 +945,268 @@ impl ElementManager {
     }
 
     #[inline]
-    ///Recalculates the sizes of the layouts of the elements. Still only updates positioning
-    fn recalc_layouts(&mut self, width: f32, height: f32) {
-        self.layout.recalculate(width, height).unwrap();
+    ///Marks the layout of the element with the given `key` as dirty, so it's measured again on the next recalculation.
+    pub fn mark_dirty(&mut self, key: HystElementKey) -> Result<(), LayoutError> {
+        let Some(element) = self.elements.get(key) else {
+            return Ok(());
+        };
+        self.layout.mark_dirty(element.layout())
+    }
+
+    #[inline]
+    ///Recalculates the sizes of the layouts of the elements. Elements with measured layouts are asked for their size
+    fn recalc_layouts(&mut self, core: &mut RenderingCore, width: f32, height: f32) {
+        let elements = &mut self.elements;
+        self.layout
+            .recalculate(width, height, |known, available, key| {
+                elements
+                    .get_mut(key)
+                    .map(|element| element.measure(core, known, available))
+                    .unwrap_or(taffy::Size::ZERO)
+            })
+            .unwrap();
     }
 
     ///Resizes the root and its children recursively
+    /// # Arguments
+    /// `origin` The position, in screen coordinates, of the layout the layout of the root is relative to.
+    /// `clip` The area the root is clipped to.
     pub fn resize_root(
         &mut self,
         core: &mut RenderingCore,
         root: HystElementKey,
-        width: f32,
-        height: f32,
+        origin: Vec2f32,
+        clip: Rect,
+    ) {
+        self.resize_element(core, root, origin, (clip, clip), 0, true);
+    }
+
+    ///Resizes the element with the given `key` and its children recursively, adding them to the draw list.
+    /// `clips` are the areas the element is clipped to on the screen, and on the target it's drawn on.
+    /// `depth` is the amount of rounded clips the element is inside of.
+    /// Unless `relayout` is true, only the elements which were moved, faded or never resized write their buffers again.
+    fn resize_element(
+        &mut self,
+        core: &mut RenderingCore,
+        key: HystElementKey,
+        origin: Vec2f32,
+        (clip, drawn_clip): (Rect, Rect),
+        depth: u32,
+        relayout: bool,
     ) {
-        let children: Vec<_> = {
-            let Some(parent) = self.elements.get_mut(root) else {
+        let transform = self.transforms.get(key).map(Pulse::cloned);
+        if let Some(transform) = transform {
+            self.applied_transforms.insert(key, transform);
+        }
+        let parent_world = self
+            .parents
+            .get(key)
+            .map(|parent| self.world_transform_of(*parent))
+            .unwrap_or_default();
+        let parent_space = self
+            .parents
+            .get(key)
+            .and_then(|parent| self.spaces.get(*parent))
+            .copied()
+            .unwrap_or(Space {
+                world: Affine::default(),
+                alpha: 1.0,
+                clip: drawn_clip,
+            });
+        let (opacity, group) = self
+            .opacities
+            .get(key)
+            .map_or((1.0, false), |(opacity, group)| {
+                (opacity.cloned().clamp(0.0, 1.0), *group)
+            });
+        //Groups are faded when compositing their target, so they're drawn on it as opaque as their ancestors let them
+        let parent_alpha = self
+            .parents
+            .get(key)
+            .map_or(1.0, |parent| self.opacity_of(*parent));
+        let factor = match group {
+            true => 1.0,
+            false => opacity,
+        };
+        let alpha = parent_alpha * factor;
+        self.alphas.insert(key, alpha);
+        //Groups are composited on the target the element is drawn on, so changing their opacity draws it again
+        let regrouped = self.opacities.contains_key(key)
+            && self.applied_opacities.insert(key, opacity) != Some(opacity);
+        let (children, origin, children_clips, draws_overlay, visible) = {
+            let Some(parent) = self.elements.get_mut(key) else {
                 return;
             };
-            let layout = self.layout.layout_of(parent.layout()).unwrap();
-            parent.resize(core, (width, height), layout);
-            parent.children().iter().cloned().collect()
+            let mut layout = *self.layout.layout_of(parent.layout()).unwrap();
+            layout.location.x += origin.x();
+            layout.location.y += origin.y();
+            let rect = Rect::from_xywh(
+                layout.location.x,
+                layout.location.y,
+                layout.size.width,
+                layout.size.height,
+            );
+            let local = transform.map(|transform| transform.affine_of(&rect));
+            let world = match local {
+                Some(local) => parent_world.then(&local),
+                None => parent_world,
+            };
+            let (width, height) = core.size();
+            let (space, layer_space) = spaces_of(
+                &parent_space,
+                local.as_ref(),
+                factor,
+                drawn_clip,
+                self.layers
+                    .contains_key(key)
+                    .then(|| Rect::from_xywh(0.0, 0.0, width as f32, height as f32)),
+            );
+            let resized = self.rects.insert(key, rect) != Some(rect);
+            if self.worlds.insert(key, world) != Some(world)
+                && let Some(text) = (parent.as_mut() as &mut dyn Any).downcast_mut::<HystText>()
+            {
+                text.set_world(world);
+            }
+            self.clips.insert(key, clip);
+            let previous = self.spaces.insert(key, space);
+            let transformed = previous.is_none_or(|previous| previous.world != space.world);
+            let faded = previous.is_none_or(|previous| previous.alpha != space.alpha);
+            let clipped = previous.is_none_or(|previous| previous.clip != space.clip);
+            let moved = resized || transformed;
+            if relayout || resized {
+                parent.resize(core, &layout);
+            }
+            if relayout || transformed {
+                parent.set_transform(core, &space.world);
+            }
+            if relayout || faded {
+                parent.set_opacity(core, space.alpha);
+            }
+            //Cached layers are drawn on the layers they're inside of, which must be drawn again if they're moved or faded
+            let placed = match (self.layers.get_mut(key), layer_space) {
+                (Some(layer), Some(layer_space)) => {
+                    if relayout {
+                        layer.resize(core);
+                    }
+                    layer.place(
+                        core,
+                        &layer_space.world,
+                        layer_space.alpha,
+                        layer_space.clip,
+                    )
+                }
+                _ => false,
+            };
+            if placed || regrouped {
+                for layer in &self.open_layers {
+                    self.layers[*layer].invalidate();
+                }
+            }
+            if layer_space.is_some() {
+                self.open_layers.push(key);
+            }
+            //Whatever the element looks like, the layers it's drawn on must be drawn again if it's somewhere else on them
+            if moved || clipped || faded {
+                for layer in &self.open_layers {
+                    self.layers[*layer].invalidate();
+                }
+            }
+            self.order.insert(key, self.order.len());
+            let mut origin = Vec2f32::new(layout.location.x, layout.location.y);
+            if parent.children_layout() != parent.layout() {
+                let inner = self.layout.layout_of(parent.children_layout()).unwrap();
+                origin = origin + Vec2f32::new(inner.location.x, inner.location.y);
+            }
+            let overflow = self.layout.overflow_of(parent.layout()).unwrap();
+            let clips_x = parent.clips_children() || overflow.x != Overflow::Visible;
+            let clips_y = parent.clips_children() || overflow.y != Overflow::Visible;
+            let radius = parent.corner_radius();
+            //Scissors can't clip to rotated nor skewed rects, so they're clipped through the stencil too
+            if clips_x && clips_y && (radius > 0.0 || !space.world.is_axis_aligned()) {
+                let fresh = !self.masks.contains_key(key);
+                let mask = self
+                    .masks
+                    .entry(key)
+                    .unwrap()
+                    .or_insert_with(|| ClipMask::new(core));
+                if relayout || moved || fresh {
+                    mask.resize(core, rect, radius, &space.world);
+                }
+            } else {
+                self.masks.remove(key);
+            }
+            let bounds = world.bounds_of(&rect);
+            let drawn_bounds = space.world.bounds_of(&rect);
+            (
+                parent.children().clone(),
+                origin + parent.children_offset(),
+                (
+                    clip_axes(&bounds, &clip, clips_x, clips_y),
+                    clip_axes(&drawn_bounds, &space.clip, clips_x, clips_y),
+                ),
+                parent.draws_overlay(),
+                drawn_bounds.intersection(&space.clip),
+            )
         };
-        for child in children {
-            self.resize_root(core, child, width, height);
+        let effects = self.effects.get(key).map(Pulse::cloned).unwrap_or_default();
+        if self.effects.contains_key(key) {
+            self.applied_effects.insert(key, effects);
+        }
+        if effects.backdrop_blur > 0.0 && visible.is_some() {
+            self.draw_list.push_action(LayerAction::Backdrop(key));
+        }
+        let mut composite = effects;
+        if group {
+            composite.opacity *= opacity;
+        }
+        let filtered = composite.filters_content();
+        if filtered {
+            self.composites.insert(key, composite);
+            self.draw_list.push_action(LayerAction::PushTarget);
+        }
+        let cached = self.layers.contains_key(key);
+        if cached {
+            self.draw_list.push_action(LayerAction::PushLayer(key));
+        }
+        //Painters which draw texts are on the layer of their glyphs too, so what's above them is drawn above the glyphs
+        let is_text = match self.get_element_with_type::<HystPainter>(key) {
+            Some(Some(painter)) => painter.has_texts(),
+            _ => matches!(self.get_element_with_type::<HystText>(key), Some(Some(_))),
+        };
+        let op = DrawOp::Element(key, depth);
+        match (is_text, visible) {
+            (true, Some(area)) => self.draw_list.push_text(key, op, area),
+            _ => self.draw_list.push(op, visible),
+        }
+        let masked = self.masks.contains_key(key);
+        if masked {
+            self.draw_list.push(DrawOp::PushMask(key, depth), None);
+        }
+        let children_depth = depth + masked as u32;
+        for child in self.sorted_by_z(&children) {
+            self.parents.insert(child, key);
+            self.resize_element(
+                core,
+                child,
+                origin,
+                children_clips,
+                children_depth,
+                relayout,
+            );
+        }
+        if masked {
+            self.draw_list.push(DrawOp::PopMask(key, depth), None);
+        }
+        if draws_overlay {
+            self.draw_list.push(DrawOp::Overlay(key, depth), visible);
+        }
+        if cached {
+            self.draw_list
+                .push_action(LayerAction::PopLayer(key, depth));
+            self.open_layers.pop();
+        }
+        if filtered {
+            self.draw_list.push_action(LayerAction::PopTarget(key));
         }
     }
 
@@ -239,11 +1215,260 @@ impl ElementManager {
     /// `width` The current width of the window
     /// `height` The current height of the window
     pub fn resize_roots(&mut self, core: &mut RenderingCore, width: f32, height: f32) {
-        self.recalc_layouts(width, height);
-        let mut idx = 0;
-        while let Some(root) = self.roots.get(idx) {
-            idx += 1;
-            self.resize_root(core, *root, width, height);
+        self.recalc_layouts(core, width, height);
+        self.compose(core, width, height, true);
+    }
+
+    ///Applies the transforms, opacities and effects of the elements again, keeping their layouts. The draw list is built again
+    ///since what's visible may have changed, but only the elements which were moved or faded write their buffers again.
+    /// # Arguments
+    /// `width` The current width of the window
+    /// `height` The current height of the window
+    pub fn recompose(&mut self, core: &mut RenderingCore, width: f32, height: f32) {
+        self.compose(core, width, height, false);
+    }
+
+    ///Walks every root, placing the elements and building the draw list. If `relayout` is true, every element is resized to it's layout
+    fn compose(&mut self, core: &mut RenderingCore, width: f32, height: f32, relayout: bool) {
+        self.order.clear();
+        self.draw_list.clear();
+        //Overlays are above every root, whatever their z-index is
+        let roots = self
+            .sorted_by_z(&self.roots)
+            .into_iter()
+            .chain(self.overlays.clone());
+        let screen = Rect::from_xywh(0.0, 0.0, width, height);
+        for root in roots {
+            self.resize_element(
+                core,
+                root,
+                Vec2f32::default(),
+                (screen, screen),
+                0,
+                relayout,
+            );
+        }
+    }
+
+    ///Renders every layer on the given `frame`, in order. Each element is clipped to the area defined by it's ancestors, and the
+    ///texts of each layer are drawn above it's elements
+    pub fn render(&self, frame: &mut Frame) {
+        let screen_size = frame.size();
+        //Cached layer which is up to date, whose elements aren't drawn
+        let mut skipped = None;
+        for layer in 0..self.draw_list.layer_count() {
+            for action in self.draw_list.actions_of(layer) {
+                match (skipped, *action) {
+                    (Some(key), LayerAction::PopLayer(popped, depth)) if key == popped => {
+                        skipped = None;
+                        self.draw_layer(frame, key, depth);
+                    }
+                    (Some(_), _) => {}
+                    (None, LayerAction::PushLayer(key))
+                        if self.layers.get(key).is_some_and(|layer| !layer.is_dirty()) =>
+                    {
+                        skipped = Some(key);
+                    }
+                    (None, action) => self.apply_action(frame, action),
+                }
+            }
+            if skipped.is_some() {
+                continue;
+            }
+            let pass = frame.pass();
+            for op in self.draw_list.ops_of(layer) {
+                let (DrawOp::Element(key, depth)
+                | DrawOp::PushMask(key, depth)
+                | DrawOp::PopMask(key, depth)
+                | DrawOp::Overlay(key, depth)) = *op;
+                let Some(element) = self.elements.get(key) else {
+                    continue;
+                };
+                let clip = match (op, element.clips_content()) {
+                    (DrawOp::Element(..), true) => self
+                        .drawn_clip_of(key)
+                        .zip(self.drawn_bounds_of(key))
+                        .and_then(|(clip, bounds)| bounds.intersection(clip)),
+                    _ => self.drawn_clip_of(key).copied(),
+                };
+                let Some((x, y, width, height)) =
+                    clip.and_then(|clip| scissor_of(&clip, screen_size))
+                else {
+                    //Nothing of the element nor of it's children would be visible
+                    continue;
+                };
+                pass.set_scissor_rect(x, y, width, height);
+                pass.set_stencil_reference(depth);
+                match op {
+                    DrawOp::Element(..) => element.render(pass),
+                    DrawOp::Overlay(..) => element.render_overlay(pass),
+                    DrawOp::PushMask(..) => {
+                        if let Some(mask) = self.masks.get(key) {
+                            mask.push(pass, depth);
+                        }
+                    }
+                    DrawOp::PopMask(..) => {
+                        if let Some(mask) = self.masks.get(key) {
+                            mask.pop(pass, depth);
+                        }
+                    }
+                }
+            }
+            frame.draw_texts(layer, self.draw_list.text_depth_of(layer));
+        }
+    }
+
+    fn apply_action(&self, frame: &mut Frame, action: LayerAction) {
+        match action {
+            LayerAction::PushLayer(key) => {
+                if let Some(layer) = self.layers.get(key) {
+                    frame.push_layer(layer);
+                }
+            }
+            LayerAction::PopLayer(key, depth) => {
+                if let Some(layer) = self.layers.get(key) {
+                    frame.pop_layer();
+                    layer.mark_clean();
+                    self.draw_layer(frame, key, depth);
+                }
+            }
+            LayerAction::PushTarget => frame.push_target(),
+            LayerAction::PopTarget(key) => {
+                let effects = self.composites.get(key).copied().unwrap_or_default();
+                frame.pop_target(&effects);
+            }
+            LayerAction::Backdrop(key) => {
+                let (Some(element), Some(rect), Some(clip), Some(effects)) = (
+                    self.elements.get(key),
+                    self.drawn_bounds_of(key),
+                    self.drawn_clip_of(key),
+                    self.effects.get(key),
+                ) else {
+                    return;
+                };
+                let sigma = effects.read().backdrop_blur;
+                if sigma > 0.0 {
+                    frame.backdrop(rect, element.corner_radius(), sigma, *clip);
+                }
+            }
         }
     }
+
+    ///Draws the texture of the cached layer of the element with the given `key`, which is inside of `depth` rounded clips
+    fn draw_layer(&self, frame: &mut Frame, key: HystElementKey, depth: u32) {
+        let screen_size = frame.size();
+        let Some(layer) = self.layers.get(key) else {
+            return;
+        };
+        let Some((x, y, width, height)) =
+            layer.clip().and_then(|clip| scissor_of(&clip, screen_size))
+        else {
+            return;
+        };
+        let pass = frame.pass();
+        pass.set_scissor_rect(x, y, width, height);
+        pass.set_stencil_reference(depth);
+        layer.draw(pass);
+    }
+}
+
+///Restricts the given `clip` to the given `rect` on the clipped axes. The result is empty if they don't overlap
+fn clip_axes(rect: &Rect, clip: &Rect, clips_x: bool, clips_y: bool) -> Rect {
+    let (position, size) = (rect.position(), rect.size());
+    let (clip_position, clip_size) = (clip.position(), clip.size());
+    let bounds = Rect::from_xywh(
+        if clips_x {
+            position.x()
+        } else {
+            clip_position.x()
+        },
+        if clips_y {
+            position.y()
+        } else {
+            clip_position.y()
+        },
+        if clips_x { size.x() } else { clip_size.x() },
+        if clips_y { size.y() } else { clip_size.y() },
+    );
+    bounds
+        .intersection(clip)
+        .unwrap_or(Rect::from_xywh(0.0, 0.0, 0.0, 0.0))
+}
+
+///Converts the given `clip` into a scissor rect, clamped to the screen. None if it's empty
+fn scissor_of(clip: &Rect, (width, height): (u32, u32)) -> Option<(u32, u32, u32, u32)> {
+    let left = clip.position().x().floor().clamp(0.0, width as f32) as u32;
+    let top = clip.position().y().floor().clamp(0.0, height as f32) as u32;
+    let right = (clip.position().x() + clip.size().x())
+        .ceil()
+        .clamp(0.0, width as f32) as u32;
+    let bottom = (clip.position().y() + clip.size().y())
+        .ceil()
+        .clamp(0.0, height as f32) as u32;
+    (right > left && bottom > top).then_some((left, top, right - left, bottom - top))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area() -> Rect {
+        Rect::from_xywh(0.0, 0.0, 800.0, 600.0)
+    }
+
+    fn screen() -> Space {
+        Space {
+            world: Affine::default(),
+            alpha: 1.0,
+            clip: area(),
+        }
+    }
+
+    #[test]
+    fn transforming_or_fading_a_cached_root_only_places_the_layer() {
+        let child = Affine::translation(10.0, 20.0);
+        let (before, placement) = spaces_of(
+            &screen(),
+            Some(&Affine::translation(5.0, 5.0)),
+            1.0,
+            area(),
+            Some(area()),
+        );
+        let (after, moved) = spaces_of(
+            &screen(),
+            Some(&Affine::rotation(1.0).then(&Affine::scale(2.0, 2.0))),
+            0.5,
+            area(),
+            Some(area()),
+        );
+        //The subtree is drawn on the layer exactly as before, so the layer is still up to date
+        assert_eq!(before, after);
+        assert_eq!(
+            before.inner(Some(&child), 0.8, area()),
+            after.inner(Some(&child), 0.8, area())
+        );
+        assert_eq!(after.world, Affine::default());
+        assert_eq!(after.alpha, 1.0);
+        let (placement, moved) = (placement.unwrap(), moved.unwrap());
+        assert_ne!(placement.world, moved.world);
+        assert_eq!(moved.alpha, 0.5);
+    }
+
+    #[test]
+    fn uncached_elements_are_drawn_in_the_space_of_their_parent() {
+        let parent = screen().inner(Some(&Affine::translation(5.0, 0.0)), 0.5, area());
+        let (space, placement) = spaces_of(
+            &parent,
+            Some(&Affine::translation(0.0, 5.0)),
+            0.5,
+            area(),
+            None,
+        );
+        assert!(placement.is_none());
+        assert_eq!(space.alpha, 0.25);
+        assert_eq!(
+            space.world.apply(Vec2f32::new(0.0, 0.0)),
+            Vec2f32::new(5.0, 5.0)
+        );
+    }
 }
@@ -1,30 +1,76 @@
+mod draw_list;
 mod element_manager;
 mod options;
+mod overlay;
 pub mod pulse;
 use std::{
     ops::{Deref, DerefMut},
+    path::Path,
     sync::mpsc::{Receiver, Sender, channel},
+    time::{Duration, Instant},
 };
 
 use element_manager::ElementManager;
 pub use options::*;
-use pulse::Pulse;
+use overlay::Overlay;
+pub use overlay::Placement;
+use pulse::{Pulse, PulseUpdate};
+use slotmap::SecondaryMap;
 
-use crate::{core::RenderingCore, error::LayoutError};
-use hyst_math::vectors::{Vec2f32, Vec4f32};
+use crate::{
+    core::RenderingCore,
+    effects::Effects,
+    error::LayoutError,
+    text::{FontFamily, PreparedText},
+    transform::Transform,
+    widgets::Widget,
+};
+use hyst_math::{
+    Rect,
+    vectors::{Vec2f32, Vec4f32},
+};
 pub use smol_str;
+use smol_str::SmolStr;
 pub use taffy;
+use winit::{
+    event::{ElementState, MouseButton, WindowEvent},
+    keyboard::{Key, NamedKey},
+};
 
-use super::elements::HystText;
+use super::elements::{HystCustomCreationOption, HystElement, HystList, RowBuilder};
 
 slotmap::new_key_type! {pub struct HystElementKey;}
 
+///Maximum amount of times the layouts are recalculated on a single resize, when lists create rows or measure their heights and when
+///overlays are placed next to their anchors
+const MAX_LAYOUT_PASSES: usize = 4;
+
 pub struct HystUi {
     core: RenderingCore,
     element_manager: ElementManager,
     bg: Vec4f32,
-    rx: Receiver<HystElementKey>,
-    tx: Sender<HystElementKey>,
+    rx: Receiver<PulseUpdate>,
+    tx: Sender<PulseUpdate>,
+    ///Last position of the cursor, used to find which element receives pointer events
+    cursor: Vec2f32,
+    ///Time of the last check for updates, used for animating scrolls
+    last_tick: Instant,
+    ///Builders of the rows of each list
+    lists: SecondaryMap<HystElementKey, Box<dyn RowBuilder>>,
+    ///Widgets by the key of the element they receive events from
+    widgets: SecondaryMap<HystElementKey, Box<dyn Widget>>,
+    ///Widget under the cursor
+    hovered: Option<HystElementKey>,
+    ///Widget the primary button was pressed on, which receives the pointer events until it's released
+    captured: Option<HystElementKey>,
+    ///Modals, popovers and tooltips, by the key of their content
+    overlays: SecondaryMap<HystElementKey, Overlay>,
+    ///Tooltip whose anchor is under the cursor, and since when. The time is None if the tooltip was dismissed while hovered
+    hovered_tooltip: Option<(HystElementKey, Option<Instant>)>,
+    ///Effects applied to the whole frame
+    frame_effects: Pulse<Effects>,
+    ///Frame effects the last frame was drawn with, used to know when they changed
+    drawn_effects: Effects,
 }
 
 ///Struct that manages the creation and modification of elements. Until now the modification can only be done here
@@ -32,11 +78,21 @@ impl HystUi {
     pub fn new(core: RenderingCore, bg: Vec4f32) -> Self {
         let (tx, rx) = channel();
         Self {
+            frame_effects: Pulse::new(Effects::default(), tx.clone()),
+            drawn_effects: Effects::default(),
             element_manager: ElementManager::new(),
             core,
             bg,
             rx,
             tx,
+            cursor: Vec2f32::default(),
+            last_tick: Instant::now(),
+            lists: SecondaryMap::new(),
+            widgets: SecondaryMap::new(),
+            hovered: None,
+            captured: None,
+            overlays: SecondaryMap::new(),
+            hovered_tooltip: None,
         }
     }
 
@@ -45,25 +101,540 @@ impl HystUi {
     }
 
     pub fn create_text(&mut self, options: HystTextOptions) -> Result<HystElementKey, LayoutError> {
-        self.element_manager.insert_text(&mut self.core, options)
+        self.element_manager
+            .insert_text(&mut self.core, None, options)
+    }
+
+    ///Creates a text as the last child of the element with the given `parent` key
+    pub fn create_text_in(
+        &mut self,
+        parent: HystElementKey,
+        options: HystTextOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        self.element_manager
+            .insert_text(&mut self.core, Some(parent), options)
     }
 
     pub fn create_box(&mut self, options: HystBoxOptions) -> Result<HystElementKey, LayoutError> {
-        let style = self.generate_layout(None, options.style)?;
+        self.insert_box(None, options)
+    }
+
+    ///Creates a box as the last child of the element with the given `parent` key
+    pub fn create_box_in(
+        &mut self,
+        parent: HystElementKey,
+        options: HystBoxOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        self.insert_box(Some(parent), options)
+    }
+
+    fn insert_box(
+        &mut self,
+        parent: Option<HystElementKey>,
+        options: HystBoxOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        let style = self.generate_layout_in(parent, options.style)?;
         let rect = self.get_rect(style)?;
-        Ok(self
-            .element_manager
-            .insert_box(style, options.bg, rect, &mut self.core))
+        Ok(self.element_manager.insert_box(
+            parent,
+            style,
+            options.bg,
+            rect,
+            options.radius,
+            &mut self.core,
+        ))
     }
+
     pub fn create_image(
         &mut self,
         options: HystImageOptions,
     ) -> Result<HystElementKey, LayoutError> {
-        let style = self.generate_layout(None, options.style)?;
+        self.insert_image(None, options)
+    }
+
+    ///Creates an image as the last child of the element with the given `parent` key
+    pub fn create_image_in(
+        &mut self,
+        parent: HystElementKey,
+        options: HystImageOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        self.insert_image(Some(parent), options)
+    }
+
+    fn insert_image(
+        &mut self,
+        parent: Option<HystElementKey>,
+        options: HystImageOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        let style = self.generate_layout_in(parent, options.style.clone())?;
         let rect = self.get_rect(style)?;
         Ok(self
             .element_manager
-            .insert_image(&mut self.core, rect, options.source, style))
+            .insert_image(&mut self.core, parent, rect, style, options))
+    }
+
+    ///Creates an animation, such as a gif or a sprite sheet. If `parent` is None, it's created as a root, otherwise as the last child
+    ///of the element with the given `parent` key. It's played while it's `playing` pulse is true.
+    pub fn create_animation(
+        &mut self,
+        parent: Option<HystElementKey>,
+        options: HystAnimationOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        let style = self.generate_layout_in(parent, options.style.clone())?;
+        let rect = self.get_rect(style)?;
+        Ok(self
+            .element_manager
+            .insert_animation(&mut self.core, parent, rect, style, options))
+    }
+
+    ///Creates a canvas which draws the vector shapes of it's options. If `parent` is None, it's created as a root, otherwise as the
+    ///last child of the element with the given `parent` key
+    pub fn create_canvas(
+        &mut self,
+        parent: Option<HystElementKey>,
+        options: HystCanvasOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        let style = self.generate_layout_in(parent, options.style.clone())?;
+        Ok(self
+            .element_manager
+            .insert_canvas(&mut self.core, parent, style, options))
+    }
+
+    ///Creates a painter, whose content is drawn by the callback of the given `options`. If `parent` is None, it's created as a root,
+    ///otherwise as the last child of the element with the given `parent` key.
+    pub fn create_painter(
+        &mut self,
+        parent: Option<HystElementKey>,
+        options: HystPainterOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        let style = self.generate_layout_in(parent, options.style.clone())?;
+        Ok(self.element_manager.insert_painter(parent, style, options))
+    }
+
+    ///Asks the painter with the given `key` to call it's callback again before the next frame, such as when the data it draws changed.
+    ///Painters can also be repainted whenever some pulse changes, by adding them as a dependency of it
+    pub fn repaint(&self, key: HystElementKey) {
+        self.tx.send(PulseUpdate::Content(key)).unwrap();
+    }
+
+    ///Inserts an element of a type defined outside of the crate, such as one drawn with a custom pipeline. If `parent` is None, it's
+    ///created as a root, otherwise as the last child of the element with the given `parent` key.
+    /// # Arguments
+    /// * `style` - Name of the layout the element is laid out with
+    /// * `build` - Creates the element, given it's key and it's layout, and the rendering core for creating it's buffers and shaders
+    ///
+    ///If the element has no children, it's size is measured with HystElement::measure, whose default is zero. Updating it through
+    ///a pulse measures it again. HystCustomCreationOption explains how custom elements draw through their own pipelines.
+    pub fn insert_custom<E, F>(
+        &mut self,
+        parent: Option<HystElementKey>,
+        style: &str,
+        build: F,
+    ) -> Result<HystElementKey, LayoutError>
+    where
+        E: HystElement,
+        F: FnOnce(HystCustomCreationOption, &mut RenderingCore) -> E,
+    {
+        let style = self.generate_layout_in(parent, style.into())?;
+        self.element_manager
+            .insert_custom(&mut self.core, parent, style, build)
+    }
+
+    ///Creates a scroll. If `parent` is None, it's created as a root, otherwise as the last child of the element with the given `parent` key.
+    ///Children created in the scroll are laid out in it's content, which grows on the scrolled axis.
+    pub fn create_scroll(
+        &mut self,
+        parent: Option<HystElementKey>,
+        options: HystScrollOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        self.element_manager
+            .insert_scroll(&mut self.core, parent, options)
+    }
+
+    ///Scrolls the scroll with the given `scroll` key the minimum required for the element with the given `target` key to be visible.
+    ///The target is expected to be a descendant of the scroll.
+    /// # Returns
+    /// * Whether the scroll moved
+    pub fn scroll_to(&mut self, scroll: HystElementKey, target: HystElementKey) -> bool {
+        let Some(target) = self.element_manager.rect_of(target).copied() else {
+            return false;
+        };
+        self.element_manager
+            .get_element_mut(scroll)
+            .and_then(|element| element.as_scroll_mut())
+            .is_some_and(|scroll| scroll.scroll_into_view(&target))
+    }
+
+    ///Creates a list which shows the amount of items defined by the `count` of the options. Rows are created by the given `builder`
+    ///only for the items which are visible, and are reused when scrolled out of view.
+    pub fn create_list<B: RowBuilder>(
+        &mut self,
+        parent: Option<HystElementKey>,
+        options: HystListOptions,
+        builder: B,
+    ) -> Result<HystElementKey, LayoutError> {
+        let key = self
+            .element_manager
+            .insert_list(&mut self.core, parent, options)?;
+        self.lists.insert(key, Box::new(builder));
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+        Ok(key)
+    }
+
+    ///Scrolls the list with the given `list` key so the item at the given `index` is at the top of it's viewport.
+    /// # Returns
+    /// * Whether the list moved
+    pub fn scroll_to_index(&mut self, list: HystElementKey, index: usize) -> bool {
+        self.with_element(list, |list: &mut HystList, _| {
+            let offset = Vec2f32::new(0.0, list.offset_of(index));
+            list.as_scroll_mut()
+                .is_some_and(|scroll| scroll.scroll_to_offset(offset))
+        })
+        .unwrap_or(false)
+    }
+
+    ///Creates the rows of the lists which became visible, reusing the ones which aren't visible anymore, and stores the heights
+    ///the rows were laid out with. Lists in `failed` are skipped, and lists whose rows can't be created are added to it, so they
+    ///aren't materialized again until the next resize.
+    /// # Returns
+    /// * Whether some list changed, so the layouts must be recalculated
+    fn virtualize_lists(&mut self, failed: &mut Vec<HystElementKey>) -> bool {
+        let mut changed = false;
+        for key in self.lists.keys().collect::<Vec<_>>() {
+            if failed.contains(&key) {
+                continue;
+            }
+            let Some(mut builder) = self.lists.remove(key) else {
+                continue;
+            };
+            match self.virtualize(key, builder.as_mut()) {
+                Ok(virtualized) => changed |= virtualized,
+                //The rows which were created are still laid out, so the ui changed anyway
+                Err(_) => {
+                    failed.push(key);
+                    changed = true;
+                }
+            }
+            self.lists.insert(key, builder);
+        }
+        changed
+    }
+
+    ///Materializes the rows of the list with the given `key` which became visible.
+    /// # Returns
+    /// * Whether the rows of the list changed
+    /// * An error if the builder can't create a row, after laying the ones it created out
+    fn virtualize(
+        &mut self,
+        key: HystElementKey,
+        builder: &mut dyn RowBuilder,
+    ) -> Result<bool, LayoutError> {
+        let Some(Some(list)) = self.element_manager.get_element_with_type::<HystList>(key) else {
+            return Ok(false);
+        };
+        let heights = list
+            .rows()
+            .iter()
+            .filter_map(|(idx, row)| Some((*idx, self.rect_of(*row)?.size().y())))
+            .collect::<Vec<_>>();
+        let Some(Some(list)) = self
+            .element_manager
+            .get_element_mut_with_type::<HystList>(key)
+        else {
+            return Ok(false);
+        };
+        let mut changed = false;
+        for (idx, height) in heights {
+            changed |= list.set_height(idx, height);
+        }
+        let range = list.visible_range();
+        changed |= list.recycle_outside(&range);
+        let missing = list.missing(range);
+        changed |= !missing.is_empty();
+        let mut failure = None;
+        for idx in missing {
+            let recycled = self
+                .element_manager
+                .get_element_mut_with_type::<HystList>(key)
+                .flatten()
+                .and_then(|list| list.take_recycled());
+            let row = match recycled {
+                Some(row) => row,
+                None => match builder.create(self, key) {
+                    Ok(row) => row,
+                    Err(error) => {
+                        failure = Some(error);
+                        break;
+                    }
+                },
+            };
+            builder.bind(self, row, idx);
+            if let Some(Some(list)) = self
+                .element_manager
+                .get_element_mut_with_type::<HystList>(key)
+            {
+                list.insert_row(idx, row);
+            }
+        }
+        if !changed {
+            return Ok(false);
+        }
+        let Some(Some(list)) = self.element_manager.get_element_with_type::<HystList>(key) else {
+            return Ok(false);
+        };
+        let children = list.rows().values().copied().collect();
+        let (content, style) = (list.children_layout(), list.content_style());
+        self.element_manager.set_children(key, children)?;
+        self.element_manager
+            .layout_mut()
+            .set_style(content, style)?;
+        match failure {
+            Some(error) => Err(error),
+            None => Ok(true),
+        }
+    }
+
+    ///Registers the given `widget`, so it receives the pointer events of it's element and is refreshed when the pulses it depends on change.
+    pub fn add_widget<W: Widget>(&mut self, widget: W) -> HystElementKey {
+        let key = widget.key();
+        self.widgets.insert(key, Box::new(widget));
+        self.with_widget(key, |widget, ui| widget.refresh(ui));
+        key
+    }
+
+    ///Executes the given function with the widget whose element has the given `key` and the ui. Returns None if there's no such widget.
+    pub fn with_widget<R, F>(&mut self, key: HystElementKey, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut dyn Widget, &mut HystUi) -> R,
+    {
+        let mut widget = self.widgets.remove(key)?;
+        let out = f(widget.as_mut(), self);
+        self.widgets.insert(key, widget);
+        Some(out)
+    }
+
+    ///Handles the given window `event`, routing pointer events such as scrolling to the elements under the cursor.
+    /// # Returns
+    /// * Whether the event changed the ui, so a redraw is required
+    pub fn handle_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor = Vec2f32::new(position.x as f32, position.y as f32);
+                let cursor = self.cursor;
+                self.core.set_cursor(cursor.x(), cursor.y());
+                let hovered = self.widget_at(cursor);
+                let mut changed = false;
+                if hovered != self.hovered {
+                    if let Some(previous) = self.hovered {
+                        changed |= self
+                            .with_widget(previous, |widget, ui| widget.on_hover(ui, false))
+                            .is_some();
+                    }
+                    if let Some(current) = hovered {
+                        changed |= self
+                            .with_widget(current, |widget, ui| widget.on_hover(ui, true))
+                            .is_some();
+                    }
+                    self.hovered = hovered;
+                }
+                if let Some(captured) = self.captured {
+                    changed |= self
+                        .with_widget(captured, |widget, ui| widget.on_drag(ui, cursor))
+                        .is_some();
+                }
+                changed | self.hover_tooltips(cursor)
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                let cursor = self.cursor;
+                match state {
+                    ElementState::Pressed => {
+                        let (hidden, consumed) = self.dismiss_at(cursor);
+                        if consumed {
+                            return hidden;
+                        }
+                        let Some(target) = self.widget_at(cursor) else {
+                            return hidden;
+                        };
+                        self.captured = Some(target);
+                        self.with_widget(target, |widget, ui| widget.on_press(ui, cursor))
+                            .is_some()
+                            || hidden
+                    }
+                    ElementState::Released => {
+                        let Some(captured) = self.captured.take() else {
+                            return false;
+                        };
+                        let inside = self
+                            .topmost_at([captured], cursor.x(), cursor.y())
+                            .is_some();
+                        self.with_widget(captured, |widget, ui| {
+                            widget.on_release(ui, cursor, inside)
+                        })
+                        .is_some()
+                    }
+                }
+            }
+            WindowEvent::MouseWheel { delta, phase, .. } => {
+                let (x, y) = (self.cursor.x(), self.cursor.y());
+                //The innermost scroll which is able to move consumes the event
+                self.element_manager
+                    .scrolls_at(x, y)
+                    .into_iter()
+                    .filter(|key| self.accepts_input(*key))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .any(|key| {
+                        self.element_manager
+                            .get_element_mut(key)
+                            .and_then(|element| element.as_scroll_mut())
+                            .is_some_and(|scroll| scroll.on_wheel(*delta, *phase))
+                    })
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                //Shaders get it on the next frame, which the resize following this event requests
+                self.core.set_scale_factor(*scale_factor as f32);
+                false
+            }
+            WindowEvent::KeyboardInput { event, .. }
+                if event.state == ElementState::Pressed
+                    && event.logical_key == Key::Named(NamedKey::Escape) =>
+            {
+                self.dismiss_topmost()
+            }
+            _ => false,
+        }
+    }
+
+    ///Gets the topmost widget under the given `position`, ignoring the ones below a modal
+    fn widget_at(&self, position: Vec2f32) -> Option<HystElementKey> {
+        let widgets = self.widgets.keys().filter(|key| self.accepts_input(*key));
+        self.element_manager
+            .topmost_at(widgets, position.x(), position.y())
+    }
+
+    ///Sets the z-index of the element with the given `key`, drawing it above the siblings with smaller ones, and lays the ui out again.
+    pub fn set_z_index(&mut self, key: HystElementKey, z_index: i32) {
+        self.element_manager.set_z_index(key, z_index);
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+    }
+
+    ///Sets the `effects` of the element with the given `key`, which are applied to it and it's children, and lays the ui out again.
+    ///Changing the pulse applies them on the next check for updates without laying the ui out, so they can be animated.
+    pub fn set_effects(&mut self, key: HystElementKey, effects: Pulse<Effects>) {
+        self.element_manager.set_effects(key, effects);
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+    }
+
+    ///Sets the `opacity` of the element with the given `key`, from 0 to 1, which is multiplied with the ones of it's children,
+    ///and lays the ui out again. Changing the pulse fades the subtree on the next check for updates without laying the ui out,
+    ///so it can be animated.
+    pub fn set_opacity(&mut self, key: HystElementKey, opacity: Pulse<f32>) {
+        self.element_manager.set_opacity(key, opacity, false);
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+    }
+
+    ///Same as `set_opacity`, but the subtree of the element is drawn on an offscreen target which is faded as a whole,
+    ///so overlapping children don't show through each other. Costs a texture the size of the screen while faded.
+    pub fn set_group_opacity(&mut self, key: HystElementKey, opacity: Pulse<f32>) {
+        self.element_manager.set_opacity(key, opacity, true);
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+    }
+
+    ///Sets the `transform` of the element with the given `key`, which is applied to it and it's children, and lays the ui out again.
+    ///Changing the pulse moves the subtree on the next check for updates without laying the ui out, so it can be animated.
+    ///Pointer events follow the transformed elements.
+    pub fn set_transform(&mut self, key: HystElementKey, transform: Pulse<Transform>) {
+        self.element_manager.set_transform(key, transform);
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+    }
+
+    ///Sets whether the subtree of the element with the given `key` is cached on a texture of it's own, which is only drawn again
+    ///when something inside of it changes relative to the element. Transforming or fading the element itself only moves or fades
+    ///the texture, so it's useful for complex subtrees which are moved or faded as a whole.
+    pub fn set_cached(&mut self, key: HystElementKey, cached: bool) {
+        self.element_manager.set_cached(&mut self.core, key, cached);
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+    }
+
+    #[inline]
+    ///Gets the effects applied to the whole frame
+    pub fn frame_effects(&self) -> &Pulse<Effects> {
+        &self.frame_effects
+    }
+
+    ///Sets the `effects` applied to the whole frame. They're checked on every check for updates, so they can be animated through the pulse.
+    pub fn set_frame_effects(&mut self, effects: Pulse<Effects>) {
+        self.frame_effects = effects;
+    }
+
+    ///Sets the font family used by every text whose style doesn't define one, updating the texts that already exist.
+    pub fn set_default_family(&mut self, family: FontFamily) {
+        self.core.set_default_family(family);
+        self.refresh_texts();
+    }
+
+    ///Loads the font on the given `path`, updating the texts that already exist so they can use it.
+    ///Returns the families of the loaded faces.
+    pub fn load_font_file(&mut self, path: impl AsRef<Path>) -> std::io::Result<Vec<SmolStr>> {
+        let families = self.core.load_font_file(path)?;
+        self.refresh_texts();
+        Ok(families)
+    }
+
+    ///Loads the font contained on the given `data`, which can be embedded with include_bytes!, updating the texts that already
+    ///exist so they can use it. Returns the families of the loaded faces.
+    pub fn load_font_data(&mut self, data: Vec<u8>) -> Vec<SmolStr> {
+        let families = self.core.load_font_data(data);
+        self.refresh_texts();
+        families
+    }
+
+    ///Removes every loaded font, including the system ones, and sets every text again with the remaining ones. If the
+    ///`default-font` feature is enabled, the bundled font is loaded again. Useful to make texts look the same on every machine.
+    pub fn clear_fonts(&mut self) {
+        self.core.clear_fonts();
+        self.refresh_texts();
+    }
+
+    #[inline]
+    ///Gets the named families which were requested by some text but aren't loaded, so a fallback was used instead
+    pub fn missing_families(&self) -> impl Iterator<Item = &SmolStr> {
+        self.core.missing_families()
+    }
+
+    ///Sets the content of every text again, so they're shaped with the fonts currently loaded, and lays them out
+    fn refresh_texts(&mut self) {
+        let texts = self.texts().clone();
+        for key in texts {
+            if let Some(element) = self.element_manager.get_element_mut(key) {
+                element.update(&mut self.core);
+                self.element_manager.mark_dirty(key).unwrap();
+            }
+        }
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+    }
+
+    ///Executes the given function with the element which has the given `key` casted to <T> and the rendering core, so it can be modified.
+    ///Returns None if the element doesn't exist or it's not a <T>.
+    pub fn with_element<T, R, F>(&mut self, key: HystElementKey, f: F) -> Option<R>
+    where
+        T: HystElement,
+        F: FnOnce(&mut T, &mut RenderingCore) -> R,
+    {
+        let element = self.element_manager.get_element_mut_with_type::<T>(key)??;
+        Some(f(element, &mut self.core))
     }
 
     pub fn core(&self) -> &RenderingCore {
@@ -73,51 +644,166 @@ impl HystUi {
     pub fn core_mut(&mut self) -> &mut RenderingCore {
         &mut self.core
     }
-    #[inline]
     pub fn resize_roots(&mut self, width: f32, height: f32) {
         self.element_manager
             .resize_roots(&mut self.core, width, height);
+        //Lists can only know which rows are visible, and how tall they are, once they're laid out. The same goes for the size
+        //of overlays, which is required for placing them next to their anchors
+        let mut failed = Vec::new();
+        for _ in 0..MAX_LAYOUT_PASSES {
+            let virtualized = self.virtualize_lists(&mut failed);
+            if !(self.place_overlays() || virtualized) {
+                break;
+            }
+            self.apply_updates();
+            self.element_manager
+                .resize_roots(&mut self.core, width, height);
+        }
     }
 
     fn prepare_texts(&mut self) {
-        let texts = self
-            .text_elements()
-            .iter()
-            .map(|text| {
-                let buffer = text.inner().buffer();
-                let x = text.inner().x();
-                let y = text.inner().y();
-                (buffer.clone(), Vec2f32::new(x, y), text.color().cloned())
-            })
+        let mut layers = (0..self.layer_count())
+            .map(|_| Vec::new())
             .collect::<Vec<_>>();
-        self.core.prepare_texts(texts);
+        for text in self.text_elements() {
+            let inner = text.inner();
+            //Glyphs can only be moved and scaled, so rotated and skewed texts are drawn upright inside of their transformed area
+            let world = self.drawn_world_of(text.id());
+            //Texts are also clipped to the area of their ancestors, such as scrolls. Texts which weren't laid out aren't part of the tree.
+            let Some(clip) = self
+                .drawn_clip_of(text.id())
+                .and_then(|clip| world.bounds_of(&inner.clip_rect()).intersection(clip))
+            else {
+                continue;
+            };
+            let Some(layer) = self
+                .layer_of(text.id())
+                .and_then(|layer| layers.get_mut(layer))
+            else {
+                continue;
+            };
+            let (color, opacity) = (text.color().cloned(), self.drawn_opacity_of(text.id()));
+            //Mirrored texts start on the corner of their lines which ends up on the top left, so they stay inside of their area
+            let position = match world.is_axis_aligned() {
+                true => *world
+                    .bounds_of(&Rect::from_xywh(
+                        inner.x(),
+                        inner.y(),
+                        inner.rect().size().x(),
+                        inner.content_height(),
+                    ))
+                    .position(),
+                false => world.apply(Vec2f32::new(inner.x(), inner.y())),
+            };
+            layer.push(PreparedText {
+                buffer: inner.display_buffer().clone(),
+                position,
+                scale: world.average_scale(),
+                bounds: clip,
+                color: Vec4f32::new(color.x(), color.y(), color.z(), color.w() * opacity),
+            });
+        }
+        for painter in self.painter_elements() {
+            let world = self.drawn_world_of(painter.id());
+            let (Some(clip), Some(layer)) = (
+                self.drawn_clip_of(painter.id())
+                    .zip(self.drawn_bounds_of(painter.id()))
+                    .and_then(|(clip, bounds)| bounds.intersection(clip)),
+                self.layer_of(painter.id())
+                    .and_then(|layer| layers.get_mut(layer)),
+            ) else {
+                continue;
+            };
+            let opacity = self.drawn_opacity_of(painter.id());
+            layer.extend(painter.texts().iter().map(|text| PreparedText {
+                buffer: text.buffer.clone(),
+                position: world.apply(painter.position() + text.position),
+                scale: world.average_scale(),
+                bounds: clip,
+                color: Vec4f32::new(
+                    text.color.x(),
+                    text.color.y(),
+                    text.color.z(),
+                    text.color.w() * opacity,
+                ),
+            }));
+        }
+        self.core.prepare_texts(layers);
     }
 
     pub fn draw(&mut self) {
-        let mut children = Vec::new();
         self.prepare_texts();
-        for root in self.roots_keys().iter() {
-            let Some(parent) = self.elements().get(*root) else {
-                continue;
-            };
-            children.push(parent);
-            children.append(&mut self.get_children_of(*root));
-        }
-        self.core.draw(&children, self.bg);
+        let elements = &self.element_manager;
+        self.drawn_effects = self.frame_effects.cloned();
+        self.core
+            .draw(self.bg, &self.drawn_effects, |frame| elements.render(frame));
     }
 
     ///Checks if there are some pending element keys that require updating, if so, updates the elements that require.
     /// # Returns
     /// * Wheather some element was updated and a draw request is required
     pub fn check_for_updates(&mut self) -> bool {
-        let mut flag = false;
-        while let Ok(key) = self.rx.try_recv() {
+        let now = Instant::now();
+        //Long pauses between checks shouldn't make gliding scrolls jump
+        let dt = now
+            .duration_since(self.last_tick)
+            .as_secs_f32()
+            .min(1.0 / 30.0);
+        self.last_tick = now;
+        let mut gliding = false;
+        for key in self.element_manager.scrolls().clone() {
+            if let Some(scroll) = self.element_manager.scroll_mut(key) {
+                gliding |= scroll.tick(dt);
+            }
+        }
+        let (shown, waiting) = self.tick_tooltips();
+        //Draws are requested while images load, so they're shown as soon as they're decoded
+        let loading = self.element_manager.poll_images(&mut self.core);
+        let animating = self
+            .element_manager
+            .tick_animations(&mut self.core, Duration::from_secs_f32(dt));
+        let (updated, recomposed) = self.apply_updates();
+        let flag = updated || gliding || recomposed;
+        let (width, height) = self.core.size();
+        if updated || gliding {
+            self.resize_roots(width as f32, height as f32);
+        } else if recomposed {
+            //Transforms, opacities and effects don't change the layouts, so only the subtrees they apply to are written again
+            self.element_manager
+                .recompose(&mut self.core, width as f32, height as f32);
+        }
+        //Requesting draws while a tooltip waits for it's delay keeps the checks going until it's shown
+        let effects_changed = *self.frame_effects.read() != self.drawn_effects;
+        flag || shown || waiting || effects_changed || loading || animating
+    }
+
+    ///Updates the elements whose pulses changed, marking their layouts as dirty.
+    /// # Returns
+    /// * Whether some element was updated
+    /// * Whether some transform, opacity or effects changed
+    fn apply_updates(&mut self) -> (bool, bool) {
+        let mut updated = Vec::new();
+        let mut recomposed = false;
+        while let Ok(update) = self.rx.try_recv() {
+            let key = match update {
+                PulseUpdate::Content(key) => key,
+                PulseUpdate::Visual(_) => {
+                    recomposed = true;
+                    continue;
+                }
+            };
             if let Some(element) = self.element_manager.get_element_mut(key) {
-                flag = true;
                 element.update(&mut self.core);
+                self.element_manager.mark_dirty(key).unwrap();
+                if !updated.contains(&key) {
+                    updated.push(key);
+                }
             }
         }
-        flag
+        for key in updated.iter() {
+            self.with_widget(*key, |widget, ui| widget.refresh(ui));
+        }
+        (!updated.is_empty(), recomposed)
     }
 }
 
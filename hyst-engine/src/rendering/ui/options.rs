@@ -1,9 +1,16 @@
-use hyst_math::vectors::{Vec2f32, Vec4f32};
+use std::time::Duration;
+
+use hyst_math::vectors::{Rgba, Vec2f32, Vec4f32};
 use smol_str::SmolStr;
 
-use crate::background::Background;
+use crate::{
+    background::Background,
+    elements::{CanvasShape, PaintCallback, ScrollAxis, TextContent},
+    meshes::image::{AnimationSource, ImageDisplay, ImageSource},
+    text::TextStyle,
+};
 
-use super::pulse::Pulse;
+use super::{Placement, pulse::Pulse};
 
 //File containing the options the user will need to pass when creating elements.
 //Other options are internals.
@@ -12,17 +19,121 @@ use super::pulse::Pulse;
 pub struct HystBoxOptions {
     pub bg: Background,
     pub style: SmolStr,
+    ///Radius of the corners of the box. When it's overflow isn't visible, it's children are clipped to the rounded corners too
+    pub radius: f32,
 }
 
 pub struct HystImageOptions {
-    pub source: String,
+    ///Where the pixels come from. Encoded sources are decoded on a background thread
+    pub source: ImageSource,
+    pub style: SmolStr,
+    ///Color filling the rect while the source is decoded, or if it can't be
+    pub placeholder: Rgba,
+    ///How the image is fitted, sampled and tiled inside of it's rect
+    pub display: ImageDisplay,
+    ///Biggest width or height the source is uploaded with, scaled down on the cpu when it's bigger. None only limits it to the
+    ///max texture size of the device
+    pub max_size: Option<u32>,
+}
+
+pub struct HystAnimationOptions {
+    ///Where the frames come from. They're decoded on a background thread
+    pub source: AnimationSource,
     pub style: SmolStr,
+    ///Color filling the rect while the frames are decoded, or if they can't be
+    pub placeholder: Rgba,
+    ///How the frames are fitted, sampled and tiled inside of the rect. It's source rect is replaced by the one of each frame
+    pub display: ImageDisplay,
+    ///Biggest width or height the frames are uploaded with, all of them packed together
+    pub max_size: Option<u32>,
+    ///Whether the frames advance. It's set to false when an animation which doesn't loop ends
+    pub playing: Pulse<bool>,
+    ///Whether the animation starts again after it's last frame
+    pub looping: Pulse<bool>,
+}
+
+pub struct HystCanvasOptions {
+    pub style: SmolStr,
+    ///Shapes drawn on the canvas, in order, with positions relative to it's top left corner. They're tessellated again when it changes
+    pub shapes: Pulse<Vec<CanvasShape>>,
+}
+
+pub struct HystPainterOptions {
+    pub style: SmolStr,
+    ///Draws the content of the painter. It's called when the painter is laid out with a new size, or asked to repaint
+    pub paint: PaintCallback,
 }
 
 pub struct HystTextOptions {
-    pub content: Pulse<String>,
-    pub position: Vec2f32,
+    pub content: TextContent,
+    pub style: SmolStr,
+    pub font_size: f32,
+    pub color: Pulse<Vec4f32>,
+    pub text_style: TextStyle,
+}
+
+pub struct HystListOptions {
     pub style: SmolStr,
+    ///Amount of items on the list
+    pub count: Pulse<usize>,
+    ///Amount of pixels the content is scrolled by
+    pub offset: Pulse<Vec2f32>,
+    ///Height assumed for the items which were never visible, so they can't be measured
+    pub estimated_height: f32,
+    ///Amount of rows created before and after the visible ones
+    pub overscan: usize,
+    ///Whether the content keeps gliding after the scroll gesture ends
+    pub inertia: bool,
+    ///Color of the scrollbar. If None, no scrollbar is drawn
+    pub scrollbar: Option<Rgba>,
+}
+
+pub struct HystScrollOptions {
+    pub style: SmolStr,
+    pub axis: ScrollAxis,
+    ///Amount of pixels the content is scrolled by
+    pub offset: Pulse<Vec2f32>,
+    ///Whether the content keeps gliding after the scroll gesture ends
+    pub inertia: bool,
+    ///Color of the scrollbars. If None, no scrollbar is drawn
+    pub scrollbar: Option<Rgba>,
+}
+
+pub struct HystModalOptions {
+    ///Style of the dialog, which is centered on the screen
+    pub style: SmolStr,
+    pub bg: Background,
+    pub radius: f32,
+    ///Fill of the area around the dialog, which covers every element below the modal
+    pub backdrop: Background,
+    ///Whether pressing on the backdrop, or pressing escape, hides the modal
+    pub dismissible: bool,
+}
+
+pub struct HystPopoverOptions {
+    ///Style of the popover. It's position is always defined by the ui
+    pub style: SmolStr,
+    pub bg: Background,
+    pub radius: f32,
+    pub placement: Placement,
+    ///Distance between the popover and it's anchor, in pixels
+    pub gap: f32,
+    ///Whether pressing outside of the popover and of it's anchor, or pressing escape, hides the popover
+    pub dismissible: bool,
+}
+
+pub struct HystTooltipOptions {
+    ///Style of the box of the tooltip. It's position is always defined by the ui
+    pub style: SmolStr,
+    pub bg: Background,
+    pub radius: f32,
+    pub content: TextContent,
     pub font_size: f32,
     pub color: Pulse<Vec4f32>,
+    pub text_style: TextStyle,
+    pub placement: Placement,
+    ///Distance between the tooltip and it's anchor, in pixels
+    pub gap: f32,
+    ///Time the cursor must rest above the anchor before the tooltip is shown
+    pub delay: Duration,
 }
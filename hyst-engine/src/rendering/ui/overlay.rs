@@ -0,0 +1,433 @@
+use std::time::{Duration, Instant};
+
+use hyst_math::{Rect, vectors::Vec2f32};
+use smol_str::SmolStr;
+use taffy::{AlignItems, Dimension, JustifyContent, LengthPercentageAuto, Position, Size, Style};
+
+use crate::{
+    background::Background,
+    error::LayoutError,
+    widgets::{ensure_style, modify_style},
+};
+
+use super::{
+    HystBoxOptions, HystElementKey, HystModalOptions, HystPopoverOptions, HystTextOptions,
+    HystTooltipOptions, HystUi,
+};
+
+///Name of the style of the backdrops of modals, which cover the whole screen and center their dialog
+const BACKDROP_STYLE: &str = "hyst-modal-backdrop";
+///Name of the style of the texts of tooltips
+const TOOLTIP_TEXT_STYLE: &str = "hyst-tooltip-text";
+
+///Side of it's anchor a popover or tooltip is placed on. When it doesn't fit on the screen on that side, it's flipped to the opposite one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Placement {
+    #[default]
+    Below,
+    Above,
+    Left,
+    Right,
+}
+
+impl Placement {
+    fn opposite(&self) -> Self {
+        match self {
+            Self::Below => Self::Above,
+            Self::Above => Self::Below,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    ///Gets the position of an overlay with the given `size` on this side of the `anchor`, `gap` pixels away from it
+    fn position(&self, anchor: &Rect, size: Vec2f32, gap: f32) -> Vec2f32 {
+        let (position, anchor) = (*anchor.position(), *anchor.size());
+        match self {
+            Self::Below => Vec2f32::new(position.x(), position.y() + anchor.y() + gap),
+            Self::Above => Vec2f32::new(position.x(), position.y() - size.y() - gap),
+            Self::Right => Vec2f32::new(position.x() + anchor.x() + gap, position.y()),
+            Self::Left => Vec2f32::new(position.x() - size.x() - gap, position.y()),
+        }
+    }
+
+    ///Whether an overlay at the given `position` with the given `size` fits on the `screen` on the axis of this placement
+    fn fits(&self, position: Vec2f32, size: Vec2f32, screen: Vec2f32) -> bool {
+        match self {
+            Self::Below | Self::Above => {
+                position.y() >= 0.0 && position.y() + size.y() <= screen.y()
+            }
+            Self::Left | Self::Right => {
+                position.x() >= 0.0 && position.x() + size.x() <= screen.x()
+            }
+        }
+    }
+
+    ///Gets the position of an overlay with the given `size` placed next to the `anchor`, flipped to the opposite side when only it fits,
+    ///and moved along the edges of the `screen` so it's not cut.
+    pub fn place(&self, anchor: &Rect, size: Vec2f32, gap: f32, screen: Vec2f32) -> Vec2f32 {
+        let mut position = self.position(anchor, size, gap);
+        if !self.fits(position, size, screen) {
+            let opposite = self.opposite();
+            let flipped = opposite.position(anchor, size, gap);
+            if opposite.fits(flipped, size, screen) {
+                position = flipped;
+            }
+        }
+        let max = (screen - size).max(&Vec2f32::default());
+        position.max(&Vec2f32::default()).min(&max)
+    }
+}
+
+///Element an overlay is positioned next to
+pub(crate) struct Anchor {
+    key: HystElementKey,
+    placement: Placement,
+    gap: f32,
+    ///Position the overlay was placed at on the last layout
+    position: Option<Vec2f32>,
+}
+
+pub(crate) enum OverlayKind {
+    ///Blocks the input of every layer below it
+    Modal,
+    Popover(Anchor),
+    ///Shown when the cursor rests above it's anchor for the given delay
+    Tooltip(Anchor, Duration),
+}
+
+///An element drawn above every other while it's shown
+pub(crate) struct Overlay {
+    ///Key of the root of the overlay: the backdrop for modals, the content itself otherwise
+    root: HystElementKey,
+    kind: OverlayKind,
+    ///Whether pressing outside of the content, or pressing escape, hides the overlay
+    dismissible: bool,
+}
+
+impl HystUi {
+    ///Creates a hidden modal dialog, centered on a backdrop which covers the whole screen and blocks the input of everything below it.
+    ///Children created in the returned dialog are shown with it, see `show_overlay`.
+    pub fn create_modal(
+        &mut self,
+        options: HystModalOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        ensure_style(
+            self,
+            BACKDROP_STYLE,
+            Style {
+                position: Position::Absolute,
+                size: Size {
+                    width: Dimension::percent(1.0),
+                    height: Dimension::percent(1.0),
+                },
+                align_items: Some(AlignItems::Center),
+                justify_content: Some(JustifyContent::Center),
+                ..Default::default()
+            },
+        );
+        let backdrop = self.create_box(HystBoxOptions {
+            bg: options.backdrop,
+            style: BACKDROP_STYLE.into(),
+            radius: 0.0,
+        })?;
+        let dialog = self.create_box_in(
+            backdrop,
+            HystBoxOptions {
+                bg: options.bg,
+                style: options.style,
+                radius: options.radius,
+            },
+        )?;
+        self.element_manager.hide_overlay(backdrop);
+        self.overlays.insert(
+            dialog,
+            Overlay {
+                root: backdrop,
+                kind: OverlayKind::Modal,
+                dismissible: options.dismissible,
+            },
+        );
+        Ok(dialog)
+    }
+
+    ///Creates a hidden popover placed next to the element with the given `anchor` key, such as the menu of a dropdown.
+    ///Children created in the returned popover are shown with it, see `show_overlay`.
+    pub fn create_popover(
+        &mut self,
+        anchor: HystElementKey,
+        options: HystPopoverOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        let popover = self.create_anchored(options.bg, options.style, options.radius)?;
+        self.overlays.insert(
+            popover,
+            Overlay {
+                root: popover,
+                kind: OverlayKind::Popover(Anchor {
+                    key: anchor,
+                    placement: options.placement,
+                    gap: options.gap,
+                    position: None,
+                }),
+                dismissible: options.dismissible,
+            },
+        );
+        Ok(popover)
+    }
+
+    ///Creates a tooltip shown when the cursor rests above the element with the given `anchor` key, and hidden when it leaves it.
+    pub fn create_tooltip(
+        &mut self,
+        anchor: HystElementKey,
+        options: HystTooltipOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        ensure_style(self, TOOLTIP_TEXT_STYLE, Style::default());
+        let tooltip = self.create_anchored(options.bg, options.style, options.radius)?;
+        self.create_text_in(
+            tooltip,
+            HystTextOptions {
+                content: options.content,
+                style: TOOLTIP_TEXT_STYLE.into(),
+                font_size: options.font_size,
+                color: options.color,
+                text_style: options.text_style,
+            },
+        )?;
+        self.overlays.insert(
+            tooltip,
+            Overlay {
+                root: tooltip,
+                kind: OverlayKind::Tooltip(
+                    Anchor {
+                        key: anchor,
+                        placement: options.placement,
+                        gap: options.gap,
+                        position: None,
+                    },
+                    options.delay,
+                ),
+                dismissible: true,
+            },
+        );
+        Ok(tooltip)
+    }
+
+    ///Creates a hidden root which is positioned by the ui, next to it's anchor
+    fn create_anchored(
+        &mut self,
+        bg: Background,
+        style: SmolStr,
+        radius: f32,
+    ) -> Result<HystElementKey, LayoutError> {
+        let key = self.create_box(HystBoxOptions { bg, style, radius })?;
+        modify_style(self, key, |style| style.position = Position::Absolute)?;
+        self.element_manager.hide_overlay(key);
+        Ok(key)
+    }
+
+    ///Shows the modal, popover or tooltip with the given `key` above every other element. If it's already shown, it's moved to the top.
+    /// # Returns
+    /// * Whether there's an overlay with the given key
+    pub fn show_overlay(&mut self, key: HystElementKey) -> bool {
+        let Some(overlay) = self.overlays.get(key) else {
+            return false;
+        };
+        self.element_manager.show_overlay(overlay.root);
+        let (width, height) = self.core.size();
+        self.resize_roots(width as f32, height as f32);
+        true
+    }
+
+    ///Hides the modal, popover or tooltip with the given `key`. It's elements are kept, so it can be shown again.
+    /// # Returns
+    /// * Whether the overlay was shown
+    pub fn hide_overlay(&mut self, key: HystElementKey) -> bool {
+        if !self.is_overlay_shown(key) {
+            return false;
+        }
+        let root = self.overlays[key].root;
+        self.element_manager.hide_overlay(root);
+        true
+    }
+
+    ///Whether the modal, popover or tooltip with the given `key` is shown
+    pub fn is_overlay_shown(&self, key: HystElementKey) -> bool {
+        self.overlays
+            .get(key)
+            .is_some_and(|overlay| self.element_manager.overlays().contains(&overlay.root))
+    }
+
+    ///Gets the shown overlays, the topmost first
+    fn shown_overlays(&self) -> Vec<HystElementKey> {
+        let roots = self.element_manager.overlays();
+        let mut shown = self
+            .overlays
+            .iter()
+            .filter_map(|(key, overlay)| {
+                Some((key, roots.iter().position(|root| *root == overlay.root)?))
+            })
+            .collect::<Vec<_>>();
+        shown.sort_by_key(|(_, idx)| std::cmp::Reverse(*idx));
+        shown.into_iter().map(|(key, _)| key).collect()
+    }
+
+    ///Gets the first position on the draw order which receives input, which is the one of the topmost modal being shown
+    fn input_floor(&self) -> usize {
+        self.element_manager
+            .overlays()
+            .iter()
+            .rev()
+            .find(|root| {
+                self.overlays.values().any(|overlay| {
+                    overlay.root == **root && matches!(overlay.kind, OverlayKind::Modal)
+                })
+            })
+            .and_then(|root| self.element_manager.order_of(*root))
+            .unwrap_or(0)
+    }
+
+    ///Whether the element with the given `key` receives input, which it doesn't when it's below a modal
+    pub(crate) fn accepts_input(&self, key: HystElementKey) -> bool {
+        self.element_manager
+            .order_of(key)
+            .is_some_and(|order| order >= self.input_floor())
+    }
+
+    ///Places the shown popovers and tooltips next to their anchors, based on the sizes they were laid out with.
+    /// # Returns
+    /// * Whether some overlay moved, so the layouts must be recalculated
+    pub(crate) fn place_overlays(&mut self) -> bool {
+        let (width, height) = self.core.size();
+        let screen = Vec2f32::new(width as f32, height as f32);
+        let mut moved = false;
+        for key in self.shown_overlays() {
+            let overlay = &self.overlays[key];
+            let (OverlayKind::Popover(anchor) | OverlayKind::Tooltip(anchor, _)) = &overlay.kind
+            else {
+                continue;
+            };
+            let Some(rect) = self.element_manager.bounds_of(anchor.key) else {
+                continue;
+            };
+            let size = self
+                .element_manager
+                .rect_of(overlay.root)
+                .map_or(Vec2f32::default(), |rect| *rect.size());
+            let position = anchor.placement.place(&rect, size, anchor.gap, screen);
+            let unchanged = anchor.position.is_some_and(|current| {
+                (current.x() - position.x()).abs() < 0.5 && (current.y() - position.y()).abs() < 0.5
+            });
+            if unchanged {
+                continue;
+            }
+            let root = overlay.root;
+            if let Some(OverlayKind::Popover(anchor) | OverlayKind::Tooltip(anchor, _)) =
+                self.overlays.get_mut(key).map(|overlay| &mut overlay.kind)
+            {
+                anchor.position = Some(position);
+            }
+            modify_style(self, root, |style| {
+                style.inset.left = LengthPercentageAuto::length(position.x());
+                style.inset.top = LengthPercentageAuto::length(position.y());
+            })
+            .unwrap();
+            moved = true;
+        }
+        moved
+    }
+
+    ///Hides the dismissible overlays the primary button was pressed outside of, from the topmost to the first modal.
+    /// # Returns
+    /// * Whether some overlay was hidden, and whether the press was consumed by the backdrop of a modal
+    pub(crate) fn dismiss_at(&mut self, position: Vec2f32) -> (bool, bool) {
+        let mut hidden = false;
+        for key in self.shown_overlays() {
+            let overlay = &self.overlays[key];
+            let inside = |key: HystElementKey| {
+                self.element_manager
+                    .contains(key, position.x(), position.y())
+            };
+            let (dismiss, modal) = match overlay.kind {
+                //Pressing anywhere hides the tooltips until their anchor is hovered again
+                OverlayKind::Tooltip(..) => {
+                    self.hovered_tooltip = self.hovered_tooltip.map(|(key, _)| (key, None));
+                    (true, false)
+                }
+                OverlayKind::Popover(ref anchor) => (
+                    overlay.dismissible && !inside(key) && !inside(anchor.key),
+                    false,
+                ),
+                OverlayKind::Modal => (overlay.dismissible && !inside(key), true),
+            };
+            if dismiss {
+                hidden |= self.hide_overlay(key);
+            }
+            if modal {
+                return (hidden, dismiss);
+            }
+        }
+        (hidden, false)
+    }
+
+    ///Hides the topmost dismissible modal or popover, such as when escape is pressed.
+    /// # Returns
+    /// * Whether some overlay was hidden
+    pub(crate) fn dismiss_topmost(&mut self) -> bool {
+        let topmost = self.shown_overlays().into_iter().find(|key| {
+            let overlay = &self.overlays[*key];
+            overlay.dismissible && !matches!(overlay.kind, OverlayKind::Tooltip(..))
+        });
+        topmost.is_some_and(|key| self.hide_overlay(key))
+    }
+
+    ///Starts the delay of the tooltip of the topmost anchor under the given `position`, hiding the tooltip of the previous one.
+    /// # Returns
+    /// * Whether some tooltip was hidden
+    pub(crate) fn hover_tooltips(&mut self, position: Vec2f32) -> bool {
+        let anchors = self
+            .overlays
+            .values()
+            .filter_map(|overlay| match overlay.kind {
+                OverlayKind::Tooltip(ref anchor, _) => Some(anchor.key),
+                _ => None,
+            })
+            .filter(|anchor| self.accepts_input(*anchor))
+            .collect::<Vec<_>>();
+        let anchor = self
+            .element_manager
+            .topmost_at(anchors, position.x(), position.y());
+        let tooltip = anchor.and_then(|anchor| {
+            self.overlays
+                .iter()
+                .find_map(|(key, overlay)| match overlay.kind {
+                    OverlayKind::Tooltip(ref tooltip, _) if tooltip.key == anchor => Some(key),
+                    _ => None,
+                })
+        });
+        let previous = self.hovered_tooltip.map(|(key, _)| key);
+        if tooltip == previous {
+            return false;
+        }
+        self.hovered_tooltip = tooltip.map(|key| (key, Some(Instant::now())));
+        previous.is_some_and(|key| self.hide_overlay(key))
+    }
+
+    ///Shows the tooltip whose anchor is hovered once it's delay passes.
+    /// # Returns
+    /// * Whether the tooltip was shown, and whether it's still waiting for it's delay
+    pub(crate) fn tick_tooltips(&mut self) -> (bool, bool) {
+        let Some((key, Some(since))) = self.hovered_tooltip else {
+            return (false, false);
+        };
+        let Some(OverlayKind::Tooltip(_, delay)) = self.overlays.get(key).map(|o| &o.kind) else {
+            return (false, false);
+        };
+        if self.is_overlay_shown(key) {
+            return (false, false);
+        }
+        match since.elapsed() >= *delay {
+            true => (self.show_overlay(key), false),
+            false => (false, true),
+        }
+    }
+}
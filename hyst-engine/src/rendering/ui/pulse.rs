@@ -7,21 +7,34 @@ use wgpu::naga::FastHashSet;
 
 use super::HystElementKey;
 
+///Change a pulse tells the ui about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseUpdate {
+    ///The element with the key depends on the value, so it's updated and the ui is laid out again
+    Content(HystElementKey),
+    ///The value is the transform, opacity or effects of the element with the key. The ui isn't laid out again, only the subtree
+    ///of the element is moved or faded
+    Visual(HystElementKey),
+}
+
 #[derive(Clone)]
 ///A pulse is a thread shared value which is used to get track of changing states and tell ui to compute them.
 pub struct Pulse<T> {
     pulse: Arc<RwLock<T>>,
     ///The id of dependent elements. When this Pulse modifies, every element with the listed keys will be updated before drawing the next frame
     dep_ids: Arc<RwLock<FastHashSet<HystElementKey>>>,
-    sender: Sender<HystElementKey>,
+    ///The id of the elements this Pulse is the transform, opacity or effects of
+    visual_ids: Arc<RwLock<FastHashSet<HystElementKey>>>,
+    sender: Sender<PulseUpdate>,
 }
 
 impl<T> Pulse<T> {
     ///Creates a new pulse with the given `initial` value and Sender `tx` used to communicate when it changed
-    pub fn new(initial: T, tx: Sender<HystElementKey>) -> Self {
+    pub fn new(initial: T, tx: Sender<PulseUpdate>) -> Self {
         Self {
             pulse: Arc::new(RwLock::new(initial)),
             dep_ids: Arc::new(RwLock::new(FastHashSet::default())),
+            visual_ids: Arc::new(RwLock::new(FastHashSet::default())),
             sender: tx,
         }
     }
@@ -41,22 +54,36 @@ impl<T> Pulse<T> {
         self.dep_ids.write().unwrap().insert(dep);
     }
 
+    ///Makes this pulse the transform, opacity or effects of the element with the given key, so it's subtree is moved or faded
+    ///when modifying, without updating it nor laying it out again
+    pub(crate) fn add_visual_dependency(&mut self, dep: HystElementKey) {
+        self.visual_ids.write().unwrap().insert(dep);
+    }
+
     ///Tells the receiver to update every dependency this pulse has got. The dependenc
     pub fn tell_receiver(&self) {
         let guard = self.dep_ids.read().unwrap();
         for key in guard.iter() {
-            self.sender.send(*key).unwrap();
+            self.sender.send(PulseUpdate::Content(*key)).unwrap();
+        }
+        let guard = self.visual_ids.read().unwrap();
+        for key in guard.iter() {
+            self.sender.send(PulseUpdate::Visual(*key)).unwrap();
         }
     }
     #[inline]
     ///Retrieves the guard for the underlying data of this pulse
-    pub fn read(&self) -> RwLockReadGuard<T> {
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
         self.pulse.read().unwrap()
     }
     #[inline]
     ///Retrives the writeable guard for the underlying data of this pulse. When modifying something with this, it's not automatically sent that this Pulse has changed
     ///so it might be used carefully
-    pub unsafe fn write(&mut self) -> RwLockWriteGuard<T> {
+    ///
+    ///# Safety
+    ///The elements depending on this pulse aren't told about the change, so they keep showing the old value until it's
+    ///mutated or [Pulse::tell_receiver] is called
+    pub unsafe fn write(&mut self) -> RwLockWriteGuard<'_, T> {
         self.pulse.write().unwrap()
     }
 }
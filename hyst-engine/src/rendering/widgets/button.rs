@@ -0,0 +1,156 @@
+use hyst_math::vectors::{Rgba, Vec2f32};
+use smol_str::SmolStr;
+use taffy::{AlignItems, JustifyContent, Style};
+
+use crate::{
+    background::Background,
+    error::LayoutError,
+    text::TextStyle,
+    ui::{HystBoxOptions, HystElementKey, HystTextOptions, HystUi, pulse::Pulse},
+};
+
+use super::{Widget, create_box, create_text, ensure_style, modify_style, set_background};
+
+///Name of the style of the labels of widgets
+pub(crate) const LABEL_STYLE: &str = "hyst-widget-label";
+
+///Visuals of a button on each of it's states
+#[derive(Debug, Clone)]
+pub struct ButtonVisuals {
+    pub normal: Background,
+    pub hovered: Background,
+    pub pressed: Background,
+    pub disabled: Background,
+    pub text: Rgba,
+    pub disabled_text: Rgba,
+    pub font_size: f32,
+    pub radius: f32,
+}
+
+impl Default for ButtonVisuals {
+    fn default() -> Self {
+        Self {
+            normal: Background::Solid(Rgba::new(0.22, 0.24, 0.28, 1.0)),
+            hovered: Background::Solid(Rgba::new(0.28, 0.3, 0.35, 1.0)),
+            pressed: Background::Solid(Rgba::new(0.16, 0.18, 0.21, 1.0)),
+            disabled: Background::Solid(Rgba::new(0.16, 0.16, 0.16, 1.0)),
+            text: Rgba::new(1.0, 1.0, 1.0, 1.0),
+            disabled_text: Rgba::new(0.5, 0.5, 0.5, 1.0),
+            font_size: 16.0,
+            radius: 6.0,
+        }
+    }
+}
+
+pub struct ButtonOptions {
+    ///Style of the box of the button. It's content is always centered
+    pub style: SmolStr,
+    pub label: Pulse<String>,
+    ///While true, the button doesn't react to the pointer
+    pub disabled: Pulse<bool>,
+    pub visuals: ButtonVisuals,
+}
+
+///A box with a centered label which executes a function when clicked
+pub struct Button {
+    key: HystElementKey,
+    text_color: Pulse<Rgba>,
+    disabled: Pulse<bool>,
+    visuals: ButtonVisuals,
+    hovered: bool,
+    pressed: bool,
+    on_click: Box<dyn FnMut(&mut HystUi)>,
+}
+
+impl Button {
+    ///Creates a button as a child of the element with the given `parent` key, or as a root if it's None.
+    ///The given `on_click` function is executed when the button is pressed and released while the pointer is above it.
+    pub fn create<F>(
+        ui: &mut HystUi,
+        parent: Option<HystElementKey>,
+        options: ButtonOptions,
+        on_click: F,
+    ) -> Result<HystElementKey, LayoutError>
+    where
+        F: FnMut(&mut HystUi) + 'static,
+    {
+        ensure_style(ui, LABEL_STYLE, Style::default());
+        let key = create_box(
+            ui,
+            parent,
+            HystBoxOptions {
+                bg: options.visuals.normal.clone(),
+                style: options.style,
+                radius: options.visuals.radius,
+            },
+        )?;
+        modify_style(ui, key, |style| {
+            style.align_items = Some(AlignItems::Center);
+            style.justify_content = Some(JustifyContent::Center);
+        })?;
+        let text_color = ui.create_pulse(options.visuals.text);
+        create_text(
+            ui,
+            Some(key),
+            HystTextOptions {
+                content: options.label.into(),
+                style: LABEL_STYLE.into(),
+                font_size: options.visuals.font_size,
+                color: text_color.clone(),
+                text_style: TextStyle::default(),
+            },
+        )?;
+        let mut disabled = options.disabled;
+        disabled.add_dependency(key);
+        Ok(ui.add_widget(Self {
+            key,
+            text_color,
+            disabled,
+            visuals: options.visuals,
+            hovered: false,
+            pressed: false,
+            on_click: Box::new(on_click),
+        }))
+    }
+
+    fn is_disabled(&self) -> bool {
+        *self.disabled.read()
+    }
+}
+
+impl Widget for Button {
+    fn key(&self) -> HystElementKey {
+        self.key
+    }
+    fn on_hover(&mut self, ui: &mut HystUi, hovered: bool) {
+        self.hovered = hovered;
+        self.refresh(ui);
+    }
+    fn on_press(&mut self, ui: &mut HystUi, _: Vec2f32) {
+        self.pressed = !self.is_disabled();
+        self.refresh(ui);
+    }
+    fn on_release(&mut self, ui: &mut HystUi, _: Vec2f32, inside: bool) {
+        let clicked = self.pressed && inside && !self.is_disabled();
+        self.pressed = false;
+        self.refresh(ui);
+        if clicked {
+            (self.on_click)(ui);
+        }
+    }
+    fn refresh(&mut self, ui: &mut HystUi) {
+        let disabled = self.is_disabled();
+        let bg = match (disabled, self.pressed, self.hovered) {
+            (true, _, _) => &self.visuals.disabled,
+            (false, true, true) => &self.visuals.pressed,
+            (false, _, true) => &self.visuals.hovered,
+            _ => &self.visuals.normal,
+        };
+        set_background(ui, self.key, bg);
+        let color = match disabled {
+            true => self.visuals.disabled_text,
+            false => self.visuals.text,
+        };
+        self.text_color.mutate(|mut current| *current = color);
+    }
+}
@@ -0,0 +1,139 @@
+use hyst_math::vectors::{Rgba, Vec2f32};
+use smol_str::SmolStr;
+use taffy::{AlignItems, Dimension, JustifyContent, Size, Style};
+
+use crate::{
+    background::Background,
+    error::LayoutError,
+    ui::{HystBoxOptions, HystElementKey, HystUi, pulse::Pulse},
+};
+
+use super::{Widget, create_box, ensure_style, modify_style, set_background};
+
+///Name of the style of the marks of checkboxes
+const MARK_STYLE: &str = "hyst-checkbox-mark";
+
+#[derive(Debug, Clone)]
+pub struct CheckboxVisuals {
+    pub background: Background,
+    pub checked_background: Background,
+    pub hovered: Background,
+    ///Fill of the mark shown inside the box while checked
+    pub mark: Background,
+    pub radius: f32,
+}
+
+impl Default for CheckboxVisuals {
+    fn default() -> Self {
+        Self {
+            background: Background::Solid(Rgba::new(0.22, 0.24, 0.28, 1.0)),
+            checked_background: Background::Solid(Rgba::new(0.2, 0.4, 0.9, 1.0)),
+            hovered: Background::Solid(Rgba::new(0.28, 0.3, 0.35, 1.0)),
+            mark: Background::Solid(Rgba::new(1.0, 1.0, 1.0, 1.0)),
+            radius: 4.0,
+        }
+    }
+}
+
+pub struct CheckboxOptions {
+    ///Style of the box of the checkbox. The mark is centered inside it
+    pub style: SmolStr,
+    pub checked: Pulse<bool>,
+    pub visuals: CheckboxVisuals,
+}
+
+///A box which toggles it's bound pulse when clicked, showing a mark while it's true
+pub struct Checkbox {
+    key: HystElementKey,
+    mark: HystElementKey,
+    checked: Pulse<bool>,
+    visuals: CheckboxVisuals,
+    hovered: bool,
+    pressed: bool,
+}
+
+impl Checkbox {
+    ///Creates a checkbox as a child of the element with the given `parent` key, or as a root if it's None
+    pub fn create(
+        ui: &mut HystUi,
+        parent: Option<HystElementKey>,
+        options: CheckboxOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        ensure_style(
+            ui,
+            MARK_STYLE,
+            Style {
+                size: Size {
+                    width: Dimension::percent(0.6),
+                    height: Dimension::percent(0.6),
+                },
+                ..Default::default()
+            },
+        );
+        let key = create_box(
+            ui,
+            parent,
+            HystBoxOptions {
+                bg: options.visuals.background.clone(),
+                style: options.style,
+                radius: options.visuals.radius,
+            },
+        )?;
+        modify_style(ui, key, |style| {
+            style.align_items = Some(AlignItems::Center);
+            style.justify_content = Some(JustifyContent::Center);
+        })?;
+        let mark = create_box(
+            ui,
+            Some(key),
+            HystBoxOptions {
+                bg: Background::Transparent,
+                style: MARK_STYLE.into(),
+                radius: options.visuals.radius * 0.5,
+            },
+        )?;
+        let mut checked = options.checked;
+        checked.add_dependency(key);
+        Ok(ui.add_widget(Self {
+            key,
+            mark,
+            checked,
+            visuals: options.visuals,
+            hovered: false,
+            pressed: false,
+        }))
+    }
+}
+
+impl Widget for Checkbox {
+    fn key(&self) -> HystElementKey {
+        self.key
+    }
+    fn on_hover(&mut self, ui: &mut HystUi, hovered: bool) {
+        self.hovered = hovered;
+        self.refresh(ui);
+    }
+    fn on_press(&mut self, _: &mut HystUi, _: Vec2f32) {
+        self.pressed = true;
+    }
+    fn on_release(&mut self, _: &mut HystUi, _: Vec2f32, inside: bool) {
+        if std::mem::take(&mut self.pressed) && inside {
+            //Refreshed by the update of the pulse
+            self.checked.mutate(|mut checked| *checked = !*checked);
+        }
+    }
+    fn refresh(&mut self, ui: &mut HystUi) {
+        let checked = *self.checked.read();
+        let bg = match (checked, self.hovered) {
+            (true, _) => &self.visuals.checked_background,
+            (false, true) => &self.visuals.hovered,
+            (false, false) => &self.visuals.background,
+        };
+        set_background(ui, self.key, bg);
+        let mark = match checked {
+            true => &self.visuals.mark,
+            false => &Background::Transparent,
+        };
+        set_background(ui, self.mark, mark);
+    }
+}
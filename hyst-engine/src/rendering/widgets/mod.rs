@@ -0,0 +1,99 @@
+//Widgets are made of boxes and texts, and react to the pointer events handled by the ui. Their state is bound to pulses, so
+//modifying the pulses updates their visuals too.
+mod button;
+pub use button::*;
+
+mod checkbox;
+pub use checkbox::*;
+
+mod radio;
+pub use radio::*;
+
+mod toggle;
+pub use toggle::*;
+
+mod slider;
+pub use slider::*;
+
+use hyst_math::vectors::Vec2f32;
+use taffy::Style;
+
+use crate::{
+    background::Background,
+    elements::HystBox,
+    error::LayoutError,
+    ui::{HystBoxOptions, HystElementKey, HystTextOptions, HystUi},
+};
+
+pub trait Widget: 'static {
+    ///Key of the element which receives the pointer events of this widget
+    fn key(&self) -> HystElementKey;
+
+    ///Called when the pointer enters or leaves the element of this widget
+    fn on_hover(&mut self, _ui: &mut HystUi, _hovered: bool) {}
+
+    ///Called when the primary button is pressed above the element of this widget
+    fn on_press(&mut self, _ui: &mut HystUi, _position: Vec2f32) {}
+
+    ///Called when the pointer moves while the primary button pressed above this widget is still held
+    fn on_drag(&mut self, _ui: &mut HystUi, _position: Vec2f32) {}
+
+    ///Called when the primary button pressed above this widget is released.
+    /// # Arguments
+    /// * `inside` - Whether the pointer is still above the element of this widget
+    fn on_release(&mut self, _ui: &mut HystUi, _position: Vec2f32, _inside: bool) {}
+
+    ///Updates the visuals of this widget based on it's state. Called when the pulses this widget is bound to change.
+    fn refresh(&mut self, ui: &mut HystUi);
+}
+
+///Creates the style with the given `name` if it doesn't exist. Used for the parts of widgets which aren't styled by the user.
+pub(crate) fn ensure_style(ui: &mut HystUi, name: &str, style: Style) {
+    if ui.layout().named_style(name).is_none() {
+        ui.create_layout(name, style);
+    }
+}
+
+///Fills the box with the given `key` with the given `bg`
+pub(crate) fn set_background(ui: &mut HystUi, key: HystElementKey, bg: &Background) {
+    ui.with_element(key, |element: &mut HystBox, core| {
+        element.set_background(core, bg)
+    });
+}
+
+///Modifies the style of the layout of the element with the given `key`, so it's laid out with it on the next resize.
+pub(crate) fn modify_style<F>(ui: &mut HystUi, key: HystElementKey, f: F) -> Result<(), LayoutError>
+where
+    F: FnOnce(&mut Style),
+{
+    let Some(node) = ui.get_element(key).map(|element| element.layout()) else {
+        return Err(LayoutError::InvalidElement);
+    };
+    let mut style = ui.layout().style_of(node)?.clone();
+    f(&mut style);
+    ui.layout_mut().set_style(node, style)
+}
+
+///Creates a box as a child of the element with the given `parent` key, or as a root if it's None
+pub(crate) fn create_box(
+    ui: &mut HystUi,
+    parent: Option<HystElementKey>,
+    options: HystBoxOptions,
+) -> Result<HystElementKey, LayoutError> {
+    match parent {
+        Some(parent) => ui.create_box_in(parent, options),
+        None => ui.create_box(options),
+    }
+}
+
+///Creates a text as a child of the element with the given `parent` key, or as a root if it's None
+pub(crate) fn create_text(
+    ui: &mut HystUi,
+    parent: Option<HystElementKey>,
+    options: HystTextOptions,
+) -> Result<HystElementKey, LayoutError> {
+    match parent {
+        Some(parent) => ui.create_text_in(parent, options),
+        None => ui.create_text(options),
+    }
+}
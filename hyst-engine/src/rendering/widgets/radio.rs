@@ -0,0 +1,231 @@
+use hyst_math::vectors::{Rgba, Vec2f32};
+use smol_str::SmolStr;
+use taffy::{AlignItems, Dimension, JustifyContent, LengthPercentage, Size, Style};
+
+use crate::{
+    background::Background,
+    error::LayoutError,
+    text::TextStyle,
+    ui::{HystBoxOptions, HystElementKey, HystTextOptions, HystUi, pulse::Pulse},
+};
+
+use super::{
+    LABEL_STYLE, Widget, create_box, create_text, ensure_style, modify_style, set_background,
+};
+
+///Name of the style of the rows of each option
+const OPTION_STYLE: &str = "hyst-radio-option";
+///Name of the style of the circles of each option. It's size is set per radio group
+const CIRCLE_STYLE: &str = "hyst-radio-circle";
+///Name of the style of the dot shown inside the circle of the selected option
+const DOT_STYLE: &str = "hyst-radio-dot";
+
+#[derive(Debug, Clone)]
+pub struct RadioVisuals {
+    pub circle: Background,
+    pub selected_circle: Background,
+    ///Fill of the dot shown inside the circle of the selected option
+    pub dot: Background,
+    pub text: Rgba,
+    pub font_size: f32,
+    ///Diameter of the circles, in pixels
+    pub circle_size: f32,
+    ///Space between the circle and the label of each option, in pixels
+    pub gap: f32,
+}
+
+impl Default for RadioVisuals {
+    fn default() -> Self {
+        Self {
+            circle: Background::Solid(Rgba::new(0.22, 0.24, 0.28, 1.0)),
+            selected_circle: Background::Solid(Rgba::new(0.2, 0.4, 0.9, 1.0)),
+            dot: Background::Solid(Rgba::new(1.0, 1.0, 1.0, 1.0)),
+            text: Rgba::new(1.0, 1.0, 1.0, 1.0),
+            font_size: 16.0,
+            circle_size: 18.0,
+            gap: 8.0,
+        }
+    }
+}
+
+pub struct RadioGroupOptions {
+    ///Style of the box which contains the options, one row per option
+    pub style: SmolStr,
+    pub options: Vec<String>,
+    ///Index of the selected option
+    pub selected: Pulse<usize>,
+    pub visuals: RadioVisuals,
+}
+
+///An option of a radio group
+struct RadioOption {
+    row: HystElementKey,
+    circle: HystElementKey,
+    dot: HystElementKey,
+}
+
+///A list of options of which only one is selected, whose index is bound to a pulse
+pub struct RadioGroup {
+    key: HystElementKey,
+    options: Vec<RadioOption>,
+    selected: Pulse<usize>,
+    visuals: RadioVisuals,
+    ///Index of the option the primary button was pressed above
+    pressed: Option<usize>,
+}
+
+impl RadioGroup {
+    ///Creates a radio group as a child of the element with the given `parent` key, or as a root if it's None
+    pub fn create(
+        ui: &mut HystUi,
+        parent: Option<HystElementKey>,
+        options: RadioGroupOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        ensure_style(ui, LABEL_STYLE, Style::default());
+        ensure_style(
+            ui,
+            OPTION_STYLE,
+            Style {
+                align_items: Some(AlignItems::Center),
+                ..Default::default()
+            },
+        );
+        ensure_style(
+            ui,
+            CIRCLE_STYLE,
+            Style {
+                align_items: Some(AlignItems::Center),
+                justify_content: Some(JustifyContent::Center),
+                flex_shrink: 0.0,
+                ..Default::default()
+            },
+        );
+        ensure_style(
+            ui,
+            DOT_STYLE,
+            Style {
+                size: Size {
+                    width: Dimension::percent(0.5),
+                    height: Dimension::percent(0.5),
+                },
+                ..Default::default()
+            },
+        );
+        let visuals = options.visuals;
+        let key = create_box(
+            ui,
+            parent,
+            HystBoxOptions {
+                bg: Background::Transparent,
+                style: options.style,
+                radius: 0.0,
+            },
+        )?;
+        modify_style(ui, key, |style| {
+            style.flex_direction = taffy::FlexDirection::Column;
+        })?;
+        let text_color = ui.create_pulse(visuals.text);
+        let mut rows = Vec::with_capacity(options.options.len());
+        for label in options.options {
+            let row = create_box(
+                ui,
+                Some(key),
+                HystBoxOptions {
+                    bg: Background::Transparent,
+                    style: OPTION_STYLE.into(),
+                    radius: 0.0,
+                },
+            )?;
+            modify_style(ui, row, |style| {
+                style.gap = Size {
+                    width: LengthPercentage::length(visuals.gap),
+                    height: LengthPercentage::length(0.0),
+                };
+            })?;
+            let circle = create_box(
+                ui,
+                Some(row),
+                HystBoxOptions {
+                    bg: visuals.circle.clone(),
+                    style: CIRCLE_STYLE.into(),
+                    radius: visuals.circle_size * 0.5,
+                },
+            )?;
+            modify_style(ui, circle, |style| {
+                style.size = Size {
+                    width: Dimension::length(visuals.circle_size),
+                    height: Dimension::length(visuals.circle_size),
+                };
+            })?;
+            let dot = create_box(
+                ui,
+                Some(circle),
+                HystBoxOptions {
+                    bg: Background::Transparent,
+                    style: DOT_STYLE.into(),
+                    radius: visuals.circle_size * 0.25,
+                },
+            )?;
+            create_text(
+                ui,
+                Some(row),
+                HystTextOptions {
+                    content: ui.create_pulse(label).into(),
+                    style: LABEL_STYLE.into(),
+                    font_size: visuals.font_size,
+                    color: text_color.clone(),
+                    text_style: TextStyle::default(),
+                },
+            )?;
+            rows.push(RadioOption { row, circle, dot });
+        }
+        let mut selected = options.selected;
+        selected.add_dependency(key);
+        Ok(ui.add_widget(Self {
+            key,
+            options: rows,
+            selected,
+            visuals,
+            pressed: None,
+        }))
+    }
+
+    ///Gets the index of the option under the given `position`
+    fn option_at(&self, ui: &HystUi, position: Vec2f32) -> Option<usize> {
+        self.options
+            .iter()
+            .position(|option| ui.contains(option.row, position.x(), position.y()))
+    }
+}
+
+impl Widget for RadioGroup {
+    fn key(&self) -> HystElementKey {
+        self.key
+    }
+    fn on_press(&mut self, ui: &mut HystUi, position: Vec2f32) {
+        self.pressed = self.option_at(ui, position);
+    }
+    fn on_release(&mut self, ui: &mut HystUi, position: Vec2f32, inside: bool) {
+        let Some(pressed) = self.pressed.take() else {
+            return;
+        };
+        if inside
+            && self.option_at(ui, position) == Some(pressed)
+            && *self.selected.read() != pressed
+        {
+            //Refreshed by the update of the pulse
+            self.selected.mutate(|mut selected| *selected = pressed);
+        }
+    }
+    fn refresh(&mut self, ui: &mut HystUi) {
+        let selected = *self.selected.read();
+        for (idx, option) in self.options.iter().enumerate() {
+            let (circle, dot) = match idx == selected {
+                true => (&self.visuals.selected_circle, &self.visuals.dot),
+                false => (&self.visuals.circle, &Background::Transparent),
+            };
+            set_background(ui, option.circle, circle);
+            set_background(ui, option.dot, dot);
+        }
+    }
+}
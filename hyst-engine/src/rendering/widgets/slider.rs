@@ -0,0 +1,244 @@
+use hyst_math::vectors::{Rgba, Vec2f32};
+use smol_str::SmolStr;
+use taffy::{
+    AlignItems, Dimension, LengthPercentage, LengthPercentageAuto, Position, Rect, Size, Style,
+};
+
+use crate::{
+    background::Background,
+    error::LayoutError,
+    ui::{HystBoxOptions, HystElementKey, HystUi, pulse::Pulse},
+};
+
+use super::{Widget, create_box, ensure_style, modify_style, set_background};
+
+///Name of the style of the tracks of sliders. It's thickness is set per slider
+const TRACK_STYLE: &str = "hyst-slider-track";
+///Name of the style of the part of the track before the thumb
+const FILL_STYLE: &str = "hyst-slider-fill";
+///Name of the style of the thumbs of sliders. It's size and position are set per slider
+const THUMB_STYLE: &str = "hyst-slider-thumb";
+
+#[derive(Debug, Clone)]
+pub struct SliderVisuals {
+    pub track: Background,
+    ///Fill of the part of the track before the thumb
+    pub fill: Background,
+    pub thumb: Background,
+    pub hovered_thumb: Background,
+    ///Diameter of the thumb, in pixels
+    pub thumb_size: f32,
+    pub track_thickness: f32,
+    pub radius: f32,
+}
+
+impl Default for SliderVisuals {
+    fn default() -> Self {
+        Self {
+            track: Background::Solid(Rgba::new(0.22, 0.24, 0.28, 1.0)),
+            fill: Background::Solid(Rgba::new(0.2, 0.4, 0.9, 1.0)),
+            thumb: Background::Solid(Rgba::new(0.9, 0.9, 0.9, 1.0)),
+            hovered_thumb: Background::Solid(Rgba::new(1.0, 1.0, 1.0, 1.0)),
+            thumb_size: 16.0,
+            track_thickness: 4.0,
+            radius: 2.0,
+        }
+    }
+}
+
+pub struct SliderOptions {
+    ///Style of the box of the slider, which contains the track
+    pub style: SmolStr,
+    ///Value of the slider, always between `min` and `max`
+    pub value: Pulse<f32>,
+    pub min: f32,
+    pub max: f32,
+    ///If defined, the value snaps to multiples of it starting from `min`
+    pub step: Option<f32>,
+    pub visuals: SliderVisuals,
+}
+
+///A track with a thumb which can be dragged to pick a value between a minimum and a maximum
+pub struct Slider {
+    key: HystElementKey,
+    track: HystElementKey,
+    fill: HystElementKey,
+    thumb: HystElementKey,
+    value: Pulse<f32>,
+    min: f32,
+    max: f32,
+    step: Option<f32>,
+    visuals: SliderVisuals,
+    hovered: bool,
+}
+
+impl Slider {
+    ///Creates a slider as a child of the element with the given `parent` key, or as a root if it's None
+    pub fn create(
+        ui: &mut HystUi,
+        parent: Option<HystElementKey>,
+        options: SliderOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        ensure_style(
+            ui,
+            TRACK_STYLE,
+            Style {
+                flex_grow: 1.0,
+                ..Default::default()
+            },
+        );
+        ensure_style(
+            ui,
+            FILL_STYLE,
+            Style {
+                size: Size {
+                    width: Dimension::percent(0.0),
+                    height: Dimension::percent(1.0),
+                },
+                ..Default::default()
+            },
+        );
+        ensure_style(
+            ui,
+            THUMB_STYLE,
+            Style {
+                position: Position::Absolute,
+                ..Default::default()
+            },
+        );
+        let visuals = options.visuals;
+        let key = create_box(
+            ui,
+            parent,
+            HystBoxOptions {
+                bg: Background::Transparent,
+                style: options.style,
+                radius: 0.0,
+            },
+        )?;
+        //The thumb can't overflow the box of the slider on the ends of the track
+        modify_style(ui, key, |style| {
+            style.align_items = Some(AlignItems::Center);
+            style.padding = Rect {
+                left: LengthPercentage::length(visuals.thumb_size * 0.5),
+                right: LengthPercentage::length(visuals.thumb_size * 0.5),
+                ..style.padding
+            };
+        })?;
+        let track = create_box(
+            ui,
+            Some(key),
+            HystBoxOptions {
+                bg: visuals.track.clone(),
+                style: TRACK_STYLE.into(),
+                radius: visuals.radius,
+            },
+        )?;
+        modify_style(ui, track, |style| {
+            style.size.height = Dimension::length(visuals.track_thickness);
+        })?;
+        let fill = create_box(
+            ui,
+            Some(track),
+            HystBoxOptions {
+                bg: visuals.fill.clone(),
+                style: FILL_STYLE.into(),
+                radius: visuals.radius,
+            },
+        )?;
+        let thumb = create_box(
+            ui,
+            Some(track),
+            HystBoxOptions {
+                bg: visuals.thumb.clone(),
+                style: THUMB_STYLE.into(),
+                radius: visuals.thumb_size * 0.5,
+            },
+        )?;
+        modify_style(ui, thumb, |style| {
+            style.size = Size {
+                width: Dimension::length(visuals.thumb_size),
+                height: Dimension::length(visuals.thumb_size),
+            };
+            style.inset.top =
+                LengthPercentageAuto::length((visuals.track_thickness - visuals.thumb_size) * 0.5);
+            style.margin.left = LengthPercentageAuto::length(-visuals.thumb_size * 0.5);
+        })?;
+        let mut value = options.value;
+        value.add_dependency(key);
+        Ok(ui.add_widget(Self {
+            key,
+            track,
+            fill,
+            thumb,
+            value,
+            min: options.min,
+            max: options.max,
+            step: options.step,
+            visuals,
+            hovered: false,
+        }))
+    }
+
+    ///Position of the given `value` along the track, between 0 and 1
+    fn fraction_of(&self, value: f32) -> f32 {
+        let range = self.max - self.min;
+        if range <= 0.0 {
+            return 0.0;
+        }
+        ((value - self.min) / range).clamp(0.0, 1.0)
+    }
+
+    ///Sets the value to the one under the given `position` along the track
+    fn set_from_position(&mut self, ui: &HystUi, position: Vec2f32) {
+        let (Some(rect), Some(position)) =
+            (ui.rect_of(self.track), ui.to_local(self.track, position))
+        else {
+            return;
+        };
+        let (start, len) = (rect.position().x(), rect.size().x());
+        let fraction = match len > 0.0 {
+            true => ((position.x() - start) / len).clamp(0.0, 1.0),
+            false => 0.0,
+        };
+        let mut value = self.min + fraction * (self.max - self.min);
+        if let Some(step) = self.step.filter(|step| *step > 0.0) {
+            value = (self.min + ((value - self.min) / step).round() * step).min(self.max);
+        }
+        if value != *self.value.read() {
+            //Refreshed by the update of the pulse
+            self.value.mutate(|mut current| *current = value);
+        }
+    }
+}
+
+impl Widget for Slider {
+    fn key(&self) -> HystElementKey {
+        self.key
+    }
+    fn on_hover(&mut self, ui: &mut HystUi, hovered: bool) {
+        self.hovered = hovered;
+        self.refresh(ui);
+    }
+    fn on_press(&mut self, ui: &mut HystUi, position: Vec2f32) {
+        self.set_from_position(ui, position);
+    }
+    fn on_drag(&mut self, ui: &mut HystUi, position: Vec2f32) {
+        self.set_from_position(ui, position);
+    }
+    fn refresh(&mut self, ui: &mut HystUi) {
+        let fraction = self.fraction_of(*self.value.read());
+        let thumb = match self.hovered {
+            true => &self.visuals.hovered_thumb,
+            false => &self.visuals.thumb,
+        };
+        set_background(ui, self.thumb, thumb);
+        //The fill and the thumb are moved by the layout, which is recalculated after the refresh
+        let _ = modify_style(ui, self.fill, |style| {
+            style.size.width = Dimension::percent(fraction);
+        });
+        let _ = modify_style(ui, self.thumb, |style| {
+            style.inset.left = LengthPercentageAuto::percent(fraction);
+        });
+    }
+}
@@ -0,0 +1,139 @@
+use hyst_math::vectors::{Rgba, Vec2f32};
+use smol_str::SmolStr;
+use taffy::{AlignItems, Dimension, JustifyContent, Rect, Size, Style};
+
+use crate::{
+    background::Background,
+    error::LayoutError,
+    ui::{HystBoxOptions, HystElementKey, HystUi, pulse::Pulse},
+};
+
+use super::{Widget, create_box, ensure_style, modify_style, set_background};
+
+///Name of the style of the knobs of toggles. It's size is set per toggle
+const KNOB_STYLE: &str = "hyst-toggle-knob";
+
+#[derive(Debug, Clone)]
+pub struct ToggleVisuals {
+    pub off_track: Background,
+    pub on_track: Background,
+    pub knob: Background,
+    ///Diameter of the knob, in pixels
+    pub knob_size: f32,
+    ///Space between the knob and the edges of the track, in pixels
+    pub inset: f32,
+    pub radius: f32,
+}
+
+impl Default for ToggleVisuals {
+    fn default() -> Self {
+        Self {
+            off_track: Background::Solid(Rgba::new(0.22, 0.24, 0.28, 1.0)),
+            on_track: Background::Solid(Rgba::new(0.2, 0.4, 0.9, 1.0)),
+            knob: Background::Solid(Rgba::new(1.0, 1.0, 1.0, 1.0)),
+            knob_size: 16.0,
+            inset: 3.0,
+            radius: 11.0,
+        }
+    }
+}
+
+pub struct ToggleOptions {
+    ///Style of the track of the toggle, which defines it's size
+    pub style: SmolStr,
+    pub on: Pulse<bool>,
+    pub visuals: ToggleVisuals,
+}
+
+///A switch whose knob slides to the end of it's track while it's bound pulse is true. Clicking it toggles the pulse.
+pub struct Toggle {
+    key: HystElementKey,
+    on: Pulse<bool>,
+    visuals: ToggleVisuals,
+    pressed: bool,
+}
+
+impl Toggle {
+    ///Creates a toggle as a child of the element with the given `parent` key, or as a root if it's None
+    pub fn create(
+        ui: &mut HystUi,
+        parent: Option<HystElementKey>,
+        options: ToggleOptions,
+    ) -> Result<HystElementKey, LayoutError> {
+        ensure_style(
+            ui,
+            KNOB_STYLE,
+            Style {
+                flex_shrink: 0.0,
+                ..Default::default()
+            },
+        );
+        let visuals = options.visuals;
+        let key = create_box(
+            ui,
+            parent,
+            HystBoxOptions {
+                bg: visuals.off_track.clone(),
+                style: options.style,
+                radius: visuals.radius,
+            },
+        )?;
+        modify_style(ui, key, |style| {
+            style.align_items = Some(AlignItems::Center);
+            style.padding = Rect::length(visuals.inset);
+        })?;
+        let knob = create_box(
+            ui,
+            Some(key),
+            HystBoxOptions {
+                bg: visuals.knob.clone(),
+                style: KNOB_STYLE.into(),
+                radius: visuals.knob_size * 0.5,
+            },
+        )?;
+        modify_style(ui, knob, |style| {
+            style.size = Size {
+                width: Dimension::length(visuals.knob_size),
+                height: Dimension::length(visuals.knob_size),
+            };
+        })?;
+        let mut on = options.on;
+        on.add_dependency(key);
+        Ok(ui.add_widget(Self {
+            key,
+            on,
+            visuals,
+            pressed: false,
+        }))
+    }
+}
+
+impl Widget for Toggle {
+    fn key(&self) -> HystElementKey {
+        self.key
+    }
+    fn on_press(&mut self, _: &mut HystUi, _: Vec2f32) {
+        self.pressed = true;
+    }
+    fn on_release(&mut self, _: &mut HystUi, _: Vec2f32, inside: bool) {
+        if std::mem::take(&mut self.pressed) && inside {
+            //Refreshed by the update of the pulse
+            self.on.mutate(|mut on| *on = !*on);
+        }
+    }
+    fn refresh(&mut self, ui: &mut HystUi) {
+        let on = *self.on.read();
+        let track = match on {
+            true => &self.visuals.on_track,
+            false => &self.visuals.off_track,
+        };
+        set_background(ui, self.key, track);
+        //The knob is moved by the layout, which is recalculated after the refresh
+        let _ = modify_style(ui, self.key, |style| {
+            style.justify_content = Some(match on {
+                true => JustifyContent::FlexEnd,
+                false => JustifyContent::FlexStart,
+            });
+        });
+    }
+}
@@ -17,6 +17,15 @@ where
     handler: Option<H>,
 }
 
+impl<H> Default for HystWindow<H>
+where
+    H: HystHandler,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<H> HystWindow<H>
 where
     H: HystHandler,
@@ -0,0 +1,191 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{Rect, vectors::Vec2f32};
+
+///2D affine transform, mapping a point (x, y) to (a * x + c * y + tx, b * x + d * y + ty).
+///Laid out as the uniform the shaders receive, so it can be written on buffers directly.
+#[repr(C)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
+pub struct Affine {
+    linear: [f32; 4],
+    translation: [f32; 2],
+    _padding: [f32; 2],
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine {
+    pub const IDENTITY: Self = Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    pub const fn new(a: f32, b: f32, c: f32, d: f32, tx: f32, ty: f32) -> Self {
+        Self {
+            linear: [a, b, c, d],
+            translation: [tx, ty],
+            _padding: [0.0; 2],
+        }
+    }
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, x, y)
+    }
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self::new(x, 0.0, 0.0, y, 0.0, 0.0)
+    }
+    ///Rotates clockwise on the screen by the given `radians`, since the y axis points down
+    pub fn rotation(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+    ///Skews by the given angles, in radians, along each axis
+    pub fn skew(x: f32, y: f32) -> Self {
+        Self::new(1.0, y.tan(), x.tan(), 1.0, 0.0, 0.0)
+    }
+
+    ///Gets the transform which applies `other` and then this one
+    pub fn then(&self, other: &Self) -> Self {
+        let [a, b, c, d] = self.linear;
+        let [e, f, g, h] = other.linear;
+        let [tx, ty] = other.translation;
+        Self::new(
+            a * e + c * f,
+            b * e + d * f,
+            a * g + c * h,
+            b * g + d * h,
+            a * tx + c * ty + self.translation[0],
+            b * tx + d * ty + self.translation[1],
+        )
+    }
+
+    ///Applies this transform on the given `point`
+    pub fn apply(&self, point: Vec2f32) -> Vec2f32 {
+        let [a, b, c, d] = self.linear;
+        Vec2f32::new(
+            a * point.x() + c * point.y() + self.translation[0],
+            b * point.x() + d * point.y() + self.translation[1],
+        )
+    }
+
+    ///Gets the transform which undoes this one. None if it collapses the plane, such as when scaling by 0
+    pub fn inverse(&self) -> Option<Self> {
+        let [a, b, c, d] = self.linear;
+        let det = a * d - b * c;
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+        let [tx, ty] = self.translation;
+        Some(Self::new(
+            d / det,
+            -b / det,
+            -c / det,
+            a / det,
+            (c * ty - d * tx) / det,
+            (b * tx - a * ty) / det,
+        ))
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    ///Whether this transform keeps rectangles aligned to the axes, so it only translates and scales them
+    pub fn is_axis_aligned(&self) -> bool {
+        self.linear[1] == 0.0 && self.linear[2] == 0.0
+    }
+
+    ///Gets how much this transform scales areas on average along each axis
+    pub fn average_scale(&self) -> f32 {
+        let [a, b, c, d] = self.linear;
+        (a * d - b * c).abs().sqrt()
+    }
+
+    ///Gets the smallest rectangle aligned to the axes which contains the given `rect` once transformed
+    pub fn bounds_of(&self, rect: &Rect) -> Rect {
+        if self.is_identity() {
+            return *rect;
+        }
+        let (position, size) = (*rect.position(), *rect.size());
+        let corners = [
+            position,
+            position + Vec2f32::new(size.x(), 0.0),
+            position + Vec2f32::new(0.0, size.y()),
+            position + size,
+        ]
+        .map(|corner| self.apply(corner));
+        let (min, max) = corners[1..]
+            .iter()
+            .fold((corners[0], corners[0]), |(min, max), corner| {
+                (min.min(corner), max.max(corner))
+            });
+        Rect::new(min, max - min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(point: Vec2f32, x: f32, y: f32) {
+        assert!(
+            (point.x() - x).abs() < 1e-4 && (point.y() - y).abs() < 1e-4,
+            "{point:?} isn't ({x}, {y})"
+        );
+    }
+
+    #[test]
+    fn then_applies_the_other_transform_first() {
+        let point = Vec2f32::new(2.0, 3.0);
+        let translate = Affine::translation(10.0, 0.0);
+        let scale = Affine::scale(2.0, 2.0);
+        assert_near(translate.then(&scale).apply(point), 14.0, 6.0);
+        assert_near(scale.then(&translate).apply(point), 24.0, 6.0);
+        //Rotating clockwise by a quarter moves (2, 3) to (-3, 2), which is then translated
+        let rotate = Affine::rotation(std::f32::consts::FRAC_PI_2);
+        assert_near(translate.then(&rotate).apply(point), 7.0, 2.0);
+        assert_near(
+            translate.then(&rotate).apply(point),
+            translate.apply(rotate.apply(point)).x(),
+            translate.apply(rotate.apply(point)).y(),
+        );
+        assert_eq!(Affine::IDENTITY.then(&scale), scale);
+        assert_eq!(scale.then(&Affine::IDENTITY), scale);
+    }
+
+    #[test]
+    fn inverse_undoes_the_transform() {
+        let transform = Affine::translation(5.0, -3.0)
+            .then(&Affine::rotation(0.7))
+            .then(&Affine::skew(0.2, 0.0))
+            .then(&Affine::scale(2.0, 0.5));
+        let inverse = transform.inverse().unwrap();
+        let point = Vec2f32::new(4.0, -9.0);
+        let back = inverse.apply(transform.apply(point));
+        assert_near(back, point.x(), point.y());
+        let identity = transform.then(&inverse);
+        assert_near(identity.apply(point), point.x(), point.y());
+        assert_eq!(Affine::IDENTITY.inverse(), Some(Affine::IDENTITY));
+    }
+
+    #[test]
+    fn collapsing_transforms_have_no_inverse() {
+        assert_eq!(Affine::scale(0.0, 1.0).inverse(), None);
+        assert_eq!(Affine::new(1.0, 2.0, 2.0, 4.0, 3.0, 3.0).inverse(), None);
+    }
+
+    #[test]
+    fn bounds_contain_the_transformed_corners() {
+        let rect = Rect::from_xywh(0.0, 0.0, 10.0, 20.0);
+        let bounds = Affine::translation(5.0, 5.0)
+            .then(&Affine::scale(2.0, 1.0))
+            .bounds_of(&rect);
+        assert_eq!(bounds, Rect::from_xywh(5.0, 5.0, 20.0, 20.0));
+        let rotated = Affine::rotation(std::f32::consts::FRAC_PI_2).bounds_of(&rect);
+        assert_near(*rotated.position(), -20.0, 0.0);
+        assert_near(*rotated.size(), 20.0, 10.0);
+        assert!(!Affine::rotation(0.3).is_axis_aligned());
+        assert!(Affine::scale(2.0, 3.0).is_axis_aligned());
+        assert!((Affine::scale(2.0, 8.0).average_scale() - 4.0).abs() < 1e-6);
+    }
+}
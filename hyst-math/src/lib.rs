@@ -1,3 +1,5 @@
+mod affine;
 pub mod vectors;
+pub use affine::*;
 mod rectangle;
-pub use rectangle::*; 
\ No newline at end of file
+pub use rectangle::*;
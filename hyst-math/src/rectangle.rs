@@ -3,7 +3,7 @@ use bytemuck::{Pod, Zeroable};
 use crate::vectors::Vec2f32;
 
 #[repr(C)]
-#[derive(Clone, Debug, Copy, Pod, Zeroable)]
+#[derive(Clone, Debug, Copy, PartialEq, Pod, Zeroable)]
 pub struct Rect {
     position: Vec2f32,
     size: Vec2f32,
@@ -33,4 +33,22 @@ impl Rect {
     pub fn position_mut(&mut self) -> &mut Vec2f32 {
         &mut self.position
     }
+
+    ///Checks if the given point is inside of this rectangle
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.position.x()
+            && y >= self.position.y()
+            && x < self.position.x() + self.size.x()
+            && y < self.position.y() + self.size.y()
+    }
+
+    ///Gets the area shared by this and the `other` rectangle. None if they don't overlap
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.position.x().max(other.position.x());
+        let top = self.position.y().max(other.position.y());
+        let right = (self.position.x() + self.size.x()).min(other.position.x() + other.size.x());
+        let bottom = (self.position.y() + self.size.y()).min(other.position.y() + other.size.y());
+        (right > left && bottom > top)
+            .then(|| Self::from_xywh(left, top, right - left, bottom - top))
+    }
 }
@@ -1,5 +1,7 @@
+use std::ops::{Add, Mul, Sub};
+
 use bytemuck::{Pod, Zeroable};
-#[derive(Debug, Clone, Copy, Zeroable, Pod)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Zeroable, Pod)]
 #[repr(C)]
 pub struct Vec2f32(f32, f32);
 
@@ -20,6 +22,22 @@ impl Vec2f32 {
     pub fn min(&self, rhs: &Self) -> Self {
         Self(self.0.min(rhs.0), self.1.min(rhs.1))
     }
+    pub fn max(&self, rhs: &Self) -> Self {
+        Self(self.0.max(rhs.0), self.1.max(rhs.1))
+    }
+}
+
+impl Add for Vec2f32 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+impl Sub for Vec2f32 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
 }
 impl<T> From<Vec2f32> for (T, T)
 where
@@ -29,3 +47,9 @@ where
         (value.0.into(), value.1.into())
     }
 }
+impl Mul<f32> for Vec2f32 {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
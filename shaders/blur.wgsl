@@ -0,0 +1,48 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+struct Blur {
+    //Distance between the samples, in uv coordinates. Only one axis is blurred per pass
+    direction: vec2<f32>,
+    //The source is only copied when it's 0, which is used for downscaling and upscaling it
+    sigma: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> blur: Blur;
+@group(1) @binding(0)
+var source: texture_2d<f32>;
+@group(1) @binding(1)
+var source_sampler: sampler;
+
+//A triangle which covers the whole target
+@vertex
+fn vertex_main(@builtin(vertex_index) idx: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let uv = vec2<f32>(f32((idx << 1u) & 2u), f32(idx & 2u));
+    out.position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+//Gaussian blur on a single axis, samples further than 3 sigmas are ignored. Wider blurs are done on downscaled copies of the
+//target, so the sigma is never bigger than 4 texels
+@fragment
+fn fragment_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if blur.sigma <= 0.0 {
+        return textureSampleLevel(source, source_sampler, in.uv, 0.0);
+    }
+    let radius = min(i32(ceil(blur.sigma * 3.0)), 12);
+    var sum = vec4<f32>(0.0);
+    var weights = 0.0;
+    for (var i = -radius; i <= radius; i++) {
+        let offset = f32(i);
+        let weight = exp(-(offset * offset) / (2.0 * blur.sigma * blur.sigma));
+        sum += textureSampleLevel(source, source_sampler, in.uv + blur.direction * offset, 0.0) * weight;
+        weights += weight;
+    }
+    return sum / weights;
+}
@@ -0,0 +1,79 @@
+struct VertexInput {
+    @location(0) pos: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    //Position in pixels before being transformed, used for rounding the corners
+    @location(1) local: vec2<f32>,
+};
+
+struct Rect {
+    position: vec2<f32>,
+    size: vec2<f32>
+}
+
+//Affine transform applied to the positions, in pixels
+struct Transform {
+    linear: mat2x2<f32>,
+    translation: vec2<f32>,
+}
+
+//Uniforms shared by every pipeline, written once per frame
+struct Globals {
+    screen_size: vec2<f32>,
+    //Position of the cursor in pixels, from the top left corner of the screen
+    cursor: vec2<f32>,
+    scale_factor: f32,
+    //Seconds since the app started
+    time: f32,
+    frame: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> globals: Globals;
+@group(1) @binding(0)
+var<uniform> rect: Rect;
+//Radius of each corner, clockwise from the top left one
+@group(1) @binding(1)
+var<uniform> radius: vec4<f32>;
+@group(1) @binding(2)
+var<uniform> transform: Transform;
+
+//Converts the given position in pixels, from the top left corner of the screen, into normalized device coordinates
+fn to_ndc(pixel: vec2<f32>) -> vec4<f32> {
+    let moved = transform.linear * pixel + transform.translation;
+    return vec4<f32>(moved.x / globals.screen_size.x * 2.0 - 1.0, 1.0 - moved.y / globals.screen_size.y * 2.0, 0.0, 1.0);
+}
+
+@vertex
+fn vertex_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    //Vertices go from -1 to 1, with y pointing up
+    let pixel = rect.position + (in.pos * vec2<f32>(0.5, -0.5) + 0.5) * rect.size;
+    out.position = to_ndc(pixel);
+    out.local = pixel;
+    out.color = in.color;
+    return out;
+}
+
+//Signed distance from the given pixel to the border of the rounded rect. Negative inside of it
+fn rounded_distance(pixel: vec2<f32>) -> f32 {
+    let half = rect.size * 0.5;
+    let p = pixel - rect.position - half;
+    let side = select(radius.xw, radius.yz, p.x > 0.0);
+    let corner = min(select(side.x, side.y, p.y > 0.0), min(half.x, half.y));
+    let q = abs(p) - half + vec2<f32>(corner);
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - corner;
+}
+
+//Nothing is written on the color target, pixels outside of the rounded rect are discarded so only the ones inside of it change the stencil
+@fragment
+fn fragment_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if rounded_distance(in.local) > 0.0 {
+        discard;
+    }
+    return vec4<f32>(0.0);
+}
@@ -0,0 +1,77 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+struct Composite {
+    screen_size: vec2<f32>,
+    radius: f32,
+    //Whether pixels outside of the rounded rect are discarded
+    masked: f32,
+    rect: vec4<f32>,
+    brightness: f32,
+    contrast: f32,
+    saturation: f32,
+    //Rotation of the hue, in radians
+    hue: f32,
+    grayscale: f32,
+    opacity: f32,
+    _padding: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> params: Composite;
+@group(1) @binding(0)
+var source: texture_2d<f32>;
+@group(1) @binding(1)
+var source_sampler: sampler;
+
+const LUMA: vec3<f32> = vec3<f32>(0.2126, 0.7152, 0.0722);
+
+//A triangle which covers the whole target
+@vertex
+fn vertex_main(@builtin(vertex_index) idx: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let uv = vec2<f32>(f32((idx << 1u) & 2u), f32(idx & 2u));
+    out.position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+//Signed distance from the given pixel to the border of the rounded rect. Negative inside of it
+fn rounded_distance(pixel: vec2<f32>) -> f32 {
+    let half = params.rect.zw * 0.5;
+    let p = pixel - params.rect.xy - half;
+    let corner = min(params.radius, min(half.x, half.y));
+    let q = abs(p) - half + vec2<f32>(corner);
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - corner;
+}
+
+fn hue_rotate(color: vec3<f32>, angle: f32) -> vec3<f32> {
+    let c = cos(angle);
+    let s = sin(angle);
+    let matrix = mat3x3<f32>(
+        vec3<f32>(0.213 + c * 0.787 - s * 0.213, 0.213 - c * 0.213 + s * 0.143, 0.213 - c * 0.213 - s * 0.787),
+        vec3<f32>(0.715 - c * 0.715 - s * 0.715, 0.715 + c * 0.285 + s * 0.140, 0.715 - c * 0.715 + s * 0.715),
+        vec3<f32>(0.072 - c * 0.072 + s * 0.928, 0.072 - c * 0.072 - s * 0.283, 0.072 + c * 0.928 + s * 0.072),
+    );
+    return matrix * color;
+}
+
+//The source is premultiplied by it's alpha, so the filters are applied to the color it had before being blended
+@fragment
+fn fragment_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(source, source_sampler, in.uv);
+    var coverage = 1.0;
+    if params.masked > 0.5 {
+        coverage = clamp(0.5 - rounded_distance(in.position.xy), 0.0, 1.0);
+    }
+    var rgb = color.rgb / max(color.a, 0.0001);
+    rgb = rgb * params.brightness;
+    rgb = (rgb - vec3<f32>(0.5)) * params.contrast + vec3<f32>(0.5);
+    rgb = mix(vec3<f32>(dot(rgb, LUMA)), rgb, params.saturation);
+    rgb = hue_rotate(rgb, params.hue);
+    rgb = mix(rgb, vec3<f32>(dot(rgb, LUMA)), params.grayscale);
+    let alpha = color.a * params.opacity * coverage;
+    return vec4<f32>(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0)) * alpha, alpha);
+}
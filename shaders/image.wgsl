@@ -5,7 +5,8 @@ struct VertexInput {
 
 struct FragmentInput {
     @builtin(position) position: vec4<f32>,
-    @location(0) uv: vec2<f32>
+    //Position in pixels inside of the rect, before being transformed
+    @location(0) local: vec2<f32>
 }
 
 struct Rect {
@@ -13,28 +14,132 @@ struct Rect {
     size: vec2<f32>,
 }
 
+//Affine transform applied to the positions, in pixels
+struct Transform {
+    linear: mat2x2<f32>,
+    translation: vec2<f32>,
+}
+
+//Uniforms shared by every pipeline, written once per frame
+struct Globals {
+    screen_size: vec2<f32>,
+    //Position of the cursor in pixels, from the top left corner of the screen
+    cursor: vec2<f32>,
+    scale_factor: f32,
+    //Seconds since the app started
+    time: f32,
+    frame: u32,
+}
+
 @group(0) @binding(0)
-var<uniform> screen_size: vec2<f32>;
-@group(0) @binding(1)
+var<uniform> globals: Globals;
+@group(1) @binding(0)
 var<uniform> rect: Rect;
+//Whether the colors of the texture are already multiplied by their alpha, as the ones of offscreen targets are
+@group(1) @binding(1)
+var<uniform> premultiplied: f32;
+@group(1) @binding(2)
+var<uniform> transform: Transform;
+//Opacity of the element multiplied with the ones of it's ancestors
+@group(1) @binding(3)
+var<uniform> opacity: f32;
+
+struct Params {
+    //Area the image covers, or one of it's tiles, in pixels. Position on xy and size on zw
+    image: vec4<f32>,
+    //Area of the texture which is shown, in uv coordinates
+    source: vec4<f32>,
+    //Insets of the nine-slice on the source, as fractions of it. Left, top, right, bottom
+    slice_source: vec4<f32>,
+    //Insets of the nine-slice on the screen, in pixels
+    slice_screen: vec4<f32>,
+    //0 clamps, 1 repeats and 2 mirrors
+    tiling: u32,
+    nine_slice: u32,
+}
+@group(1) @binding(4)
+var<uniform> params: Params;
+
+//Converts the given position in pixels, from the top left corner of the screen, into normalized device coordinates
+fn to_ndc(pixel: vec2<f32>) -> vec4<f32> {
+    let moved = transform.linear * pixel + transform.translation;
+    return vec4<f32>(moved.x / globals.screen_size.x * 2.0 - 1.0, 1.0 - moved.y / globals.screen_size.y * 2.0, 0.0, 1.0);
+}
 
 @vertex
 fn vertex_main(in: VertexInput) -> FragmentInput {
     var out:FragmentInput;
-    let ndc_size = rect.size / screen_size;
-    let rect_position = in.position * ndc_size + vec2<f32>(ndc_size.x, -ndc_size.y);
-    let ndc_position = vec2<f32>(rect.position.x / screen_size.x * 2.0 - 1.0, 1.0 - (rect.position.y / screen_size.y) * 2.0);
-    out.position = vec4<f32>(rect_position + ndc_position, 0.0, 1.0);
-    out.uv = in.uv;
+    //Vertices go from -1 to 1, with y pointing up
+    let pixel = rect.position + (in.position * vec2<f32>(0.5, -0.5) + 0.5) * rect.size;
+    out.position = to_ndc(pixel);
+    //Uvs of the vertices go from 0 to 1 along the rect
+    out.local = in.uv * rect.size;
     return out;
 }
 
-@group(1) @binding(0)
+@group(2) @binding(0)
 var texture: texture_2d<f32>;
-@group(1) @binding(1)
+@group(2) @binding(1)
 var sampl: sampler;
 
+//Maps a position `p` along an axis of the rect with the given `size` into the source of a nine-slice, from 0 to 1.
+//Borders keep their size and the center is stretched
+fn slice_axis(p: f32, size: f32, start: f32, end: f32, source_start: f32, source_end: f32) -> f32 {
+    if p < start {
+        return p / start * source_start;
+    }
+    if p > size - end {
+        return 1.0 - (size - p) / end * source_end;
+    }
+    let middle = max(size - start - end, 0.0001);
+    return source_start + (p - start) / middle * (1.0 - source_start - source_end);
+}
+
+//Wraps the given `uv`, which goes beyond 0 and 1 outside of the image, according to the tiling
+fn wrap(uv: vec2<f32>) -> vec2<f32> {
+    switch params.tiling {
+        case 1u: {
+            return fract(uv);
+        }
+        case 2u: {
+            return 1.0 - abs(fract(uv * 0.5) * 2.0 - 1.0);
+        }
+        default: {
+            return clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0));
+        }
+    }
+}
+
 @fragment
 fn fragment_main(in:FragmentInput) -> @location(0) vec4<f32> {
-    return textureSample(texture, sampl, in.uv);
+    var uv: vec2<f32>;
+    if params.nine_slice != 0u {
+        uv = vec2<f32>(
+            slice_axis(in.local.x, rect.size.x, params.slice_screen.x, params.slice_screen.z, params.slice_source.x, params.slice_source.z),
+            slice_axis(in.local.y, rect.size.y, params.slice_screen.y, params.slice_screen.w, params.slice_source.y, params.slice_source.w),
+        );
+    } else {
+        uv = (rect.position + in.local - params.image.xy) / params.image.zw;
+    }
+    //Gradients are taken before wrapping, so the seams between tiles don't pick a smaller mip
+    let scaled = uv * params.source.zw;
+    let dx = dpdx(scaled);
+    let dy = dpdy(scaled);
+    //Sampling is kept half a texel inside of the source, so the texels around a sprite don't bleed into it
+    let texel = 0.5 / vec2<f32>(textureDimensions(texture));
+    let coords = clamp(
+        params.source.xy + wrap(uv) * params.source.zw,
+        params.source.xy + texel,
+        params.source.xy + params.source.zw - texel,
+    );
+    var color = textureSampleGrad(texture, sampl, coords, dx, dy);
+    let outside = any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0));
+    if params.tiling == 0u && outside {
+        color = vec4<f32>(0.0);
+    }
+    //The output is premultiplied, so the opacity scales every channel
+    if premultiplied > 0.5 {
+        return color * opacity;
+    }
+    return vec4<f32>(color.rgb * color.a, color.a) * opacity;
 }
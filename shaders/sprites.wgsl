@@ -0,0 +1,64 @@
+struct VertexInput {
+    @location(0) pos: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) tint: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) tint: vec4<f32>,
+};
+
+//Affine transform applied to the positions, in pixels
+struct Transform {
+    linear: mat2x2<f32>,
+    translation: vec2<f32>,
+}
+
+//Uniforms shared by every pipeline, written once per frame
+struct Globals {
+    screen_size: vec2<f32>,
+    //Position of the cursor in pixels, from the top left corner of the screen
+    cursor: vec2<f32>,
+    scale_factor: f32,
+    //Seconds since the app started
+    time: f32,
+    frame: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> globals: Globals;
+@group(1) @binding(0)
+var<uniform> transform: Transform;
+//Opacity of the element multiplied with the ones of it's ancestors
+@group(1) @binding(1)
+var<uniform> opacity: f32;
+
+@group(2) @binding(0)
+var texture: texture_2d<f32>;
+@group(2) @binding(1)
+var sampl: sampler;
+
+//Converts the given position in pixels, from the top left corner of the screen, into normalized device coordinates
+fn to_ndc(pixel: vec2<f32>) -> vec4<f32> {
+    let moved = transform.linear * pixel + transform.translation;
+    return vec4<f32>(moved.x / globals.screen_size.x * 2.0 - 1.0, 1.0 - moved.y / globals.screen_size.y * 2.0, 0.0, 1.0);
+}
+
+//Positions are given in pixels, and uvs go from 0 to 1 along the texture
+@vertex
+fn vertex_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = to_ndc(in.pos);
+    out.uv = in.uv;
+    out.tint = in.tint;
+    return out;
+}
+
+@fragment
+fn fragment_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(texture, sampl, in.uv) * in.tint;
+    //The output is premultiplied, so the opacity scales every channel
+    return vec4<f32>(color.rgb * color.a, color.a) * opacity;
+}
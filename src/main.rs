@@ -2,6 +2,7 @@ use hyst_engine::{
     HystHandler, HystWindow,
     core::RenderingCore,
     shaders::events::ShaderEvent,
+    text::TextStyle,
     ui::{
         HystUi,
         pulse::Pulse,
@@ -9,7 +10,7 @@ use hyst_engine::{
     },
     winit::{event::WindowEvent, window::Window},
 };
-use hyst_math::vectors::{Rgba, Vec2f32, Vec4f32};
+use hyst_math::vectors::{Rgba, Vec4f32};
 pub struct Handler {
     window: Window,
     ui: HystUi,
@@ -44,19 +45,19 @@ impl HystHandler for Handler {
         );
         let text = ui.create_pulse(String::from("Jorge"));
         ui.create_text(hyst_engine::ui::HystTextOptions {
-            content: text.clone(),
-            position: Vec2f32::new(80.0, 80.0),
+            content: text.clone().into(),
             style: "suamae".into(),
             font_size: 25.0,
             color: ui.create_pulse(Vec4f32::new(1.0, 0.0, 0.0, 1.0)),
+            text_style: TextStyle::default(),
         })
         .unwrap();
         ui.create_text(hyst_engine::ui::HystTextOptions {
-            content: text.clone(),
-            position: Vec2f32::new(40.0, 0.0),
+            content: text.clone().into(),
             style: "suamae".into(),
             font_size: 12.0,
             color: ui.create_pulse(Vec4f32::new(0.0, 1.0, 0.0, 0.5)),
+            text_style: TextStyle::default(),
         })
         .unwrap();
 
@@ -65,8 +66,9 @@ impl HystHandler for Handler {
     fn on_window_event(
         &mut self,
         event: hyst_engine::winit::event::WindowEvent,
-        id: hyst_engine::winit::window::WindowId,
+        _: hyst_engine::winit::window::WindowId,
     ) {
+        let handled = self.ui.handle_event(&event);
         match event {
             WindowEvent::RedrawRequested => {
                 self.ui.draw();
@@ -74,16 +76,12 @@ impl HystHandler for Handler {
             WindowEvent::Resized(size) => {
                 size.on_executed(&mut self.ui);
             }
-            WindowEvent::KeyboardInput {
-                device_id,
-                event,
-                is_synthetic,
-            } => {
+            WindowEvent::KeyboardInput { .. } => {
                 self.text.mutate(|mut t| t.push('e'));
             }
             _ => {}
         }
-        if self.ui.check_for_updates() {
+        if self.ui.check_for_updates() || handled {
             self.window.request_redraw();
         };
     }